@@ -0,0 +1,3 @@
+ALTER TABLE cart_tally ADD deposit INTEGER NOT NULL default 0;
+ALTER TABLE cart_tally ADD delivery_fee INTEGER NOT NULL default 0;
+ALTER TABLE cart_tally ADD service_fee INTEGER NOT NULL default 0;
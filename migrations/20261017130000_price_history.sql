@@ -0,0 +1,18 @@
+-- Every observed price of a provider product, used to tell real deals from fake ones.
+CREATE TABLE IF NOT EXISTS price_history
+(
+    id              INTEGER PRIMARY KEY NOT NULL,
+    provider_id     INTEGER             NOT NULL,
+    -- Provider specific product Id
+    product_id      TEXT                NOT NULL,
+    display_price   INTEGER             NOT NULL,
+    original_price  INTEGER             NOT NULL,
+    unit_price      INTEGER,
+    unit_price_unit TEXT,
+    sale_label      TEXT,
+    observed_at     TIMESTAMP           NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (provider_id) REFERENCES providers (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS price_history_product_idx ON price_history (provider_id, product_id, observed_at);
@@ -0,0 +1,41 @@
+-- Products or aggregate ingredients a user wants to be alerted about.
+CREATE TABLE IF NOT EXISTS watchlist
+(
+    id           INTEGER PRIMARY KEY NOT NULL,
+    user_id      INTEGER             NOT NULL,
+    -- Either a provider product (provider_id + product_id), or an aggregate ingredient is watched.
+    provider_id  INTEGER,
+    product_id   TEXT,
+    aggregate_id INTEGER,
+    -- Alert as soon as the price drops to, or below, this price.
+    target_price INTEGER,
+    -- Alert as soon as any sale applies.
+    any_sale     BOOLEAN             NOT NULL DEFAULT FALSE,
+    created_at   TIMESTAMP           NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CHECK ((provider_id IS NOT NULL AND product_id IS NOT NULL) <> (aggregate_id IS NOT NULL)),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (provider_id) REFERENCES providers (id) ON DELETE CASCADE,
+    FOREIGN KEY (aggregate_id) REFERENCES agg_ingredients (id) ON DELETE CASCADE
+);
+
+-- Every time the condition of a watched item held.
+CREATE TABLE IF NOT EXISTS watchlist_alerts
+(
+    id             INTEGER PRIMARY KEY NOT NULL,
+    watch_id       INTEGER             NOT NULL,
+    provider_id    INTEGER             NOT NULL,
+    -- Provider specific product Id, for aggregate ingredients this is the constituent product which matched.
+    product_id     TEXT                NOT NULL,
+    display_price  INTEGER             NOT NULL,
+    original_price INTEGER             NOT NULL,
+    sale_label     TEXT,
+    seen           BOOLEAN             NOT NULL DEFAULT FALSE,
+    created_at     TIMESTAMP           NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (watch_id) REFERENCES watchlist (id) ON DELETE CASCADE,
+    FOREIGN KEY (provider_id) REFERENCES providers (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS watchlist_user_idx ON watchlist (user_id);
+CREATE INDEX IF NOT EXISTS watchlist_alerts_watch_idx ON watchlist_alerts (watch_id, created_at);
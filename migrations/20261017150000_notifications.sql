@@ -0,0 +1,33 @@
+-- Channels through which a user wants to receive notifications.
+CREATE TABLE IF NOT EXISTS notification_channels
+(
+    id         INTEGER PRIMARY KEY NOT NULL,
+    user_id    INTEGER             NOT NULL,
+    name       TEXT                NOT NULL,
+    -- JSON serialised channel configuration (webhook, smtp, push).
+    config     TEXT                NOT NULL,
+    enabled    BOOLEAN             NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP           NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+-- Notifications waiting to be delivered to a particular channel.
+-- Rows are removed once delivered, failed deliveries are retried until the maximum amount of attempts is reached.
+CREATE TABLE IF NOT EXISTS notification_queue
+(
+    id              INTEGER PRIMARY KEY NOT NULL,
+    channel_id      INTEGER             NOT NULL,
+    kind            TEXT                NOT NULL,
+    title           TEXT                NOT NULL,
+    body            TEXT                NOT NULL,
+    attempts        INTEGER             NOT NULL DEFAULT 0,
+    last_error      TEXT,
+    next_attempt_at TIMESTAMP           NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_at      TIMESTAMP           NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (channel_id) REFERENCES notification_channels (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS notification_channels_user_idx ON notification_channels (user_id);
+CREATE INDEX IF NOT EXISTS notification_queue_next_attempt_idx ON notification_queue (next_attempt_at);
@@ -0,0 +1,17 @@
+-- Provider accounts linked by a user, used for cart operations on that user's behalf.
+CREATE TABLE IF NOT EXISTS provider_accounts
+(
+    id                 INTEGER PRIMARY KEY NOT NULL,
+    user_id            INTEGER             NOT NULL,
+    provider_id        INTEGER             NOT NULL,
+    username           TEXT                NOT NULL,
+    -- AES-256-GCM encrypted password, see `accounts::crypto`.
+    encrypted_password BLOB                NOT NULL,
+    -- AES-256-GCM encrypted (JSON) session of the account, present once it has logged in.
+    encrypted_session  BLOB,
+    created_at         TIMESTAMP           NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (provider_id) REFERENCES providers (id) ON DELETE CASCADE,
+    UNIQUE (user_id, provider_id)
+);
@@ -0,0 +1,14 @@
+-- Orders placed outside of Wgg which were imported as completed carts, used to never import an order twice.
+CREATE TABLE IF NOT EXISTS imported_orders
+(
+    cart_id     INTEGER PRIMARY KEY NOT NULL,
+    provider_id INTEGER             NOT NULL,
+    -- The id of the order at the provider.
+    external_id TEXT                NOT NULL,
+    imported_at TIMESTAMP           NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (cart_id) REFERENCES cart (id) ON DELETE CASCADE,
+    FOREIGN KEY (provider_id) REFERENCES providers (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS imported_orders_external_idx ON imported_orders (provider_id, external_id);
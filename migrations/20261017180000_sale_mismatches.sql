@@ -0,0 +1,23 @@
+-- Sales for which our own tally disagreed with the tally of the provider, see the cart reconciliation report.
+CREATE TABLE IF NOT EXISTS sale_mismatches
+(
+    id                INTEGER PRIMARY KEY NOT NULL,
+    cart_id           INTEGER             NOT NULL,
+    provider_id       INTEGER             NOT NULL,
+    -- The sublist id of the sale at the provider.
+    sale_id           TEXT                NOT NULL,
+    sale_label        TEXT                NOT NULL,
+    -- The kind of `SaleType` the label was parsed as, NULL if it couldn't be parsed.
+    sale_type         TEXT,
+    expected_applied  BOOLEAN             NOT NULL,
+    -- Columns prefixed with `actual_` are NULL if the provider didn't report them.
+    actual_applied    BOOLEAN,
+    expected_discount INTEGER             NOT NULL,
+    actual_discount   INTEGER,
+    created_at        TIMESTAMP           NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (cart_id) REFERENCES cart (id) ON DELETE CASCADE,
+    FOREIGN KEY (provider_id) REFERENCES providers (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS sale_mismatches_cart_idx ON sale_mismatches (cart_id, provider_id);
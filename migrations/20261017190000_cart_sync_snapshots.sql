@@ -0,0 +1,13 @@
+-- The quantities both carts agreed on after the last sync of a cart with the remote cart of a provider.
+-- Used to tell products which were removed from the cart since apart from products which were never synced.
+CREATE TABLE IF NOT EXISTS cart_sync_snapshots
+(
+    cart_id          INTEGER NOT NULL,
+    provider_id      INTEGER NOT NULL,
+    provider_product TEXT    NOT NULL,
+    quantity         INTEGER NOT NULL,
+
+    PRIMARY KEY (cart_id, provider_id, provider_product),
+    FOREIGN KEY (cart_id) REFERENCES cart (id) ON DELETE CASCADE,
+    FOREIGN KEY (provider_id) REFERENCES providers (id) ON DELETE CASCADE
+);
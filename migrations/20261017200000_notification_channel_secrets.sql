@@ -0,0 +1,4 @@
+-- The secrets of a notification channel (passwords, tokens, and headers), encrypted with the `credentials_secret_key`.
+-- Channels created before this column existed keep their secrets in `config` until they're encrypted on startup.
+ALTER TABLE notification_channels
+    ADD COLUMN encrypted_secrets BLOB;
@@ -0,0 +1,129 @@
+use crate::ids::{BonusSegmentId, ProductId};
+use crate::models::{
+    BonusGroup, BonusMetadata, BonusSection, ProductDetailResponse, PromotionType, SearchResponse, SortOn,
+    SuggestionsResponse,
+};
+use crate::{ApiError, Result};
+use crate::{Config, Query};
+use anyhow::anyhow;
+use chrono::NaiveDate;
+use reqwest::{Response, StatusCode};
+
+/// Contains all routes which only require an anonymous token for the `Albert Heijn` API.
+#[async_trait::async_trait]
+pub trait BaseApi {
+    fn get_config(&self) -> &Config;
+
+    fn get_http(&self) -> &reqwest::Client;
+
+    /// Return a valid access token, which is either anonymous or associated with a user.
+    ///
+    /// Every route of the AH API requires *some* access token.
+    async fn access_token(&self) -> Result<String>;
+
+    #[doc(hidden)]
+    #[inline]
+    async fn endpoint_get(&self, url_suffix: &str, payload: &Query<'_, '_>) -> Result<Response> {
+        let url = self.get_config().get_full_url(url_suffix);
+        let token = self.access_token().await?;
+
+        let response = self
+            .get_http()
+            .get(url)
+            .bearer_auth(token)
+            .header("x-application", "AHWEBSHOP")
+            .query(payload)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(response),
+            StatusCode::NOT_FOUND => Err(ApiError::NotFound(url_suffix.to_string())),
+            _ => {
+                tracing::warn!(status = %response.status(), ?response, "AH API Error occurred");
+                Err(anyhow!("Error occurred: {}", response.status()).into())
+            }
+        }
+    }
+
+    /// Search for the provided product.
+    ///
+    /// # Arguments
+    /// * `page` - The zero-indexed page to retrieve.
+    /// * `size` - The amount of products per page, AH caps this at `1000`.
+    async fn search(&self, query: &str, page: Option<u32>, size: Option<u32>) -> Result<SearchResponse> {
+        let page = page.map(|s| s.to_string());
+        let size = size.map(|s| s.to_string());
+        let sort_on = format!("{:?}", SortOn::Relevance).to_uppercase();
+        let query = crate::utils::build_map([
+            ("query", query.into()),
+            ("page", page.as_deref()),
+            ("size", size.as_deref()),
+            ("sortOn", Some(sort_on.as_str())),
+        ]);
+        let response = self.endpoint_get("/mobile-services/product/search/v2", &query).await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve search term suggestions for the provided (partial) `query`.
+    async fn autocomplete(&self, query: &str, amount: Option<u32>) -> Result<SuggestionsResponse> {
+        let amount = amount.map(|s| s.to_string());
+        let query = crate::utils::build_map([("query", query.into()), ("amount", amount.as_deref())]);
+        let response = self
+            .endpoint_get("/mobile-services/product/search/v1/suggestions", &query)
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve the full details of a product.
+    async fn product(&self, product_id: &ProductId) -> Result<ProductDetailResponse> {
+        let response = self
+            .endpoint_get(
+                &format!("/mobile-services/product/detail/v4/fir/{product_id}"),
+                &Default::default(),
+            )
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve the bonus periods (usually the current and next week) for which bonus information is available.
+    async fn bonus_metadata(&self) -> Result<BonusMetadata> {
+        let response = self
+            .endpoint_get("/mobile-services/bonuspage/v1/metadata", &Default::default())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve all bonus groups and bonus products which are valid on the given `date`.
+    ///
+    /// Note that bonus groups in the response only contain a subset of their products, see [BaseApi::bonus_group].
+    async fn bonus_section(&self, date: NaiveDate, promotion_type: PromotionType) -> Result<BonusSection> {
+        let date = date.format("%Y-%m-%d").to_string();
+        let promotion_type = format!("{promotion_type:?}").to_uppercase();
+        let query = crate::utils::build_map([
+            ("application", Some("AHWEBSHOP")),
+            ("date", Some(date.as_str())),
+            ("promotionType", Some(promotion_type.as_str())),
+        ]);
+        let response = self
+            .endpoint_get("/mobile-services/bonuspage/v2/section", &query)
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve the full bonus group, including all its products.
+    async fn bonus_group(&self, segment_id: &BonusSegmentId, date: NaiveDate) -> Result<BonusGroup> {
+        let date = date.format("%Y-%m-%d").to_string();
+        let query = crate::utils::build_map([("segmentId", Some(segment_id.as_ref())), ("date", Some(date.as_str()))]);
+        let response = self
+            .endpoint_get("/mobile-services/bonuspage/v1/segment", &query)
+            .await?;
+
+        Ok(response.json().await?)
+    }
+}
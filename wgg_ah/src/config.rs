@@ -0,0 +1,49 @@
+use reqwest::Url;
+
+pub struct Config {
+    pub(crate) url: Url,
+    pub(crate) user_agent: String,
+    pub(crate) client_id: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            url: "https://api.ah.nl".parse().expect("Default URL Incorrect"),
+            user_agent: "Appie/8.22.3 (unknown Android_SDK_built_for_x86_64; Android 12)".to_string(),
+            client_id: "appie".to_string(),
+        }
+    }
+}
+
+impl Config {
+    /// A [Config] instance for configuring the [crate::BaseAhApi].
+    ///
+    /// # Arguments
+    /// * `client_id` - The client used for requesting anonymous tokens, the mobile app uses `appie`.
+    pub fn new(client_id: impl Into<String>) -> Self {
+        Config {
+            client_id: client_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the API url.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Returns the full url for the API and the provided suffix.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use wgg_ah::Config;
+    /// let config = Config::default();
+    ///
+    /// assert_eq!(config.get_full_url("/mobile-services/product/search/v2"), "https://api.ah.nl/mobile-services/product/search/v2")
+    /// ```
+    pub fn get_full_url(&self, suffix: &str) -> String {
+        format!("{}{}", self.url.as_str().trim_end_matches('/'), suffix)
+    }
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+pub trait Id: Sized {
+    fn id(&self) -> &str;
+    fn from_id(id: impl Into<String>) -> anyhow::Result<Self>;
+}
+
+/// The `webshopId` of a product.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Hash)]
+#[serde(transparent)]
+pub struct ProductId(String);
+
+/// The identifier of a bonus group, also known as a `segmentId`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Hash)]
+#[serde(transparent)]
+pub struct BonusSegmentId(String);
+
+/// AH is inconsistent in whether it sends its identifiers as numbers or as strings, we accept both.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(u64),
+    String(String),
+}
+
+impl From<NumberOrString> for String {
+    fn from(value: NumberOrString) -> Self {
+        match value {
+            NumberOrString::Number(num) => num.to_string(),
+            NumberOrString::String(val) => val,
+        }
+    }
+}
+
+macro_rules! impl_types {
+    ($($id:ident),+) => {
+        $(
+            // Dirty quick impl for now, can do actual error handling in the future
+            impl Id for $id {
+                fn id(&self) -> &str {
+                    &self.0
+                }
+
+                fn from_id(id: impl Into<String>) -> anyhow::Result<Self> {
+                    Ok(Self(id.into()))
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $id {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    Ok(Self(NumberOrString::deserialize(deserializer)?.into()))
+                }
+            }
+
+            impl AsRef<str> for $id {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl std::borrow::Borrow<str> for $id {
+                fn borrow(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl std::fmt::Display for $id {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    write!(f, "{}", self.0)
+                }
+            }
+
+            impl std::str::FromStr for $id {
+                type Err = anyhow::Error;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    Self::from_id(s)
+                }
+            }
+
+            impl PartialEq<&str> for $id {
+                fn eq(&self, other: &&str) -> bool {
+                    self.0.eq(other)
+                }
+            }
+
+            impl From<$id> for String {
+                fn from(i: $id) -> Self {
+                    i.0
+                }
+            }
+        )+
+    };
+}
+
+impl_types!(ProductId, BonusSegmentId);
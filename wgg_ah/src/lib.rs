@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::models::{AnonymousTokenRequest, TokenResponse};
+use std::time::Duration;
+
+pub use crate::{base_client::BaseApi, config::Config, error::ApiError};
+
+mod base_client;
+mod config;
+mod error;
+pub mod ids;
+pub mod models;
+mod utils;
+
+pub type Result<T> = std::result::Result<T, ApiError>;
+type Query<'key, 'value> = HashMap<&'key str, &'value str>;
+
+/// The interface to the `Albert Heijn` API without authenticated routes.
+///
+/// Even unauthenticated routes require an (anonymous) access token, which is automatically requested and refreshed.
+pub struct BaseAhApi {
+    config: Config,
+    client: reqwest::Client,
+    token: RwLock<Option<AccessToken>>,
+}
+
+#[derive(Clone)]
+struct AccessToken {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl BaseAhApi {
+    /// Create a new unauthenticated interface to the AH API.
+    pub fn new(config: Config) -> Self {
+        Self {
+            client: get_reqwest_client(&config.user_agent).unwrap(),
+            config,
+            token: RwLock::new(None),
+        }
+    }
+
+    /// Request a new anonymous access token from the AH API.
+    async fn anonymous_token(&self) -> Result<TokenResponse> {
+        let request = AnonymousTokenRequest {
+            client_id: &self.config.client_id,
+        };
+
+        let response = self
+            .client
+            .post(self.config.get_full_url("/mobile-auth/v1/auth/token/anonymous"))
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::LoginFailed(format!(
+                "Status: {} - Body: {}",
+                response.status(),
+                response.text().await?
+            )));
+        }
+
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait::async_trait]
+impl BaseApi for BaseAhApi {
+    fn get_config(&self) -> &Config {
+        &self.config
+    }
+
+    fn get_http(&self) -> &Client {
+        &self.client
+    }
+
+    async fn access_token(&self) -> Result<String> {
+        // Refresh a little before the actual expiry to avoid racing the AH back-end.
+        let refresh_margin = chrono::Duration::seconds(60);
+        let current = self.token.read().unwrap().clone();
+
+        if let Some(token) = current.filter(|token| token.expires_at - refresh_margin > Utc::now()) {
+            return Ok(token.token);
+        }
+
+        let response = self.anonymous_token().await?;
+        let new_token = AccessToken {
+            token: response.access_token,
+            expires_at: Utc::now() + chrono::Duration::seconds(response.expires_in),
+        };
+
+        *self.token.write().unwrap() = Some(new_token.clone());
+
+        Ok(new_token.token)
+    }
+}
+
+fn get_reqwest_client(user_agent: &str) -> anyhow::Result<reqwest::Client> {
+    Ok(reqwest::ClientBuilder::default()
+        .timeout(Duration::from_secs(10))
+        .tcp_keepalive(Duration::from_secs(20))
+        // .proxy(Proxy::https("http://localhost:8080").unwrap())
+        .gzip(true)
+        .user_agent(user_agent)
+        .build()?)
+}
@@ -0,0 +1,271 @@
+use crate::ids::{BonusSegmentId, ProductId};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+// ** Authentication **
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AnonymousTokenRequest<'a> {
+    pub client_id: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    /// Validity of the `access_token` in seconds.
+    pub expires_in: i64,
+}
+
+// ** Search **
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResponse {
+    #[serde(default)]
+    pub products: Vec<PartialProduct>,
+    pub page: Page,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page {
+    /// The amount of items requested per page.
+    pub size: u32,
+    pub total_elements: u32,
+    pub total_pages: u32,
+    /// The current page, zero-indexed.
+    pub number: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SortOn {
+    Relevance,
+    Price,
+}
+
+// ** Autocomplete **
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestionsResponse {
+    #[serde(default)]
+    pub suggestions: Vec<Suggestion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    pub value: String,
+}
+
+// ** Partial Products **
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialProduct {
+    pub webshop_id: ProductId,
+    pub title: String,
+    pub brand: Option<String>,
+    /// Contains the quantity of product, aka `500 g`, `6 stuks`, or `ca. 1 kg`.
+    pub sales_unit_size: Option<String>,
+    /// Contains text such as `prijs per kg €4.38`
+    pub unit_price_description: Option<String>,
+    #[serde(default)]
+    pub images: Vec<ProductImage>,
+    /// The price in euros (not cents!) after applying a potential bonus.
+    ///
+    /// Not present if the product is not on sale, in which case [Self::price_before_bonus] is leading.
+    pub current_price: Option<f64>,
+    /// The price in euros (not cents!) without the bonus applied.
+    pub price_before_bonus: Option<f64>,
+    #[serde(default)]
+    pub is_bonus: bool,
+    /// The sale label, such as `2e halve prijs` or `1 + 1 gratis`.
+    pub bonus_mechanism: Option<String>,
+    pub bonus_start_date: Option<NaiveDate>,
+    pub bonus_end_date: Option<NaiveDate>,
+    pub bonus_segment_id: Option<BonusSegmentId>,
+    #[serde(default)]
+    pub discount_labels: Vec<DiscountLabel>,
+    pub order_availability_status: Option<OrderAvailabilityStatus>,
+    #[serde(default)]
+    pub available_online: bool,
+    #[serde(default)]
+    pub is_orderable: bool,
+    pub main_category: Option<String>,
+    pub sub_category: Option<String>,
+    /// Contains tid-bits like `Dagvers` or `Biologisch`
+    #[serde(default)]
+    pub property_icons: Vec<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductImage {
+    pub width: u32,
+    pub height: u32,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscountLabel {
+    /// Machine readable form of the label, f.e. `DISCOUNT_X_FOR_Y`
+    pub code: String,
+    pub default_description: String,
+    pub count: Option<u32>,
+    pub free_count: Option<u32>,
+    pub percentage: Option<f64>,
+    pub price: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderAvailabilityStatus {
+    InAssortment,
+    OutOfStock,
+    NotAvailable,
+    #[serde(other)]
+    Other,
+}
+
+// ** Full Product **
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductDetailResponse {
+    pub product_id: ProductId,
+    pub product_card: PartialProduct,
+    pub trade_item: Option<TradeItem>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeItem {
+    pub description: Option<TradeItemDescription>,
+    pub ingredients: Option<TradeItemIngredients>,
+    #[serde(default)]
+    pub nutritions: Vec<NutritionTable>,
+    pub usage: Option<TradeItemUsage>,
+    pub origin: Option<TradeItemOrigin>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeItemDescription {
+    #[serde(default)]
+    pub descriptions: Vec<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeItemIngredients {
+    /// The ingredient blob, such as `Tarwebloem, water, gist, zout`
+    pub statement: Option<String>,
+    pub allergens: Option<Allergens>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Allergens {
+    #[serde(default)]
+    pub contains: Vec<String>,
+    #[serde(default)]
+    pub may_contain: Vec<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NutritionTable {
+    /// Usually `per 100 g`
+    pub basis_quantity: Option<String>,
+    #[serde(default)]
+    pub nutrients: Vec<Nutrient>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Nutrient {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub sub_nutrients: Vec<Nutrient>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeItemUsage {
+    pub preparation_instructions: Option<String>,
+    pub storage_instructions: Option<String>,
+    pub safety_warning: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeItemOrigin {
+    pub country_of_origin: Option<String>,
+}
+
+// ** Bonus **
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BonusMetadata {
+    pub periods: Vec<BonusPeriod>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BonusPeriod {
+    pub bonus_start_date: NaiveDate,
+    pub bonus_end_date: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PromotionType {
+    National,
+    Personal,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BonusSection {
+    pub section_description: Option<String>,
+    #[serde(default)]
+    pub bonus_group_or_products: Vec<BonusGroupOrProduct>,
+}
+
+/// AH mixes full bonus groups (`2 verschillende voor €5`) and single products in the same bonus section.
+///
+/// Exactly one of the two fields is expected to be present.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BonusGroupOrProduct {
+    pub bonus_group: Option<BonusGroup>,
+    pub product: Option<PartialProduct>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BonusGroup {
+    pub id: BonusSegmentId,
+    pub segment_description: String,
+    /// The sale label, such as `2e halve prijs` or `1 + 1 gratis`.
+    pub discount_description: Option<String>,
+    pub category: Option<String>,
+    pub bonus_start_date: NaiveDate,
+    pub bonus_end_date: NaiveDate,
+    #[serde(default)]
+    pub images: Vec<ProductImage>,
+    #[serde(default)]
+    pub discount_labels: Vec<DiscountLabel>,
+    /// Only the first few products are embedded when queried through [crate::BaseApi::bonus_section].
+    ///
+    /// See [crate::BaseApi::bonus_group] for the full list.
+    #[serde(default)]
+    pub products: Vec<PartialProduct>,
+}
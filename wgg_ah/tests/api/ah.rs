@@ -0,0 +1,49 @@
+use crate::common::base_ah_api;
+use crate::conditional_test;
+use wgg_ah::models::PromotionType;
+use wgg_ah::BaseApi;
+
+#[tokio::test]
+pub async fn test_search() {
+    let api = conditional_test!(base_ah_api);
+
+    let result = api.search("croissant", None, None).await.unwrap();
+
+    assert!(result.page.total_elements > 0)
+}
+
+#[tokio::test]
+pub async fn test_autocomplete() {
+    let api = conditional_test!(base_ah_api);
+
+    let result = api.autocomplete("eier", None).await.unwrap();
+
+    assert!(!result.suggestions.is_empty())
+}
+
+#[tokio::test]
+pub async fn test_product() {
+    let api = conditional_test!(base_ah_api);
+
+    let search = api.search("melk", None, Some(1)).await.unwrap();
+    let product = &search.products[0];
+
+    let result = api.product(&product.webshop_id).await.unwrap();
+
+    assert_eq!(result.product_card.webshop_id, product.webshop_id)
+}
+
+#[tokio::test]
+pub async fn test_bonus_section() {
+    let api = conditional_test!(base_ah_api);
+
+    let metadata = api.bonus_metadata().await.unwrap();
+    let current = metadata.periods.first().unwrap();
+
+    let result = api
+        .bonus_section(current.bonus_start_date, PromotionType::National)
+        .await
+        .unwrap();
+
+    assert!(!result.bonus_group_or_products.is_empty())
+}
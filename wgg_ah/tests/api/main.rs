@@ -0,0 +1,4 @@
+#![allow(unused)]
+
+mod ah;
+mod common;
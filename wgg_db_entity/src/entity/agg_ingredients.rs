@@ -46,6 +46,7 @@ pub enum Relation {
     AggIngredientsLinks,
     CartContentsAggregate,
     Users,
+    Watchlist,
 }
 
 impl ColumnTrait for Column {
@@ -70,6 +71,7 @@ impl RelationTrait for Relation {
                 .from(Column::CreatedBy)
                 .to(super::users::Column::Id)
                 .into(),
+            Self::Watchlist => Entity::has_many(super::watchlist::Entity).into(),
         }
     }
 }
@@ -92,4 +94,10 @@ impl Related<super::users::Entity> for Entity {
     }
 }
 
+impl Related<super::watchlist::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Watchlist.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -44,8 +44,11 @@ pub enum Relation {
     CartContentsAggregate,
     CartContentsNotes,
     CartContentsProvider,
+    CartSyncSnapshots,
     CartTally,
+    ImportedOrders,
     Providers,
+    SaleMismatches,
     Users,
 }
 
@@ -67,11 +70,14 @@ impl RelationTrait for Relation {
             Self::CartContentsAggregate => Entity::has_many(super::cart_contents_aggregate::Entity).into(),
             Self::CartContentsNotes => Entity::has_many(super::cart_contents_notes::Entity).into(),
             Self::CartContentsProvider => Entity::has_many(super::cart_contents_provider::Entity).into(),
+            Self::CartSyncSnapshots => Entity::has_many(super::cart_sync_snapshots::Entity).into(),
             Self::CartTally => Entity::has_many(super::cart_tally::Entity).into(),
+            Self::ImportedOrders => Entity::has_many(super::imported_orders::Entity).into(),
             Self::Providers => Entity::belongs_to(super::providers::Entity)
                 .from(Column::PickedId)
                 .to(super::providers::Column::Id)
                 .into(),
+            Self::SaleMismatches => Entity::has_many(super::sale_mismatches::Entity).into(),
             Self::Users => Entity::belongs_to(super::users::Entity)
                 .from(Column::UserId)
                 .to(super::users::Column::Id)
@@ -98,12 +104,30 @@ impl Related<super::cart_contents_provider::Entity> for Entity {
     }
 }
 
+impl Related<super::cart_sync_snapshots::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CartSyncSnapshots.def()
+    }
+}
+
 impl Related<super::cart_tally::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::CartTally.def()
     }
 }
 
+impl Related<super::imported_orders::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ImportedOrders.def()
+    }
+}
+
+impl Related<super::sale_mismatches::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SaleMismatches.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
@@ -17,6 +17,9 @@ pub struct Model {
     pub provider_id: i32,
     pub price_cents: i32,
     pub discount: i32,
+    pub deposit: i32,
+    pub delivery_fee: i32,
+    pub service_fee: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -25,6 +28,9 @@ pub enum Column {
     ProviderId,
     PriceCents,
     Discount,
+    Deposit,
+    DeliveryFee,
+    ServiceFee,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -54,6 +60,9 @@ impl ColumnTrait for Column {
             Self::ProviderId => ColumnType::Integer.def(),
             Self::PriceCents => ColumnType::Integer.def(),
             Self::Discount => ColumnType::Integer.def(),
+            Self::Deposit => ColumnType::Integer.def(),
+            Self::DeliveryFee => ColumnType::Integer.def(),
+            Self::ServiceFee => ColumnType::Integer.def(),
         }
     }
 }
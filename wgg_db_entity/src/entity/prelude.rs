@@ -6,7 +6,16 @@ pub use super::cart::Entity as Cart;
 pub use super::cart_contents_aggregate::Entity as CartContentsAggregate;
 pub use super::cart_contents_notes::Entity as CartContentsNotes;
 pub use super::cart_contents_provider::Entity as CartContentsProvider;
+pub use super::cart_sync_snapshots::Entity as CartSyncSnapshots;
 pub use super::cart_tally::Entity as CartTally;
+pub use super::imported_orders::Entity as ImportedOrders;
+pub use super::notification_channels::Entity as NotificationChannels;
+pub use super::notification_queue::Entity as NotificationQueue;
+pub use super::price_history::Entity as PriceHistory;
+pub use super::provider_accounts::Entity as ProviderAccounts;
 pub use super::providers::Entity as Providers;
+pub use super::sale_mismatches::Entity as SaleMismatches;
 pub use super::users::Entity as Users;
 pub use super::users_tokens::Entity as UsersTokens;
+pub use super::watchlist::Entity as Watchlist;
+pub use super::watchlist_alerts::Entity as WatchlistAlerts;
@@ -40,7 +40,14 @@ pub enum Relation {
     AggIngredientsLinks,
     Cart,
     CartContentsProvider,
+    CartSyncSnapshots,
     CartTally,
+    ImportedOrders,
+    PriceHistory,
+    ProviderAccounts,
+    SaleMismatches,
+    Watchlist,
+    WatchlistAlerts,
 }
 
 impl ColumnTrait for Column {
@@ -59,7 +66,14 @@ impl RelationTrait for Relation {
             Self::AggIngredientsLinks => Entity::has_many(super::agg_ingredients_links::Entity).into(),
             Self::Cart => Entity::has_many(super::cart::Entity).into(),
             Self::CartContentsProvider => Entity::has_many(super::cart_contents_provider::Entity).into(),
+            Self::CartSyncSnapshots => Entity::has_many(super::cart_sync_snapshots::Entity).into(),
             Self::CartTally => Entity::has_many(super::cart_tally::Entity).into(),
+            Self::ImportedOrders => Entity::has_many(super::imported_orders::Entity).into(),
+            Self::PriceHistory => Entity::has_many(super::price_history::Entity).into(),
+            Self::ProviderAccounts => Entity::has_many(super::provider_accounts::Entity).into(),
+            Self::SaleMismatches => Entity::has_many(super::sale_mismatches::Entity).into(),
+            Self::Watchlist => Entity::has_many(super::watchlist::Entity).into(),
+            Self::WatchlistAlerts => Entity::has_many(super::watchlist_alerts::Entity).into(),
         }
     }
 }
@@ -76,12 +90,54 @@ impl Related<super::cart_contents_provider::Entity> for Entity {
     }
 }
 
+impl Related<super::cart_sync_snapshots::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CartSyncSnapshots.def()
+    }
+}
+
 impl Related<super::cart_tally::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::CartTally.def()
     }
 }
 
+impl Related<super::imported_orders::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ImportedOrders.def()
+    }
+}
+
+impl Related<super::price_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PriceHistory.def()
+    }
+}
+
+impl Related<super::provider_accounts::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProviderAccounts.def()
+    }
+}
+
+impl Related<super::sale_mismatches::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SaleMismatches.def()
+    }
+}
+
+impl Related<super::watchlist::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Watchlist.def()
+    }
+}
+
+impl Related<super::watchlist_alerts::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WatchlistAlerts.def()
+    }
+}
+
 impl Related<super::cart::Entity> for Entity {
     fn to() -> RelationDef {
         super::cart_tally::Relation::Cart.def()
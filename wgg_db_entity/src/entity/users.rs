@@ -47,7 +47,10 @@ impl PrimaryKeyTrait for PrimaryKey {
 pub enum Relation {
     AggIngredients,
     Cart,
+    NotificationChannels,
+    ProviderAccounts,
     UsersTokens,
+    Watchlist,
 }
 
 impl ColumnTrait for Column {
@@ -69,7 +72,10 @@ impl RelationTrait for Relation {
         match self {
             Self::AggIngredients => Entity::has_many(super::agg_ingredients::Entity).into(),
             Self::Cart => Entity::has_many(super::cart::Entity).into(),
+            Self::NotificationChannels => Entity::has_many(super::notification_channels::Entity).into(),
+            Self::ProviderAccounts => Entity::has_many(super::provider_accounts::Entity).into(),
             Self::UsersTokens => Entity::has_many(super::users_tokens::Entity).into(),
+            Self::Watchlist => Entity::has_many(super::watchlist::Entity).into(),
         }
     }
 }
@@ -86,10 +92,28 @@ impl Related<super::cart::Entity> for Entity {
     }
 }
 
+impl Related<super::notification_channels::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::NotificationChannels.def()
+    }
+}
+
+impl Related<super::provider_accounts::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProviderAccounts.def()
+    }
+}
+
 impl Related<super::users_tokens::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UsersTokens.def()
     }
 }
 
+impl Related<super::watchlist::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Watchlist.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
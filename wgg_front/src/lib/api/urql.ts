@@ -66,6 +66,13 @@ export function createUrqlClient(opts?: ClientOptions): Client {
                     NumthPercentOff: () => null,
                     NumForPrice: () => null,
                     NumEuroPrice: () => null,
+                    NumForPercentOff: () => null,
+                    StackingDiscount: () => null,
+                    StackingTier: () => null,
+                    PricePerUnit: () => null,
+                    MinimumQuantity: () => null,
+                    UnmatchedSaleLabel: () => null,
+                    PriceHistoryEntry: () => null,
                     WggSaleCategory: (x) => {
                         const data = x as WggSaleCategory;
                         const id = data.providerInfo.provider + data.name + data.id;
@@ -1,14 +1,15 @@
 import { Provider } from '$lib/api/graphql_types';
 import { notifications } from '$lib/components/notifications/notification';
-import { getProviders } from '$lib/state';
+import { isProviderId, type ProviderMap } from '$lib/state';
 import { error } from '@sveltejs/kit';
 
 /**
- * Verify whether the given provider is valid, if it is not (or undefined) then a `401` error is thrown.
+ * Verify whether the given provider is one of the `availableProviders`, if it is not (or undefined) then a `401` error
+ * is thrown.
  */
-export function verifyProviderOrError(provider?: string): Provider {
-    if (provider != undefined && getProviders().includes(provider as Provider)) {
-        return provider as Provider;
+export function verifyProviderOrError(availableProviders: ProviderMap, provider?: string): Provider {
+    if (provider != undefined && isProviderId(provider, availableProviders)) {
+        return provider;
     } else {
         notifications.error(
             `Provider in URL (${provider}) is not valid, please try going back to the main site.`,
@@ -2,7 +2,7 @@ import { PriceFilter, Provider } from '$lib/api/graphql_types';
 import { notifications } from '$lib/components/notifications/notification';
 import { getContext, setContext } from 'svelte';
 import { get, Writable } from 'svelte/store';
-import { getProviders, ProviderMap } from './providers';
+import { ProviderMap } from './providers';
 import { createPersistentWritable } from './stores';
 
 export type PreferenceStore = Writable<Preferences>;
@@ -59,7 +59,7 @@ export function createPreferenceStore(): Writable<Preferences> {
     const defaultItem: Preferences = {
         displayPrice: "AVERAGE",
         aggregateDisplayPrice: PriceFilter.Average,
-        favouriteProvider: Provider.Picnic
+        favouriteProvider: 'PICNIC'
     };
 
     const store = createPersistentWritable('wggPreferences', defaultItem);
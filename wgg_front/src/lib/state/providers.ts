@@ -3,10 +3,12 @@ import { Provider, ProviderInfo } from '$lib/api/graphql_types';
 export type ProviderMap = Map<Provider, ProviderInfo>;
 
 /**
- * Return all available providers when this front-end was compiled.
+ * Whether the given string is the id of one of the providers returned by the `proProviders` query.
+ *
+ * Providers are registered on the server, see {@link createAvailableProvidersMap}.
  */
-export function getProviders(): Provider[] {
-    return Object.values(Provider);
+export function isProviderId(provider: string, availableProviders: ProviderMap): provider is Provider {
+    return availableProviders.has(provider as Provider);
 }
 
 export function createAvailableProvidersMap(remoteProviders: ProviderInfo[]): ProviderMap {
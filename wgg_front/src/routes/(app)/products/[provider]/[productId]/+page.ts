@@ -5,8 +5,8 @@ import { capitaliseFirst } from '$lib/utils';
 import type { PageLoad } from './$types';
 
 export const load: PageLoad = async (event) => {
-    const { client } = await event.parent();
-    const provider = verifyProviderOrError(event.params.provider);
+    const { client, availableProviders } = await event.parent();
+    const provider = verifyProviderOrError(availableProviders, event.params.provider);
 
     const { store, item } = await asyncQueryStore({
         query: FullProductQueryDocument,
@@ -6,11 +6,14 @@ import { get } from 'svelte/store';
 import type { PageLoad } from './$types';
 
 export const load: PageLoad = async (event) => {
-    const { client, preferences } = await event.parent();
+    const { client, preferences, availableProviders } = await event.parent();
 
     const r_prefs = get(preferences);
     // We want to preserve history when bouncing back and forth between pages. At the moment searchParams are the easiest way of doing that.
-    const initialProvider = verifyProviderOrError(event.url.searchParams.get('provider') ?? r_prefs.favouriteProvider);
+    const initialProvider = verifyProviderOrError(
+        availableProviders,
+        event.url.searchParams.get('provider') ?? r_prefs.favouriteProvider
+    );
 
     const { store } = await asyncQueryStore({
         query: GetFilteredPromotionsDocument,
@@ -0,0 +1,67 @@
+use crate::accounts::CredentialsCipher;
+use crate::db;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, IntoActiveValue};
+use std::sync::Arc;
+use wgg_db_entity::DbId;
+use wgg_providers::wgg_picnic::credentials::{Credentials, CredentialsCache};
+use wgg_providers::wgg_picnic::ApiError;
+
+/// A [CredentialsCache] which keeps the session of a linked provider account, encrypted, in the database.
+pub struct DbCredentialsCache {
+    db: DatabaseConnection,
+    cipher: CredentialsCipher,
+    account_id: DbId,
+    current: Option<Arc<Credentials>>,
+}
+
+impl DbCredentialsCache {
+    /// Create a cache for the given account, initialised with its stored session (if any).
+    pub fn new(db: DatabaseConnection, cipher: CredentialsCipher, account: &db::provider_accounts::Model) -> Self {
+        let current = account.encrypted_session.as_deref().and_then(|session| {
+            match cipher.decrypt_json::<Credentials>(session) {
+                Ok(credentials) => Some(Arc::new(credentials)),
+                Err(e) => {
+                    tracing::warn!(account_id = account.id, error = ?e, "Discarding unreadable account session");
+                    None
+                }
+            }
+        });
+
+        Self {
+            db,
+            cipher,
+            account_id: account.id,
+            current,
+        }
+    }
+}
+
+impl CredentialsCache for DbCredentialsCache {
+    fn request_credentials(&self) -> BoxFuture<wgg_providers::wgg_picnic::Result<Option<Arc<Credentials>>>> {
+        futures::future::ready(Ok(self.current.clone())).boxed()
+    }
+
+    fn persist_credentials(
+        &mut self,
+        credentials: Arc<Credentials>,
+    ) -> BoxFuture<wgg_providers::wgg_picnic::Result<()>> {
+        async move {
+            let update = db::provider_accounts::ActiveModel {
+                id: self.account_id.into_active_value(),
+                encrypted_session: Some(self.cipher.encrypt_json(credentials.as_ref())?).into_active_value(),
+                ..Default::default()
+            };
+            update.update(&self.db).await.map_err(|e| anyhow::anyhow!(e))?;
+
+            self.current = Some(credentials);
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn request_2fa_code(&self) -> BoxFuture<wgg_providers::wgg_picnic::Result<String>> {
+        futures::future::ready(Err(ApiError::NoSecondFactorCode)).boxed()
+    }
+}
@@ -0,0 +1,158 @@
+use crate::config::AppConfig;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, Context};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// The length of the nonce which is prepended to every ciphertext.
+const NONCE_LENGTH: usize = 12;
+
+/// Encrypts secrets, such as the passwords of linked provider accounts, before they're stored in the database.
+///
+/// Uses AES-256-GCM, with the key derived from the configured `credentials_secret_key`.
+///
+/// Data encrypted with one of the `previous_credentials_secret_keys` can still be decrypted, see [Self::reencrypt] to
+/// rotate such data to the current key.
+#[derive(Clone)]
+pub struct CredentialsCipher {
+    cipher: Aes256Gcm,
+    previous: Vec<Aes256Gcm>,
+}
+
+impl CredentialsCipher {
+    pub fn new(secret_key: &str) -> Self {
+        Self {
+            cipher: derive_cipher(secret_key),
+            previous: Vec::new(),
+        }
+    }
+
+    /// Create the cipher for the `credentials_secret_key` of the given config, accepting its previous keys as well.
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new(&config.credentials_secret_key).with_previous_keys(&config.previous_credentials_secret_keys)
+    }
+
+    /// Also accept the given keys when decrypting, these are never used to encrypt.
+    pub fn with_previous_keys(mut self, keys: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.previous
+            .extend(keys.into_iter().map(|key| derive_cipher(key.as_ref())));
+        self
+    }
+
+    /// Encrypt the given `plaintext`, the result contains both the nonce and the ciphertext.
+    pub fn encrypt(&self, plaintext: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow!("Failed to encrypt credentials"))?;
+
+        let mut result = Vec::with_capacity(NONCE_LENGTH + ciphertext.len());
+        result.extend_from_slice(&nonce);
+        result.extend(ciphertext);
+
+        Ok(result)
+    }
+
+    /// Decrypt data created by [Self::encrypt].
+    ///
+    /// Fails if the data was encrypted with a different key, or has been tampered with.
+    pub fn decrypt(&self, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        if data.len() < NONCE_LENGTH {
+            anyhow::bail!("Encrypted credentials are too short");
+        }
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+        let nonce = Nonce::from_slice(nonce);
+
+        std::iter::once(&self.cipher)
+            .chain(&self.previous)
+            .find_map(|cipher| cipher.decrypt(nonce, ciphertext).ok())
+            .ok_or_else(|| anyhow!("Failed to decrypt credentials, has the `credentials_secret_key` changed?"))
+    }
+
+    /// Re-encrypt data which was encrypted with one of the previous keys using the current key.
+    ///
+    /// Returns `None` if `data` is already encrypted with the current key.
+    pub fn reencrypt(&self, data: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        if data.len() >= NONCE_LENGTH {
+            let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+
+            if self.cipher.decrypt(Nonce::from_slice(nonce), ciphertext).is_ok() {
+                return Ok(None);
+            }
+        }
+
+        self.encrypt(&self.decrypt(data)?).map(Some)
+    }
+
+    pub fn encrypt_str(&self, plaintext: &str) -> anyhow::Result<Vec<u8>> {
+        self.encrypt(plaintext.as_bytes())
+    }
+
+    pub fn decrypt_str(&self, data: &[u8]) -> anyhow::Result<String> {
+        String::from_utf8(self.decrypt(data)?).context("Decrypted credentials are not valid UTF-8")
+    }
+
+    pub fn encrypt_json<T: Serialize>(&self, value: &T) -> anyhow::Result<Vec<u8>> {
+        self.encrypt(&serde_json::to_vec(value)?)
+    }
+
+    pub fn decrypt_json<T: DeserializeOwned>(&self, data: &[u8]) -> anyhow::Result<T> {
+        Ok(serde_json::from_slice(&self.decrypt(data)?)?)
+    }
+}
+
+fn derive_cipher(secret_key: &str) -> Aes256Gcm {
+    let key = Sha256::digest(secret_key.as_bytes());
+
+    Aes256Gcm::new(&key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CredentialsCipher;
+
+    #[test]
+    fn test_round_trip() {
+        let cipher = CredentialsCipher::new("secret");
+        let first = cipher.encrypt_str("hunter2").unwrap();
+        let second = cipher.encrypt_str("hunter2").unwrap();
+
+        // Every encryption uses a fresh nonce.
+        assert_ne!(first, second);
+        assert_eq!(cipher.decrypt_str(&first).unwrap(), "hunter2");
+        assert_eq!(cipher.decrypt_str(&second).unwrap(), "hunter2");
+    }
+
+    #[test]
+    fn test_wrong_key_or_tampering() {
+        let cipher = CredentialsCipher::new("secret");
+        let mut encrypted = cipher.encrypt_str("hunter2").unwrap();
+
+        assert!(CredentialsCipher::new("other").decrypt(&encrypted).is_err());
+        assert!(cipher.decrypt(&encrypted[..4]).is_err());
+
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 1;
+        assert!(cipher.decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let old = CredentialsCipher::new("old");
+        let encrypted = old.encrypt_str("hunter2").unwrap();
+
+        let rotated = CredentialsCipher::new("new").with_previous_keys(["old"]);
+        assert_eq!(rotated.decrypt_str(&encrypted).unwrap(), "hunter2");
+
+        let reencrypted = rotated.reencrypt(&encrypted).unwrap().unwrap();
+        assert!(rotated.reencrypt(&reencrypted).unwrap().is_none());
+        assert_eq!(
+            CredentialsCipher::new("new").decrypt_str(&reencrypted).unwrap(),
+            "hunter2"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use crate::accounts::CredentialsCipher;
+use anyhow::Context;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use wgg_providers::wgg_picnic::credentials::{Credentials, CredentialsCache};
+use wgg_providers::wgg_picnic::ApiError;
+
+/// A [CredentialsCache] which stores its credentials in a file, encrypted with a [CredentialsCipher].
+///
+/// Replaces the plaintext `JsonFileCache`, see [Self::migrate_plaintext] for importing its files.
+pub struct EncryptedFileCache {
+    cipher: CredentialsCipher,
+    cache_path: PathBuf,
+    current: Option<Arc<Credentials>>,
+}
+
+impl EncryptedFileCache {
+    /// Initialise the cache from the given file.
+    ///
+    /// If the file was encrypted with a previous key it is immediately rewritten with the current key. Should the file
+    /// not exist, or be unreadable, the [Credentials] will be initialised as [None].
+    pub fn from_file(file: impl Into<PathBuf>, cipher: CredentialsCipher) -> Self {
+        let cache_path = file.into();
+        let mut result = Self {
+            cipher,
+            cache_path,
+            current: None,
+        };
+
+        let Ok(contents) = std::fs::read(&result.cache_path) else {
+            return result;
+        };
+
+        match result.cipher.decrypt_json::<Credentials>(&contents) {
+            Ok(credentials) => {
+                let credentials = Arc::new(credentials);
+
+                if matches!(result.cipher.reencrypt(&contents), Ok(Some(_))) {
+                    tracing::info!(path = ?result.cache_path, "Re-encrypting credentials with the current key");
+                    if let Err(e) = result.write_file(&credentials) {
+                        tracing::warn!(error = ?e, "Failed to re-encrypt credentials");
+                    }
+                }
+
+                result.current = Some(credentials);
+            }
+            Err(e) => tracing::warn!(path = ?result.cache_path, error = ?e, "Discarding unreadable credentials"),
+        }
+
+        result
+    }
+
+    /// Import the credentials stored in plaintext by a `JsonFileCache` at `plaintext_file`, after which that file
+    /// is removed.
+    ///
+    /// Does nothing if `plaintext_file` doesn't exist. Imported credentials replace the current ones.
+    pub fn migrate_plaintext(&mut self, plaintext_file: impl AsRef<Path>) -> anyhow::Result<()> {
+        let plaintext_file = plaintext_file.as_ref();
+        let Ok(contents) = std::fs::read(plaintext_file) else {
+            return Ok(());
+        };
+
+        match serde_json::from_slice::<Credentials>(&contents) {
+            Ok(credentials) => {
+                tracing::info!(path = ?plaintext_file, "Migrating plaintext credentials to encrypted storage");
+                let credentials = Arc::new(credentials);
+                self.write_file(&credentials)?;
+                self.current = Some(credentials);
+            }
+            Err(e) => tracing::warn!(path = ?plaintext_file, error = ?e, "Discarding unreadable plaintext credentials"),
+        }
+
+        std::fs::remove_file(plaintext_file).context("Failed to remove plaintext credentials")
+    }
+
+    fn write_file(&self, credentials: &Credentials) -> anyhow::Result<()> {
+        if let Some(path) = self.cache_path.parent() {
+            std::fs::create_dir_all(path)?;
+        }
+
+        // Write to a temporary file first, to never leave a partially written file behind.
+        let temp_path = self.cache_path.with_extension("tmp");
+        std::fs::write(&temp_path, self.cipher.encrypt_json(credentials)?)?;
+        std::fs::rename(&temp_path, &self.cache_path)?;
+
+        Ok(())
+    }
+}
+
+impl CredentialsCache for EncryptedFileCache {
+    fn request_credentials(&self) -> BoxFuture<wgg_providers::wgg_picnic::Result<Option<Arc<Credentials>>>> {
+        futures::future::ready(Ok(self.current.clone())).boxed()
+    }
+
+    fn persist_credentials(
+        &mut self,
+        credentials: Arc<Credentials>,
+    ) -> BoxFuture<wgg_providers::wgg_picnic::Result<()>> {
+        async move {
+            self.write_file(&credentials)?;
+            self.current = Some(credentials);
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn request_2fa_code(&self) -> BoxFuture<wgg_providers::wgg_picnic::Result<String>> {
+        futures::future::ready(Err(ApiError::NoSecondFactorCode)).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EncryptedFileCache;
+    use crate::accounts::CredentialsCipher;
+    use wgg_providers::wgg_picnic::credentials::Credentials;
+
+    fn auth_token(cache: &EncryptedFileCache) -> Option<&str> {
+        cache
+            .current
+            .as_ref()
+            .map(|credentials| credentials.auth_token.as_str())
+    }
+
+    #[test]
+    fn test_migrate_and_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let plaintext = dir.path().join("picnic.json");
+        let encrypted = dir.path().join("picnic.credentials");
+        std::fs::write(
+            &plaintext,
+            serde_json::to_vec(&Credentials::new("token".into(), "user".into())).unwrap(),
+        )
+        .unwrap();
+
+        let mut cache = EncryptedFileCache::from_file(&encrypted, CredentialsCipher::new("old"));
+        cache.migrate_plaintext(&plaintext).unwrap();
+
+        assert!(!plaintext.exists());
+        assert!(!std::fs::read_to_string(&encrypted)
+            .unwrap_or_default()
+            .contains("token"));
+        assert_eq!(auth_token(&cache), Some("token"));
+
+        let cipher = CredentialsCipher::new("new").with_previous_keys(["old"]);
+        let cache = EncryptedFileCache::from_file(&encrypted, cipher);
+        assert_eq!(auth_token(&cache), Some("token"));
+
+        // The file should now only be readable with the new key.
+        let cache = EncryptedFileCache::from_file(&encrypted, CredentialsCipher::new("new"));
+        assert_eq!(auth_token(&cache), Some("token"));
+    }
+}
@@ -0,0 +1,141 @@
+//! Provider accounts which users link to their own Wgg account.
+//!
+//! Cart operations for a provider are performed on the account the user linked for it, see [AccountCarts]. Passwords
+//! and sessions of these accounts are only stored encrypted, see [CredentialsCipher].
+use crate::api::AppState;
+use crate::db;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveValue, QueryFilter};
+use secrecy::SecretString;
+use std::collections::HashMap;
+use std::sync::Arc;
+use wgg_db_entity::DbId;
+use wgg_providers::models::Provider;
+use wgg_providers::{AccountCart, PicnicCredentials};
+
+mod credentials_cache;
+mod crypto;
+mod file_cache;
+
+pub use credentials_cache::DbCredentialsCache;
+pub use crypto::CredentialsCipher;
+pub use file_cache::EncryptedFileCache;
+
+/// All providers for which a user can link their own account.
+pub const LINKABLE_PROVIDERS: [Provider; 1] = [Provider::PICNIC];
+
+/// The carts of all linked accounts which have been used since startup, by user and provider.
+///
+/// Carts are kept around as they hold the session of their account.
+#[derive(Clone, Default)]
+pub struct AccountCarts {
+    carts: Arc<tokio::sync::Mutex<HashMap<(DbId, Provider), AccountCart>>>,
+}
+
+impl AccountCarts {
+    /// Retrieve the cart of the account the given user linked for `provider`.
+    ///
+    /// Returns `None` if the user didn't link an account for `provider`.
+    pub async fn cart_for(
+        &self,
+        state: &AppState,
+        user_id: DbId,
+        provider: Provider,
+    ) -> anyhow::Result<Option<AccountCart>> {
+        let mut carts = self.carts.lock().await;
+
+        if let Some(cart) = carts.get(&(user_id, provider)) {
+            return Ok(Some(cart.clone()));
+        }
+
+        let account = db::provider_accounts::Entity::find()
+            .filter(db::provider_accounts::owned_by(user_id))
+            .filter(db::provider_accounts::for_provider(
+                state.provider_id_from_provider(&provider),
+            ))
+            .one(&state.db)
+            .await?;
+
+        let Some(account) = account else {
+            return Ok(None);
+        };
+
+        let cart = create_cart(state, provider, &account).await?;
+        carts.insert((user_id, provider), cart.clone());
+
+        Ok(Some(cart))
+    }
+
+    /// Drop the cart of the given account, has to be called whenever an account is re-linked or unlinked.
+    pub async fn forget(&self, user_id: DbId, provider: Provider) {
+        self.carts.lock().await.remove(&(user_id, provider));
+    }
+}
+
+async fn create_cart(
+    state: &AppState,
+    provider: Provider,
+    account: &db::provider_accounts::Model,
+) -> anyhow::Result<AccountCart> {
+    let password = state.credentials_cipher.decrypt_str(&account.encrypted_password)?;
+
+    match provider {
+        Provider::PICNIC => {
+            let credentials = PicnicCredentials::new(account.username.clone(), SecretString::new(password));
+            let cache = DbCredentialsCache::new(state.db.clone(), state.credentials_cipher.clone(), account);
+
+            Ok(state.providers.picnic_account_cart(credentials, cache).await?)
+        }
+        _ => anyhow::bail!("Linked accounts are not supported for {}", provider),
+    }
+}
+
+/// Re-encrypt the stored credentials of all linked accounts which still use one of the previous keys of `cipher`.
+///
+/// Returns the amount of accounts which were updated.
+pub async fn rotate_account_credentials(db: &DatabaseConnection, cipher: &CredentialsCipher) -> anyhow::Result<u64> {
+    let accounts = db::provider_accounts::Entity::find().all(db).await?;
+    let mut updated = 0;
+
+    for account in accounts {
+        let password = match cipher.reencrypt(&account.encrypted_password) {
+            Ok(password) => password,
+            Err(e) => {
+                tracing::warn!(account_id = account.id, error = ?e, "Unreadable account password, has to be re-linked");
+                continue;
+            }
+        };
+        // Sessions can always be recreated, so unreadable ones are simply removed.
+        let session = account
+            .encrypted_session
+            .as_deref()
+            .map(|session| cipher.reencrypt(session));
+        let session = match session {
+            Some(Ok(Some(session))) => Some(Some(session)),
+            Some(Err(e)) => {
+                tracing::warn!(account_id = account.id, error = ?e, "Discarding unreadable account session");
+                Some(None)
+            }
+            _ => None,
+        };
+
+        if password.is_none() && session.is_none() {
+            continue;
+        }
+
+        let mut update = db::provider_accounts::ActiveModel {
+            id: account.id.into_active_value(),
+            ..Default::default()
+        };
+        if let Some(password) = password {
+            update.encrypted_password = password.into_active_value();
+        }
+        if let Some(session) = session {
+            update.encrypted_session = session.into_active_value();
+        }
+        update.update(db).await?;
+
+        updated += 1;
+    }
+
+    Ok(updated)
+}
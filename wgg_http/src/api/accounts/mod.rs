@@ -0,0 +1,48 @@
+use crate::api::auth::AuthContext;
+use crate::api::error::GraphqlError;
+use crate::api::{AppState, GraphqlResult};
+use crate::db;
+use sea_orm::{EntityTrait, QueryFilter};
+use wgg_providers::models::Provider;
+use wgg_providers::AccountCart;
+
+mod mutation;
+mod objects;
+mod query;
+
+pub use mutation::AccountMutation;
+pub use query::AccountQuery;
+
+/// Retrieve the cart which should be used for the cart operations of the given user at `provider`.
+///
+/// Returns `None` if the server-wide account should be used instead. Admins can always use the server-wide account,
+/// but other users only until anyone links an account for `provider`, after which they have to link their own.
+pub(crate) async fn user_cart_for(
+    state: &AppState,
+    user: &AuthContext,
+    provider: Provider,
+) -> GraphqlResult<Option<AccountCart>> {
+    if let Some(cart) = state.account_carts.cart_for(state, user.id, provider).await? {
+        return Ok(Some(cart));
+    }
+
+    if user.is_admin {
+        return Ok(None);
+    }
+
+    let any_linked = db::provider_accounts::Entity::find()
+        .filter(db::provider_accounts::for_provider(
+            state.provider_id_from_provider(&provider),
+        ))
+        .one(&state.db)
+        .await?
+        .is_some();
+
+    if any_linked {
+        Err(GraphqlError::UserError(format!(
+            "Link your own {provider} account before using its cart"
+        )))
+    } else {
+        Ok(None)
+    }
+}
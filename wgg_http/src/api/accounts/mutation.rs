@@ -0,0 +1,135 @@
+use crate::accounts::LINKABLE_PROVIDERS;
+use crate::api::accounts::objects::LinkedProviderAccount;
+use crate::api::error::GraphqlError;
+use crate::api::{ContextExt, GraphqlResult};
+use crate::db;
+use async_graphql::{Context, Object};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, EntityTrait, IntoActiveModel, IntoActiveValue, QueryFilter, TransactionTrait,
+};
+use wgg_providers::models::Provider;
+
+#[derive(Default)]
+pub struct AccountMutation;
+
+#[Object]
+impl AccountMutation {
+    /// Link a provider account to the current user, replacing the previously linked account for that provider.
+    ///
+    /// Cart operations, such as exporting the current cart, will afterwards be performed on this account.
+    /// The credentials are only verified once the account is first used.
+    ///
+    /// # Returns
+    ///
+    /// The linked account.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx, input))]
+    async fn provider_account_link(
+        &self,
+        ctx: &Context<'_>,
+        input: ProviderAccountLinkInput,
+    ) -> GraphqlResult<ProviderAccountLinkPayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        if !LINKABLE_PROVIDERS.contains(&input.provider) {
+            return Err(GraphqlError::InvalidInput(format!(
+                "Accounts can't be linked for provider `{}`",
+                input.provider
+            )));
+        }
+
+        let provider_id = state.provider_id_from_provider(&input.provider);
+        let encrypted_password = state.credentials_cipher.encrypt_str(&input.password)?;
+        let tx = state.db.begin().await?;
+
+        let existing = db::provider_accounts::Entity::find()
+            .filter(db::provider_accounts::owned_by(user.id))
+            .filter(db::provider_accounts::for_provider(provider_id))
+            .one(&tx)
+            .await?;
+
+        let model = if let Some(existing) = existing {
+            let mut update = existing.into_active_model();
+            update.username = input.username.into_active_value();
+            update.encrypted_password = encrypted_password.into_active_value();
+            // The session belonged to the previous credentials.
+            update.encrypted_session = ActiveValue::Set(None);
+
+            update.update(&tx).await?
+        } else {
+            let new_account = db::provider_accounts::ActiveModel {
+                user_id: user.id.into_active_value(),
+                provider_id: provider_id.into_active_value(),
+                username: input.username.into_active_value(),
+                encrypted_password: encrypted_password.into_active_value(),
+                ..Default::default()
+            };
+
+            new_account.insert(&tx).await?
+        };
+
+        tx.commit().await?;
+
+        state.account_carts.forget(user.id, input.provider).await;
+
+        tracing::debug!(account_id = model.id, "Linked provider account");
+
+        Ok(ProviderAccountLinkPayload {
+            data: LinkedProviderAccount::from_model(model, input.provider),
+        })
+    }
+
+    /// Unlink the account of the given provider from the current user.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone. One can only unlink accounts of the current viewer.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn provider_account_unlink(
+        &self,
+        ctx: &Context<'_>,
+        provider: Provider,
+    ) -> GraphqlResult<ProviderAccountUnlinkPayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let result = db::provider_accounts::Entity::delete_many()
+            .filter(db::provider_accounts::owned_by(user.id))
+            .filter(db::provider_accounts::for_provider(
+                state.provider_id_from_provider(&provider),
+            ))
+            .exec(&state.db)
+            .await?;
+
+        state.account_carts.forget(user.id, provider).await;
+
+        Ok(ProviderAccountUnlinkPayload {
+            deleted: result.rows_affected,
+        })
+    }
+}
+
+#[derive(Debug, async_graphql::InputObject)]
+pub struct ProviderAccountLinkInput {
+    pub provider: Provider,
+    /// The username (usually an email address) used to log in to the provider.
+    pub username: String,
+    #[graphql(secret)]
+    pub password: String,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct ProviderAccountLinkPayload {
+    /// The newly linked account
+    pub data: LinkedProviderAccount,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct ProviderAccountUnlinkPayload {
+    /// The amount of accounts unlinked.
+    pub deleted: u64,
+}
@@ -0,0 +1,40 @@
+use crate::db;
+use async_graphql::{ComplexObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use wgg_db_entity::DbId;
+use wgg_providers::models::{Provider, ProviderInfo};
+
+/// A provider account linked by a user, cart operations for that provider are performed on this account.
+///
+/// The credentials themselves are never exposed.
+#[derive(Clone, Debug, SimpleObject)]
+#[graphql(complex)]
+pub struct LinkedProviderAccount {
+    pub id: DbId,
+    #[graphql(skip)]
+    pub provider: Provider,
+    /// The username (usually an email address) used to log in to the provider.
+    pub username: String,
+    /// Whether the account has successfully logged in since it was linked.
+    pub logged_in: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[ComplexObject]
+impl LinkedProviderAccount {
+    async fn provider_info(&self) -> ProviderInfo {
+        self.provider.as_provider_info()
+    }
+}
+
+impl LinkedProviderAccount {
+    pub fn from_model(model: db::provider_accounts::Model, provider: Provider) -> Self {
+        Self {
+            id: model.id,
+            provider,
+            username: model.username,
+            logged_in: model.encrypted_session.is_some(),
+            created_at: model.created_at,
+        }
+    }
+}
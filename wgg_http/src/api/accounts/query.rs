@@ -0,0 +1,55 @@
+use crate::accounts::LINKABLE_PROVIDERS;
+use crate::api::accounts::objects::LinkedProviderAccount;
+use crate::api::{ContextExt, GraphqlResult};
+use crate::db;
+use async_graphql::{Context, Object};
+use sea_orm::{EntityTrait, QueryFilter, QueryOrder};
+use wgg_providers::models::ProviderInfo;
+
+#[derive(Default)]
+pub struct AccountQuery;
+
+#[Object]
+impl AccountQuery {
+    /// Returns all provider accounts linked by the current user.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn provider_accounts(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<LinkedProviderAccount>> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let accounts = db::provider_accounts::Entity::find()
+            .filter(db::provider_accounts::owned_by(user.id))
+            .order_by_asc(db::provider_accounts::Column::CreatedAt)
+            .all(&state.db)
+            .await?;
+
+        Ok(accounts
+            .into_iter()
+            .map(|account| {
+                let provider = state.provider_from_id(account.provider_id);
+                LinkedProviderAccount::from_model(account, provider)
+            })
+            .collect())
+    }
+
+    /// Returns all active providers for which a user can link their own account.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn provider_accounts_linkable(&self, ctx: &Context<'_>) -> Vec<ProviderInfo> {
+        let state = ctx.wgg_state();
+
+        LINKABLE_PROVIDERS
+            .into_iter()
+            .filter(|provider| {
+                state
+                    .providers
+                    .active_providers()
+                    .any(|active| active.provider() == *provider)
+            })
+            .map(|provider| provider.as_provider_info())
+            .collect()
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
 use wgg_db_entity::{DbId, EntityExt, IntoActiveValueExt, SelectExt};
 
 use super::objects::AggregateIngredient;
+use crate::api::providers::ProductEquivalent;
 
 #[derive(Default)]
 pub struct AggregateMutation;
@@ -22,6 +23,8 @@ impl AggregateMutation {
     /// The sub-ingredients list should have at least one ingredient inside.
     /// The first in the aforementioned list's image will be used as the image for the aggregate ingredient, this can later be changed.
     ///
+    /// If `suggestLinks` is set then likely equivalents of the first ingredient at the other providers are suggested.
+    ///
     /// # Returns
     ///
     /// The newly created aggregate ingredient.
@@ -37,17 +40,18 @@ impl AggregateMutation {
     ) -> GraphqlResult<AggregateCreatePayload> {
         let state = ctx.wgg_state();
         let current_user = ctx.wgg_user()?;
-        let product_image = if let Some(ingredient) = input.ingredients.first() {
+        let first_product = if let Some(ingredient) = input.ingredients.first() {
             Some(
                 state
                     .providers
                     .search_product(ingredient.provider, &ingredient.id)
-                    .await?
-                    .image_url,
+                    .await?,
             )
         } else {
             None
         };
+        let product_image = first_product.as_ref().map(|product| product.image_url.clone());
+        let mut linked_providers: Vec<Provider> = input.ingredients.iter().map(|item| item.provider).collect();
 
         let tx = state.db.begin().await?;
 
@@ -81,7 +85,22 @@ impl AggregateMutation {
             "New aggregate ingredient created"
         );
 
-        Ok(AggregateCreatePayload { data: model.into() })
+        let mut suggestions = Vec::new();
+
+        if let Some(product) = first_product.filter(|_| input.suggest_links) {
+            // Only suggest the best match for every provider which doesn't have a linked ingredient yet.
+            for equivalent in state.providers.equivalents(&product).await? {
+                if !linked_providers.contains(&equivalent.product.provider) {
+                    linked_providers.push(equivalent.product.provider);
+                    suggestions.push(equivalent.into());
+                }
+            }
+        }
+
+        Ok(AggregateCreatePayload {
+            data: model.into(),
+            suggestions,
+        })
     }
 
     /// Update an aggregate ingredient.
@@ -219,6 +238,11 @@ impl AggregateMutation {
 pub struct AggregateCreateInput {
     pub name: String,
     pub ingredients: Vec<ProviderProductInput>,
+    /// Whether to suggest products at other providers which are likely equivalent to the first ingredient.
+    ///
+    /// See `AggregateCreatePayload.suggestions`.
+    #[graphql(default)]
+    pub suggest_links: bool,
 }
 
 #[derive(Debug, async_graphql::InputObject)]
@@ -231,6 +255,10 @@ pub struct ProviderProductInput {
 pub struct AggregateCreatePayload {
     /// The newly created aggregate ingredient
     pub data: AggregateIngredient,
+    /// The best equivalent of the first ingredient at every provider which has no linked ingredient yet.
+    ///
+    /// Only filled when `suggestLinks` was requested, these can be added with `aggregateIngredientUpdate`.
+    pub suggestions: Vec<ProductEquivalent>,
 }
 
 #[derive(Debug, async_graphql::InputObject)]
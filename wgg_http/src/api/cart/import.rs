@@ -0,0 +1,120 @@
+//! Import orders which were placed outside of Wgg as completed carts.
+use crate::api::AppState;
+use crate::db;
+use crate::db::cart_contents::raw_product;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, DatabaseConnection, EntityTrait, IntoActiveValue, QueryFilter, TransactionTrait,
+};
+use std::collections::{BTreeMap, HashSet};
+use wgg_db_entity::DbId;
+use wgg_providers::models::{CentPrice, Provider, WggOrder};
+
+/// Import all delivered orders of `provider` which haven't yet been imported for the given user.
+///
+/// The orders of the account the user linked for `provider` are used, or those of the server-wide account if they
+/// didn't link one. Returns the amount of imported orders.
+pub async fn import_delivered_orders(state: &AppState, user_id: DbId, provider: Provider) -> anyhow::Result<u64> {
+    let account = state.account_carts.cart_for(state, user_id, provider).await?;
+    let provider_id = state.provider_id_from_provider(&provider);
+
+    let orders = match &account {
+        Some(account) => account.delivered_orders().await?,
+        None => state.providers.delivered_orders(provider).await?,
+    };
+
+    let imported: HashSet<String> = db::imported_orders::Entity::find()
+        .inner_join(db::cart::Entity)
+        .filter(db::cart::has_user(user_id))
+        .filter(db::imported_orders::for_provider(provider_id))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|order| order.external_id)
+        .collect();
+
+    let mut count = 0;
+
+    for summary in orders.into_iter().filter(|order| !imported.contains(&order.id)) {
+        let order = match &account {
+            Some(account) => account.delivered_order(&summary.id).await?,
+            None => state.providers.delivered_order(provider, &summary.id).await?,
+        };
+
+        insert_order(&state.db, user_id, provider_id, order).await?;
+        count += 1;
+    }
+
+    if count > 0 {
+        tracing::info!(user_id, %provider, count, "Imported delivered orders");
+    }
+
+    Ok(count)
+}
+
+/// Save the given `order` as a completed cart, with a tally of the prices which were actually paid.
+async fn insert_order(
+    db: &DatabaseConnection,
+    user_id: DbId,
+    provider_id: DbId,
+    order: WggOrder,
+) -> anyhow::Result<()> {
+    let tx = db.begin().await?;
+
+    let cart = db::cart::ActiveModel {
+        user_id: user_id.into_active_value(),
+        completed_at: ActiveValue::Set(Some(order.delivered_at)),
+        picked_id: ActiveValue::Set(Some(provider_id)),
+        ..Default::default()
+    }
+    .insert(&tx)
+    .await?;
+
+    // A cart can only contain a product once, so lines of the same product are merged.
+    let mut quantities: BTreeMap<&str, u32> = BTreeMap::new();
+    for line in &order.lines {
+        *quantities.entry(line.product_id.as_str()).or_default() += line.quantity;
+    }
+
+    if !quantities.is_empty() {
+        let contents = quantities
+            .into_iter()
+            .map(|(product_id, quantity)| raw_product::ActiveModel {
+                cart_id: cart.id.into_active_value(),
+                provider_id: provider_id.into_active_value(),
+                provider_product: product_id.to_string().into_active_value(),
+                quantity: (quantity as i32).into_active_value(),
+                created_at: order.delivered_at.into_active_value(),
+                ..Default::default()
+            });
+
+        raw_product::Entity::insert_many(contents).exec(&tx).await?;
+    }
+
+    let original_price: CentPrice = order.lines.iter().map(|line| line.original_price).sum();
+    let display_price: CentPrice = order.lines.iter().map(|line| line.display_price).sum();
+
+    db::cart_tally::ActiveModel {
+        cart_id: cart.id.into_active_value(),
+        provider_id: provider_id.into_active_value(),
+        price_cents: (original_price as i32).into_active_value(),
+        discount: (original_price.saturating_sub(display_price) as i32).into_active_value(),
+        deposit: (order.deposit as i32).into_active_value(),
+        delivery_fee: (order.delivery_fee as i32).into_active_value(),
+        service_fee: (order.service_fee as i32).into_active_value(),
+    }
+    .insert(&tx)
+    .await?;
+
+    db::imported_orders::ActiveModel {
+        cart_id: cart.id.into_active_value(),
+        provider_id: provider_id.into_active_value(),
+        external_id: order.id.into_active_value(),
+        ..Default::default()
+    }
+    .insert(&tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -1,8 +1,13 @@
+mod import;
 mod mutation;
 mod objects;
 mod query;
+mod reconcile;
+mod sale_grouping;
 pub mod scheduled_jobs;
 mod service;
+mod split;
+mod sync;
 
 pub use mutation::CartMutation;
 pub use objects::UserCart;
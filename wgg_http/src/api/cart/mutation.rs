@@ -1,14 +1,20 @@
+use crate::api::cart::reconcile::ReconciliationReport;
+use crate::api::cart::service::ProviderTally;
+use crate::api::cart::sync::CartSyncDirection;
 use crate::api::cart::{service, UserCart};
 use crate::api::error::GraphqlError;
 use crate::api::{ContextExt, GraphqlResult, ProductId};
 use crate::db;
+use crate::notifications::{self, Notification, NotificationKind};
 use async_graphql::Context;
+use itertools::Itertools;
 use sea_orm::sea_query::OnConflict;
 use sea_orm::{
     ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, IntoActiveValue, QueryFilter, TransactionTrait,
 };
+use std::collections::HashMap;
 use wgg_db_entity::{DbId, IntoActiveValueExt};
-use wgg_providers::models::Provider;
+use wgg_providers::models::{Provider, WggCheckoutStatus};
 
 #[derive(Default)]
 pub struct CartMutation;
@@ -188,18 +194,26 @@ impl CartMutation {
 
         // Save the historic tallies
         let tallies = super::service::calculate_tallies(&state.db, cart.id, state).await?;
+        let summary = cart_completed_notification(input.picked_provider, &tallies);
 
         if !tallies.is_empty() {
-            let to_submit = tallies.into_iter().map(|(provider, info)| db::cart_tally::ActiveModel {
-                cart_id: cart.id.into_active_value(),
-                provider_id: state.provider_id_from_provider(&provider).into_active_value(),
-                price_cents: (info.original_price as i32).into_active_value(),
-                discount: (info.discount as i32).into_active_value(),
-            });
+            let to_submit = tallies
+                .into_iter()
+                .map(|(provider, tally)| db::cart_tally::ActiveModel {
+                    cart_id: cart.id.into_active_value(),
+                    provider_id: state.provider_id_from_provider(&provider).into_active_value(),
+                    price_cents: (tally.price_info.original_price as i32).into_active_value(),
+                    discount: (tally.price_info.discount as i32).into_active_value(),
+                    deposit: (tally.price_info.deposit as i32).into_active_value(),
+                    delivery_fee: (tally.price_info.delivery_fee as i32).into_active_value(),
+                    service_fee: (tally.price_info.service_fee as i32).into_active_value(),
+                });
 
             let _ = db::cart_tally::Entity::insert_many(to_submit).exec(&tx).await?;
         }
 
+        notifications::notify_user(&tx, user.id, &summary).await?;
+
         tx.commit().await?;
 
         Ok(CartCompletePayload { data: cart.into() })
@@ -207,6 +221,10 @@ impl CartMutation {
 
     /// Export the items in the current cart to the given [Provider]'s cart implementation.
     ///
+    /// If the current user linked an account for the provider then the items are exported to that account's cart.
+    /// Once anyone linked an account for the provider, users other than admins have to link their own.
+    /// To only apply the differences between both carts use `cartCurrentSync` instead.
+    ///
     /// # Arguments
     /// * `clear_existing` - Whether to clear any existing external cart contents or not.
     ///
@@ -229,21 +247,165 @@ impl CartMutation {
 
         tx.commit().await?;
 
-        if input.clear_existing {
-            state.providers.clear_cart(input.provider).await?;
-        }
-
         let items_to_add = items
             .iter()
             .map(|item| (item.product_id.as_ref(), item.quantity))
             .collect::<Vec<_>>();
-        state
-            .providers
-            .add_to_cart(input.provider, items_to_add.as_slice())
-            .await?;
+
+        // Prefer the user's own account, only falling back to the server-wide account if they're allowed to.
+        if let Some(account_cart) = crate::api::accounts::user_cart_for(state, user, input.provider).await? {
+            if input.clear_existing {
+                account_cart.clear_cart().await?;
+            }
+
+            account_cart.add_to_cart(items_to_add.as_slice()).await?;
+        } else {
+            if input.clear_existing {
+                state.providers.clear_cart(input.provider).await?;
+            }
+
+            state
+                .providers
+                .add_to_cart(input.provider, items_to_add.as_slice())
+                .await?;
+        }
 
         Ok(CartExportPayload { success: true })
     }
+
+    /// Synchronise the current cart with the given [Provider]'s cart implementation.
+    ///
+    /// Only the differences between both carts are applied, see [CartSyncDirection] for what is synchronised in every
+    /// direction. If the current user linked an account for the provider then that account's cart is used.
+    /// Once anyone linked an account for the provider, users other than admins have to link their own.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn cart_current_sync(&self, ctx: &Context<'_>, input: CartSyncInput) -> GraphqlResult<CartSyncPayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let summary = super::sync::sync_cart(state, user, input.provider, input.direction).await?;
+        let cart = db::cart::get_active_cart_for_user(user.id, &state.db).await?;
+
+        Ok(CartSyncPayload {
+            data: cart.into(),
+            imported: summary.imported,
+            exported: summary.exported,
+        })
+    }
+
+    /// Compare the tally of the current cart with the tally the given [Provider] calculated for its own cart.
+    ///
+    /// Should be used right after exporting the cart, as the comparison is only meaningful if both carts contain the
+    /// same products. Sales for which the tallies disagree are then logged, see `cartSaleMismatches`.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn cart_current_reconcile(
+        &self,
+        ctx: &Context<'_>,
+        provider: Provider,
+    ) -> GraphqlResult<CartReconcilePayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let report = super::reconcile::reconcile_cart(state, user, provider).await?;
+
+        Ok(CartReconcilePayload { data: report })
+    }
+
+    /// Select a delivery slot for the exported cart at the given [Provider], see `cartCurrentDeliverySlots`.
+    ///
+    /// If the current user linked an account for the provider then the slot is selected for that account's cart.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn cart_current_select_delivery_slot(
+        &self,
+        ctx: &Context<'_>,
+        input: CartSelectDeliverySlotInput,
+    ) -> GraphqlResult<CartSelectDeliverySlotPayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let status = match crate::api::accounts::user_cart_for(state, user, input.provider).await? {
+            Some(account_cart) => {
+                account_cart.select_delivery_slot(&input.slot_id).await?;
+                account_cart.checkout_status().await?
+            }
+            None => {
+                state
+                    .providers
+                    .select_delivery_slot(input.provider, &input.slot_id)
+                    .await?;
+                state.providers.checkout_status(input.provider).await?
+            }
+        };
+
+        Ok(CartSelectDeliverySlotPayload { data: status })
+    }
+
+    /// Import all delivered orders of the given [Provider] as completed carts, using the prices which were actually
+    /// paid.
+    ///
+    /// The orders are imported for `userId` (the viewer if omitted), from the account they linked for the provider or
+    /// the server-wide account if they didn't link one. Orders which were imported before are skipped.
+    ///
+    /// # Accessible By
+    ///
+    /// Admins.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn cart_import_delivered_orders(
+        &self,
+        ctx: &Context<'_>,
+        input: CartImportDeliveredOrdersInput,
+    ) -> GraphqlResult<CartImportDeliveredOrdersPayload> {
+        let state = ctx.wgg_state();
+        let admin = ctx.wgg_admin()?;
+
+        if !input.provider.get_metadata().supports_delivery {
+            return Err(GraphqlError::InvalidInput(format!(
+                "{} does not support importing orders",
+                input.provider
+            )));
+        }
+
+        let user_id = input.user_id.unwrap_or(admin.id);
+        let imported = super::import::import_delivered_orders(state, user_id, input.provider).await?;
+
+        Ok(CartImportDeliveredOrdersPayload { imported })
+    }
+}
+
+/// Summarise the tallies of a just completed cart.
+fn cart_completed_notification(picked: Provider, tallies: &HashMap<Provider, ProviderTally>) -> Notification {
+    let body = tallies
+        .iter()
+        .sorted_by_key(|(provider, _)| **provider)
+        .map(|(provider, tally)| {
+            let marker = if *provider == picked { " (picked)" } else { "" };
+            format!(
+                "{}{}: {} (saved {})",
+                provider.get_metadata().display_name,
+                marker,
+                notifications::format_price(tally.price_info.total_price()),
+                notifications::format_price(tally.price_info.discount)
+            )
+        })
+        .join("\n");
+
+    Notification::new(
+        NotificationKind::CartCompleted,
+        format!("Cart completed with {}", picked.get_metadata().display_name),
+        body,
+    )
 }
 
 #[derive(Debug, async_graphql::InputObject)]
@@ -302,6 +464,25 @@ pub struct CartExportInput {
     pub clear_existing: bool,
 }
 
+#[derive(Debug, async_graphql::InputObject)]
+pub struct CartSyncInput {
+    pub provider: Provider,
+    pub direction: CartSyncDirection,
+}
+
+#[derive(Debug, async_graphql::InputObject)]
+pub struct CartSelectDeliverySlotInput {
+    pub provider: Provider,
+    pub slot_id: String,
+}
+
+#[derive(Debug, async_graphql::InputObject)]
+pub struct CartImportDeliveredOrdersInput {
+    pub provider: Provider,
+    /// The user to import the orders for, defaults to the viewer.
+    pub user_id: Option<DbId>,
+}
+
 #[derive(Debug, async_graphql::SimpleObject)]
 pub struct CartAddProductPayload {
     /// The current cart
@@ -324,3 +505,30 @@ pub struct CartCompletePayload {
 pub struct CartExportPayload {
     pub success: bool,
 }
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct CartSyncPayload {
+    /// The current cart, including any imported products
+    pub data: UserCart,
+    /// The amount of products whose quantity was changed in the current cart
+    pub imported: u64,
+    /// The amount of products whose quantity was changed in the remote cart
+    pub exported: u64,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct CartReconcilePayload {
+    pub data: ReconciliationReport,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct CartSelectDeliverySlotPayload {
+    /// The checkout status of the cart, including the newly selected slot
+    pub data: WggCheckoutStatus,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct CartImportDeliveredOrdersPayload {
+    /// The amount of orders which were imported
+    pub imported: u64,
+}
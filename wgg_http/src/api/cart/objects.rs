@@ -1,16 +1,23 @@
 use crate::api::aggregate_ingredients::AggregateIngredient;
 use crate::api::auth::AuthContext;
+use crate::api::cart::service::{
+    CartEntrySource, CartProductEntry, ProductWithQuantity, ProviderTally, SaleGroupStatus, SaleHint, SaleItemGroup,
+    TallyPriceInfo,
+};
+use crate::api::cart::split::{CartSplitResult, ProviderSplit};
 use crate::api::error::GraphqlError;
 use crate::api::providers::WggSearchProductWrapper;
-use crate::api::{ContextExt, GraphqlResult, ProductId};
+use crate::api::{AppState, ContextExt, GraphqlResult, ProductId};
 use crate::db;
 use async_graphql::{Context, SimpleObject};
 use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use sea_orm::{EntityTrait, ModelTrait, TransactionTrait};
 use std::borrow::Cow;
+use std::collections::HashMap;
+use tokio::sync::OnceCell;
 use wgg_db_entity::{DbId, SelectExt};
-use wgg_providers::models::{CentPrice, Provider, ProviderInfo};
+use wgg_providers::models::{CentPrice, Provider, ProviderInfo, SaleInformation, SublistId};
 
 #[derive(Clone, Debug, SimpleObject)]
 #[graphql(complex)]
@@ -18,6 +25,18 @@ pub struct UserCart {
     id: DbId,
     #[graphql(skip)]
     model: db::cart::Model,
+    /// The current tallies, shared between all fields which need them.
+    #[graphql(skip)]
+    current_tallies: OnceCell<HashMap<Provider, ProviderTally>>,
+}
+
+impl UserCart {
+    /// Calculate the current tallies of this cart, or return the previously calculated ones.
+    async fn current_tallies(&self, state: &AppState) -> GraphqlResult<&HashMap<Provider, ProviderTally>> {
+        self.current_tallies
+            .get_or_try_init(|| super::service::calculate_tallies(&state.db, self.id, state))
+            .await
+    }
 }
 
 #[async_graphql::ComplexObject]
@@ -59,19 +78,33 @@ impl UserCart {
             Ok(result.into_iter().map(|tally| tally.into()).collect())
         } else {
             // Otherwise we calculate the current values.
-            let tallies = super::service::calculate_tallies(&state.db, self.id, state).await?;
+            let tallies = self.current_tallies(state).await?;
 
             Ok(tallies
-                .into_iter()
-                .map(|(provider, info)| CartTally::Current {
-                    provider,
-                    original_price: info.original_price,
-                    discount: info.discount,
+                .iter()
+                .map(|(provider, tally)| CartTally::Current {
+                    provider: *provider,
+                    tally: tally.clone(),
                 })
                 .collect())
         }
     }
 
+    /// Return all sales for which the cart *almost* qualifies, e.g, a single product of a `1 + 1 free` sale.
+    ///
+    /// Completed carts never have any hints.
+    pub async fn sale_hints(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<CartSaleHint>> {
+        if self.model.completed_at.is_some() {
+            return Ok(Vec::new());
+        }
+
+        let state = ctx.wgg_state();
+        let tallies = self.current_tallies(state).await?;
+        let hints = super::service::calculate_sale_hints(state, tallies).await;
+
+        Ok(hints.into_iter().map(CartSaleHint).collect())
+    }
+
     /// Return all the contents of the current cart, notes, products, and aggregates.
     ///
     /// The contents are sorted by the timestamp they were added (recent on top)
@@ -222,32 +255,42 @@ impl CartAggregateProduct {
 #[derive(Clone, Debug)]
 pub enum CartTally {
     Historical(db::cart_tally::Model),
-    Current {
-        provider: Provider,
-        original_price: CentPrice,
-        discount: CentPrice,
-    },
+    Current { provider: Provider, tally: ProviderTally },
 }
 
-#[async_graphql::Object]
 impl CartTally {
-    pub async fn full_price_cents(&self) -> CentPrice {
+    fn price_info(&self) -> TallyPriceInfo {
         match self {
-            CartTally::Historical(model) => model.price_cents as CentPrice,
-            CartTally::Current { original_price, .. } => *original_price,
+            CartTally::Historical(model) => TallyPriceInfo {
+                original_price: model.price_cents as CentPrice,
+                discount: model.discount as CentPrice,
+                deposit: model.deposit as CentPrice,
+                delivery_fee: model.delivery_fee as CentPrice,
+                service_fee: model.service_fee as CentPrice,
+            },
+            CartTally::Current { tally, .. } => tally.price_info,
         }
     }
 
-    pub async fn price_cents(&self) -> CentPrice {
+    /// The sale groups of this tally, `None` for stored tallies as their sale groups aren't stored.
+    fn sale_item_groups(&self) -> Option<&[SaleItemGroup]> {
         match self {
-            CartTally::Historical(model) => (model.price_cents - model.discount) as CentPrice,
-            CartTally::Current {
-                original_price,
-                discount,
-                ..
-            } => original_price - discount,
+            CartTally::Historical(_) => None,
+            CartTally::Current { tally, .. } => Some(&tally.sale_groups),
         }
     }
+}
+
+#[async_graphql::Object]
+impl CartTally {
+    pub async fn full_price_cents(&self) -> CentPrice {
+        self.price_info().original_price
+    }
+
+    /// The price of all products after discounts, excluding deposits and fees.
+    pub async fn price_cents(&self) -> CentPrice {
+        self.price_info().price()
+    }
 
     pub async fn provider_info(&self, ctx: &Context<'_>) -> ProviderInfo {
         let provider = match self {
@@ -263,11 +306,251 @@ impl CartTally {
     }
 
     pub async fn discount_cents(&self) -> CentPrice {
-        match self {
-            CartTally::Historical(model) => model.discount as CentPrice,
-            CartTally::Current { discount, .. } => *discount,
+        self.price_info().discount
+    }
+
+    /// The deposit (statiegeld) charged on top of the product prices.
+    ///
+    /// Note that not all providers expose deposit information for all products, so this can be an underestimate.
+    pub async fn deposit_cents(&self) -> CentPrice {
+        self.price_info().deposit
+    }
+
+    pub async fn delivery_fee_cents(&self) -> CentPrice {
+        self.price_info().delivery_fee
+    }
+
+    pub async fn service_fee_cents(&self) -> CentPrice {
+        self.price_info().service_fee
+    }
+
+    /// The full amount to be paid, including deposits and fees.
+    pub async fn total_price_cents(&self) -> CentPrice {
+        self.price_info().total_price()
+    }
+
+    /// All sales which were considered for this tally, including the ones which weren't applied (and why).
+    ///
+    /// Always `null` for the stored tallies of completed carts, as their sale groups aren't stored.
+    /// Use `forceCurrent` to see the current sale groups instead.
+    pub async fn sale_groups(&self) -> Option<Vec<CartSaleGroup<'_>>> {
+        self.sale_item_groups()
+            .map(|groups| groups.iter().map(CartSaleGroup).collect())
+    }
+}
+
+/// A group of products which were bundled together for a single sale.
+pub struct CartSaleGroup<'a>(&'a SaleItemGroup);
+
+#[async_graphql::Object]
+impl<'a> CartSaleGroup<'a> {
+    /// The sale relevant to this group, including the derived `saleType`.
+    pub async fn sale_info(&self) -> &SaleInformation {
+        &self.0.sale_info
+    }
+
+    pub async fn status(&self) -> SaleGroupStatus {
+        self.0.status
+    }
+
+    /// The amount of additional items required for this group to qualify for the sale.
+    ///
+    /// Only relevant when the `status` is `MISSING_ITEMS`.
+    pub async fn missing_quantity(&self) -> u32 {
+        self.0.missing_quantity
+    }
+
+    /// The price the bundled products would have cost without the sale.
+    pub async fn full_price_cents(&self) -> CentPrice {
+        self.0.price_info.original_price
+    }
+
+    /// The price the bundled products cost with the sale applied.
+    pub async fn price_cents(&self) -> CentPrice {
+        self.0.price_info.display_price
+    }
+
+    pub async fn discount_cents(&self) -> CentPrice {
+        self.0.price_info.original_price - self.0.price_info.display_price
+    }
+
+    pub async fn items(&self) -> Vec<CartSaleGroupItem<'_>> {
+        self.0.items.iter().map(CartSaleGroupItem).collect()
+    }
+}
+
+pub struct CartSaleGroupItem<'a>(&'a ProductWithQuantity);
+
+#[async_graphql::Object]
+impl<'a> CartSaleGroupItem<'a> {
+    /// The amount of this product which is part of the sale group.
+    pub async fn quantity(&self) -> u32 {
+        self.0.quantity
+    }
+
+    pub async fn product(&self) -> WggSearchProductWrapper {
+        self.0.item.clone().into()
+    }
+}
+
+/// A sale for which the cart *almost* qualifies.
+pub struct CartSaleHint(SaleHint);
+
+#[async_graphql::Object]
+impl CartSaleHint {
+    pub async fn provider_info(&self) -> ProviderInfo {
+        self.0.group.provider.as_provider_info()
+    }
+
+    /// The id of the sale's sublist, which can be used to view all products participating in the sale.
+    pub async fn sublist_id(&self) -> &SublistId {
+        &self.0.group.sale_id
+    }
+
+    pub async fn sale_info(&self) -> &SaleInformation {
+        &self.0.group.sale_info
+    }
+
+    /// The amount of additional products which need to be added to the cart to qualify for the sale.
+    pub async fn missing_quantity(&self) -> u32 {
+        self.0.group.missing_quantity
+    }
+
+    /// The products currently in the cart which would qualify for the sale.
+    pub async fn items(&self) -> Vec<CartSaleGroupItem<'_>> {
+        self.0.group.items.iter().map(CartSaleGroupItem).collect()
+    }
+
+    /// The cheapest product participating in the sale, or the product already in the cart for sales which require a
+    /// minimum quantity of every product on its own.
+    ///
+    /// `null` if the products participating in the sale couldn't be retrieved.
+    pub async fn suggestion(&self) -> Option<WggSearchProductWrapper> {
+        self.0.suggestion.clone().map(Into::into)
+    }
+
+    /// The discount which would be given if `missingQuantity` of the `suggestion` were added to the cart.
+    pub async fn potential_discount_cents(&self) -> CentPrice {
+        self.0.potential_discount
+    }
+}
+
+/// The cheapest split of a cart over multiple providers.
+#[derive(Debug)]
+pub struct CartSplit(pub(super) CartSplitResult);
+
+#[async_graphql::Object]
+impl CartSplit {
+    /// The total price of the split, including deposits and fees.
+    pub async fn total_price_cents(&self) -> CentPrice {
+        self.0.total_price
+    }
+
+    /// The total amount by which the providers of this split fall short of their minimum order values.
+    ///
+    /// The split can't be ordered as-is as long as this is larger than `0`.
+    pub async fn shortfall_cents(&self) -> CentPrice {
+        self.0.providers.iter().map(|split| split.shortfall).sum()
+    }
+
+    /// The providers which are part of the split, and the items assigned to them.
+    pub async fn providers(&self) -> Vec<CartSplitProvider<'_>> {
+        self.0.providers.iter().map(CartSplitProvider).collect()
+    }
+
+    /// The cheapest provider which can supply the entire cart on its own.
+    ///
+    /// `null` if no single provider can supply the entire cart.
+    pub async fn best_single_provider(&self) -> Option<ProviderInfo> {
+        self.0.best_single.map(|(provider, _)| provider.as_provider_info())
+    }
+
+    /// The total price (including deposits and fees) of the [Self::best_single_provider].
+    pub async fn best_single_price_cents(&self) -> Option<CentPrice> {
+        self.0.best_single.map(|(_, price)| price)
+    }
+
+    /// The amount saved by this split compared to ordering everything at the [Self::best_single_provider].
+    pub async fn savings_cents(&self) -> Option<CentPrice> {
+        self.0
+            .best_single
+            .map(|(_, price)| price.saturating_sub(self.0.total_price))
+    }
+}
+
+pub struct CartSplitProvider<'a>(&'a ProviderSplit);
+
+#[async_graphql::Object]
+impl<'a> CartSplitProvider<'a> {
+    pub async fn provider_info(&self) -> ProviderInfo {
+        self.0.provider.as_provider_info()
+    }
+
+    pub async fn full_price_cents(&self) -> CentPrice {
+        self.0.tally.original_price
+    }
+
+    /// The price of all items after discounts, excluding deposits and fees.
+    pub async fn price_cents(&self) -> CentPrice {
+        self.0.tally.price()
+    }
+
+    pub async fn discount_cents(&self) -> CentPrice {
+        self.0.tally.discount
+    }
+
+    pub async fn deposit_cents(&self) -> CentPrice {
+        self.0.tally.deposit
+    }
+
+    /// The total of all (delivery, service) fees for this provider.
+    pub async fn fees_cents(&self) -> CentPrice {
+        self.0.fees
+    }
+
+    /// The price of all items, including deposits and fees.
+    pub async fn total_price_cents(&self) -> CentPrice {
+        self.0.tally.price() + self.0.tally.deposit + self.0.fees
+    }
+
+    /// The amount still missing to reach this provider's minimum order value, `0` if it has been reached.
+    pub async fn shortfall_cents(&self) -> CentPrice {
+        self.0.shortfall
+    }
+
+    pub async fn items(&self) -> Vec<CartSplitItem<'_>> {
+        self.0.items.iter().map(CartSplitItem).collect()
+    }
+}
+
+pub struct CartSplitItem<'a>(&'a CartProductEntry);
+
+#[async_graphql::Object]
+impl<'a> CartSplitItem<'a> {
+    /// The id of the [CartProviderProduct] this item originates from, if any.
+    pub async fn product_content_id(&self) -> Option<DbId> {
+        match self.0.source {
+            CartEntrySource::Product(id) => Some(id),
+            CartEntrySource::Aggregate(_) => None,
         }
     }
+
+    /// The id of the [CartAggregateProduct] this item originates from, if any.
+    pub async fn aggregate_content_id(&self) -> Option<DbId> {
+        match self.0.source {
+            CartEntrySource::Aggregate(id) => Some(id),
+            CartEntrySource::Product(_) => None,
+        }
+    }
+
+    pub async fn quantity(&self) -> u32 {
+        self.0.quantity
+    }
+
+    /// The provider product chosen for this item.
+    pub async fn product(&self) -> WggSearchProductWrapper {
+        self.0.product.clone().into()
+    }
 }
 
 impl From<db::cart_contents::notes::Model> for CartNoteProduct {
@@ -313,6 +596,66 @@ impl From<db::cart_tally::Model> for CartTally {
 
 impl From<db::cart::Model> for UserCart {
     fn from(model: db::cart::Model) -> Self {
-        UserCart { id: model.id, model }
+        UserCart {
+            id: model.id,
+            model,
+            current_tallies: OnceCell::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wgg_providers::models::{PriceInfo, SaleValidity};
+
+    #[test]
+    fn test_sale_groups() {
+        let historical = CartTally::Historical(db::cart_tally::Model {
+            cart_id: 1,
+            provider_id: 1,
+            price_cents: 1000,
+            discount: 100,
+            deposit: 0,
+            delivery_fee: 0,
+            service_fee: 0,
+        });
+
+        assert!(historical.sale_item_groups().is_none());
+
+        let group = SaleItemGroup {
+            sale_id: "sale".to_string(),
+            provider: Provider::PICNIC,
+            sale_info: SaleInformation {
+                id: None,
+                label: "1 + 1 gratis".to_string(),
+                additional_label: Vec::new(),
+                sale_validity: SaleValidity {
+                    valid_from: Utc::now(),
+                    valid_until: Utc::now(),
+                },
+                sale_type: None,
+            },
+            status: SaleGroupStatus::UnknownSaleType,
+            missing_quantity: 0,
+            price_info: PriceInfo {
+                display_price: 100,
+                original_price: 100,
+                unit_price: None,
+                deposit: None,
+            },
+            items: Vec::new(),
+        };
+        let current = CartTally::Current {
+            provider: Provider::PICNIC,
+            tally: ProviderTally {
+                price_info: TallyPriceInfo::default(),
+                sale_groups: vec![group],
+            },
+        };
+
+        let groups = current.sale_item_groups().unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].status, SaleGroupStatus::UnknownSaleType);
     }
 }
@@ -1,4 +1,6 @@
-use crate::api::cart::objects::UserCart;
+use crate::api::cart::objects::{CartSplit, UserCart};
+use crate::api::cart::reconcile::SaleMismatchSummary;
+use crate::api::cart::split::ProviderCosts;
 use crate::api::error::GraphqlError;
 use crate::api::pagination::{ConnectionResult, QueryResult};
 use crate::api::{ContextExt, GraphqlResult};
@@ -6,7 +8,9 @@ use crate::cross_system::Filter;
 use crate::{api, cross_system, db};
 use async_graphql::Context;
 use sea_orm::{EntityTrait, QueryFilter};
+use std::collections::HashMap;
 use wgg_db_entity::{DbId, SelectExt};
+use wgg_providers::models::{CentPrice, Provider, WggCheckoutStatus, WggDeliverySlot};
 
 #[derive(Default)]
 pub struct CartQuery;
@@ -28,6 +32,123 @@ impl CartQuery {
         Ok(cart.into())
     }
 
+    /// Calculate the cheapest way to split the current cart of the viewer over multiple providers.
+    ///
+    /// Sales, deposits, fees, and minimum order values are taken into account.
+    /// Should no split reach the minimum order values of its providers, the split with the smallest shortfall is
+    /// returned instead, see `shortfallCents`.
+    /// Aggregate ingredients can be assigned to any of their linked providers, whereas direct products are always
+    /// assigned to their own provider.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn cart_current_split(
+        &self,
+        ctx: &Context<'_>,
+        input: Option<CartSplitInput>,
+    ) -> GraphqlResult<CartSplit> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+        let input = input.unwrap_or_default();
+
+        let max_stores = match input.max_stores {
+            Some(0) => {
+                return Err(GraphqlError::InvalidInput(
+                    "`maxStores` should be at least 1".to_string(),
+                ));
+            }
+            Some(max) => max as usize,
+            None => usize::MAX,
+        };
+        let costs: HashMap<Provider, ProviderCosts> = input
+            .provider_costs
+            .into_iter()
+            .map(|cost| {
+                (
+                    cost.provider,
+                    ProviderCosts {
+                        fees: cost.fees_cents,
+                        minimum_order: cost.minimum_order_cents,
+                    },
+                )
+            })
+            .collect();
+
+        let cart = db::cart::get_active_cart_for_user(user.id, &state.db).await?;
+        let split = super::split::calculate_cart_split(&state.db, cart.id, state, max_stores, &costs).await?;
+
+        Ok(CartSplit(split))
+    }
+
+    /// List all delivery slots of the given [Provider] for the exported cart, see `cartCurrentExport`.
+    ///
+    /// If the viewer linked an account for the provider then the slots for that account's cart are returned.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn cart_current_delivery_slots(
+        &self,
+        ctx: &Context<'_>,
+        provider: Provider,
+    ) -> GraphqlResult<Vec<WggDeliverySlot>> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let slots = match crate::api::accounts::user_cart_for(state, user, provider).await? {
+            Some(account_cart) => account_cart.delivery_slots().await?,
+            None => state.providers.delivery_slots(provider).await?,
+        };
+
+        Ok(slots)
+    }
+
+    /// Retrieve the status of the checkout of the exported cart at the given [Provider], see `cartCurrentExport`.
+    ///
+    /// If the viewer linked an account for the provider then the status of that account's cart is returned.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn cart_current_checkout_status(
+        &self,
+        ctx: &Context<'_>,
+        provider: Provider,
+    ) -> GraphqlResult<WggCheckoutStatus> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let status = match crate::api::accounts::user_cart_for(state, user, provider).await? {
+            Some(account_cart) => account_cart.checkout_status().await?,
+            None => state.providers.checkout_status(provider).await?,
+        };
+
+        Ok(status)
+    }
+
+    /// Summarise all sales for which the tally of a provider disagreed with our own, per provider and kind of sale.
+    ///
+    /// Mismatches are logged by `cartCurrentReconcile`.
+    ///
+    /// # Accessible By
+    ///
+    /// Admins.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn cart_sale_mismatches(
+        &self,
+        ctx: &Context<'_>,
+        provider: Option<Provider>,
+    ) -> GraphqlResult<Vec<SaleMismatchSummary>> {
+        let state = ctx.wgg_state();
+        let _ = ctx.wgg_admin()?;
+
+        super::reconcile::sale_mismatch_summaries(state, provider).await
+    }
+
     #[tracing::instrument(skip(self, ctx))]
     pub async fn carts(
         &self,
@@ -81,3 +202,23 @@ pub struct CartList {
     /// Whether the cart has been resolved (aka completed)
     pub is_completed: Option<bool>,
 }
+
+#[derive(async_graphql::InputObject, Debug, Default)]
+pub struct CartSplitInput {
+    /// The maximum amount of stores the cart may be split over. No limit if not provided.
+    pub max_stores: Option<u32>,
+    /// Overrides for the fees and minimum order values of providers.
+    ///
+    /// Providers which are not mentioned use their default fees and minimum order values.
+    #[graphql(default)]
+    pub provider_costs: Vec<ProviderCostsInput>,
+}
+
+#[derive(async_graphql::InputObject, Debug)]
+pub struct ProviderCostsInput {
+    pub provider: Provider,
+    /// The total of all (delivery, service) fees, uses the provider's fee schedule if not provided.
+    pub fees_cents: Option<CentPrice>,
+    /// Uses the provider's minimum order value if not provided.
+    pub minimum_order_cents: Option<CentPrice>,
+}
@@ -0,0 +1,421 @@
+//! Reconcile our own tally of the current cart with the tally the provider calculated for its remote cart.
+use crate::api::auth::AuthContext;
+use crate::api::cart::{service, sync};
+use crate::api::{AppState, GraphqlResult};
+use crate::db;
+use sea_orm::{EntityTrait, IntoActiveValue, QueryFilter, TransactionTrait};
+use std::collections::{BTreeMap, HashSet};
+use wgg_db_entity::DbId;
+use wgg_providers::models::{CentPrice, Provider, SaleInformation, SublistId, WggRemoteTally};
+
+/// The comparison of our own tally of the current cart with the tally of the provider's remote cart.
+///
+/// Every `actual` field is `null` if the provider doesn't report it.
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+pub struct ReconciliationReport {
+    pub provider: Provider,
+    /// Whether the remote cart contains exactly the same products as the current cart.
+    ///
+    /// If not, the comparison is meaningless, export or sync the cart first.
+    pub contents_match: bool,
+    /// The price of all products after discounts, excluding deposits and fees.
+    pub expected_price_cents: CentPrice,
+    pub actual_price_cents: Option<CentPrice>,
+    pub expected_deposit_cents: CentPrice,
+    pub actual_deposit_cents: Option<CentPrice>,
+    pub sales: Vec<SaleReconciliation>,
+}
+
+/// The comparison of a single sale of our own tally with the provider's.
+///
+/// A sale can be split over multiple groups in our own tally (e.g, completed groups and leftover items), these are
+/// compared as a whole, as providers only report the result per sale.
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+pub struct SaleReconciliation {
+    pub sale_id: SublistId,
+    pub sale_info: SaleInformation,
+    /// Whether at least one group of the sale was applied in our own tally.
+    pub expected_applied: bool,
+    pub actual_applied: Option<bool>,
+    pub expected_discount_cents: CentPrice,
+    pub actual_discount_cents: Option<CentPrice>,
+    /// Whether everything the provider reported matched our own tally.
+    pub matches: bool,
+}
+
+/// All logged mismatches of a single kind of sale at a provider.
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+pub struct SaleMismatchSummary {
+    pub provider: Provider,
+    /// The kind of sale, see `SaleType`, `null` for sales whose label couldn't be interpreted.
+    pub sale_type: Option<String>,
+    pub mismatches: u64,
+    /// The total discount given by the provider minus the discount we expected, for the mismatches where the provider
+    /// reported its discount.
+    pub discount_difference_cents: i64,
+    /// All distinct sale labels which were part of the mismatches.
+    pub sale_labels: Vec<String>,
+}
+
+/// Compare the tally of the current cart of the given user with the tally of the remote cart of `provider`.
+///
+/// If both carts contain the same products then all mismatching sales are logged in the `sale_mismatches` table,
+/// replacing the mismatches which were logged for this cart before.
+/// The remote cart of the account the user linked for `provider` is used, see [user_cart_for] for when the server-wide
+/// account is used instead.
+///
+/// [user_cart_for]: crate::api::accounts::user_cart_for
+pub async fn reconcile_cart(
+    state: &AppState,
+    user: &AuthContext,
+    provider: Provider,
+) -> GraphqlResult<ReconciliationReport> {
+    let user_id = user.id;
+    let account = crate::api::accounts::user_cart_for(state, user, provider).await?;
+    let provider_id = state.provider_id_from_provider(&provider);
+
+    let cart = db::cart::get_active_cart_for_user(user_id, &state.db).await?;
+    let local = sync::sum_quantities(
+        service::get_products_from_provider(&state.db, cart.id, provider_id)
+            .await?
+            .into_iter()
+            .map(|item| (item.product_id, item.quantity)),
+    );
+    let tally = service::calculate_tallies(&state.db, cart.id, state)
+        .await?
+        .remove(&provider)
+        .unwrap_or_default();
+    let sale_ids = tally
+        .sale_groups
+        .iter()
+        .map(|group| group.sale_id.as_str())
+        .collect::<Vec<_>>();
+
+    let (remote_contents, remote) = match &account {
+        Some(account) => futures::future::try_join(account.cart_contents(), account.cart_tally(&sale_ids)).await?,
+        None => {
+            futures::future::try_join(
+                state.providers.cart_contents(provider),
+                state.providers.cart_tally(provider, &sale_ids),
+            )
+            .await?
+        }
+    };
+    let remote_contents =
+        sync::sum_quantities(remote_contents.into_iter().map(|item| (item.product_id, item.quantity)));
+
+    let sales = reconcile_sales(&tally.sale_groups, &remote);
+
+    let report = ReconciliationReport {
+        provider,
+        contents_match: local == remote_contents,
+        expected_price_cents: tally.price_info.price(),
+        actual_price_cents: remote.total_price,
+        expected_deposit_cents: tally.price_info.deposit,
+        actual_deposit_cents: remote.deposit,
+        sales,
+    };
+
+    if report.contents_match {
+        log_mismatches(state, cart.id, provider_id, &report).await?;
+    } else {
+        tracing::debug!(user_id, %provider, "Not logging sale mismatches, the remote cart has different contents");
+    }
+
+    Ok(report)
+}
+
+/// Replace the logged mismatches of the given cart with the mismatching sales in `report`.
+async fn log_mismatches(
+    state: &AppState,
+    cart_id: DbId,
+    provider_id: DbId,
+    report: &ReconciliationReport,
+) -> GraphqlResult<()> {
+    let tx = state.db.begin().await?;
+
+    db::sale_mismatches::Entity::delete_many()
+        .filter(db::sale_mismatches::for_cart(cart_id, provider_id))
+        .exec(&tx)
+        .await?;
+
+    let to_insert = report
+        .sales
+        .iter()
+        .filter(|sale| !sale.matches)
+        .map(|sale| db::sale_mismatches::ActiveModel {
+            cart_id: cart_id.into_active_value(),
+            provider_id: provider_id.into_active_value(),
+            sale_id: sale.sale_id.clone().into_active_value(),
+            sale_label: sale.sale_info.label.clone().into_active_value(),
+            sale_type: sale
+                .sale_info
+                .sale_type
+                .as_ref()
+                .map(|sale_type| sale_type.kind().to_string())
+                .into_active_value(),
+            expected_applied: sale.expected_applied.into_active_value(),
+            actual_applied: sale.actual_applied.into_active_value(),
+            expected_discount: (sale.expected_discount_cents as i32).into_active_value(),
+            actual_discount: sale
+                .actual_discount_cents
+                .map(|discount| discount as i32)
+                .into_active_value(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+
+    if !to_insert.is_empty() {
+        tracing::info!(cart_id, provider = %report.provider, mismatches = to_insert.len(), "Logging sale mismatches");
+        db::sale_mismatches::Entity::insert_many(to_insert).exec(&tx).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Summarise all logged sale mismatches per provider and kind of sale, optionally only for the given `provider`.
+pub async fn sale_mismatch_summaries(
+    state: &AppState,
+    provider: Option<Provider>,
+) -> GraphqlResult<Vec<SaleMismatchSummary>> {
+    let mut query = db::sale_mismatches::Entity::find();
+
+    if let Some(provider) = provider {
+        query = query.filter(db::sale_mismatches::for_provider(
+            state.provider_id_from_provider(&provider),
+        ));
+    }
+
+    let mut summaries: BTreeMap<(DbId, Option<String>), SaleMismatchSummary> = BTreeMap::new();
+
+    for mismatch in query.all(&state.db).await? {
+        let summary = summaries
+            .entry((mismatch.provider_id, mismatch.sale_type.clone()))
+            .or_insert_with(|| SaleMismatchSummary {
+                provider: state.provider_from_id(mismatch.provider_id),
+                sale_type: mismatch.sale_type,
+                mismatches: 0,
+                discount_difference_cents: 0,
+                sale_labels: Vec::new(),
+            });
+
+        summary.mismatches += 1;
+        summary.discount_difference_cents += mismatch
+            .actual_discount
+            .map(|actual| (actual - mismatch.expected_discount) as i64)
+            .unwrap_or_default();
+
+        if !summary.sale_labels.contains(&mismatch.sale_label) {
+            summary.sale_labels.push(mismatch.sale_label);
+        }
+    }
+
+    Ok(summaries.into_values().collect())
+}
+
+/// Compare every sale in our own sale `groups` with the result the provider reported in `remote`.
+///
+/// All groups of the same sale are aggregated first, as the provider's line discounts can't be attributed to
+/// individual groups.
+fn reconcile_sales(groups: &[service::SaleItemGroup], remote: &WggRemoteTally) -> Vec<SaleReconciliation> {
+    let mut sales: Vec<(&service::SaleItemGroup, bool, CentPrice, HashSet<&str>)> = Vec::new();
+
+    for group in groups {
+        let index = match sales.iter().position(|(first, ..)| first.sale_id == group.sale_id) {
+            Some(index) => index,
+            None => {
+                sales.push((group, false, 0, HashSet::new()));
+                sales.len() - 1
+            }
+        };
+        let (_, applied, discount, product_ids) = &mut sales[index];
+
+        *applied |= group.status == service::SaleGroupStatus::Applied;
+        *discount += group
+            .price_info
+            .original_price
+            .saturating_sub(group.price_info.display_price);
+        product_ids.extend(group.items.iter().map(|item| item.item.id.as_str()));
+    }
+
+    sales
+        .into_iter()
+        .map(|(group, expected_applied, expected_discount_cents, product_ids)| {
+            let (actual_applied, actual_discount_cents) = actual_sale_result(&group.sale_id, &product_ids, remote);
+
+            SaleReconciliation {
+                sale_id: group.sale_id.clone(),
+                sale_info: group.sale_info.clone(),
+                expected_applied,
+                actual_applied,
+                expected_discount_cents,
+                actual_discount_cents,
+                matches: actual_applied.is_none_or(|applied| applied == expected_applied)
+                    && actual_discount_cents.is_none_or(|discount| discount == expected_discount_cents),
+            }
+        })
+        .collect()
+}
+
+/// Determine whether the provider applied the given sale, and the discount it gave.
+///
+/// The prices of the remote lines are preferred, as not every provider reports the savings of its promotions.
+fn actual_sale_result(
+    sale_id: &str,
+    product_ids: &HashSet<&str>,
+    remote: &WggRemoteTally,
+) -> (Option<bool>, Option<CentPrice>) {
+    let promotion = remote.promotions.iter().find(|promotion| promotion.sale_id == sale_id);
+
+    let line_discount = (!remote.lines.is_empty()).then(|| {
+        remote
+            .lines
+            .iter()
+            .filter(|line| product_ids.contains(line.product_id.as_str()))
+            .map(|line| line.original_price.saturating_sub(line.display_price))
+            .sum::<CentPrice>()
+    });
+    let discount = line_discount.or_else(|| promotion.and_then(|promotion| promotion.savings));
+    let applied = promotion
+        .map(|promotion| promotion.applied)
+        .or_else(|| line_discount.map(|discount| discount > 0));
+
+    (applied, discount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{actual_sale_result, reconcile_sales};
+    use crate::api::cart::service::{ProductWithQuantity, SaleGroupStatus, SaleItemGroup};
+    use chrono::Utc;
+    use std::collections::HashSet;
+    use wgg_providers::models::{
+        CentPrice, PriceInfo, Provider, SaleInformation, SaleValidity, UnitQuantity, WggOrderLine, WggRemotePromotion,
+        WggRemoteTally, WggSearchProduct,
+    };
+
+    fn line(product_id: &str, quantity: u32, original_price: CentPrice, display_price: CentPrice) -> WggOrderLine {
+        WggOrderLine {
+            product_id: product_id.to_string(),
+            quantity,
+            original_price,
+            display_price,
+        }
+    }
+
+    fn group(status: SaleGroupStatus, product_id: &str, quantity: u32, discount: CentPrice) -> SaleItemGroup {
+        let item = WggSearchProduct {
+            id: product_id.to_string(),
+            name: product_id.to_string(),
+            price_info: PriceInfo {
+                display_price: 100,
+                original_price: 100,
+                unit_price: None,
+                deposit: None,
+            },
+            unit_quantity: UnitQuantity::default(),
+            gtin: None,
+            unavailable_details: None,
+            image_url: None,
+            decorators: Vec::new(),
+            sale_information: None,
+            provider: Provider::PICNIC,
+        };
+
+        SaleItemGroup {
+            sale_id: "sale".to_string(),
+            provider: Provider::PICNIC,
+            sale_info: SaleInformation {
+                id: None,
+                label: "1 + 1 gratis".to_string(),
+                additional_label: Vec::new(),
+                sale_validity: SaleValidity {
+                    valid_from: Utc::now(),
+                    valid_until: Utc::now(),
+                },
+                sale_type: None,
+            },
+            status,
+            missing_quantity: 0,
+            price_info: PriceInfo {
+                display_price: 100 * quantity - discount,
+                original_price: 100 * quantity,
+                unit_price: None,
+                deposit: None,
+            },
+            items: vec![ProductWithQuantity { quantity, item }],
+        }
+    }
+
+    #[test]
+    fn test_actual_sale_result() {
+        let promotion = WggRemotePromotion {
+            sale_id: "sale".to_string(),
+            applied: true,
+            missing_quantity: Some(0),
+            savings: None,
+        };
+        let product_ids = HashSet::from(["1", "2"]);
+
+        // Only promotion progress, like Jumbo
+        let remote = WggRemoteTally {
+            lines: Vec::new(),
+            total_price: None,
+            deposit: None,
+            promotions: vec![promotion],
+        };
+        assert_eq!(actual_sale_result("sale", &product_ids, &remote), (Some(true), None));
+        assert_eq!(actual_sale_result("other", &product_ids, &remote), (None, None));
+
+        // Only line prices, like Picnic
+        let remote = WggRemoteTally {
+            lines: vec![line("1", 1, 200, 100), line("2", 1, 200, 200), line("3", 1, 300, 100)],
+            total_price: Some(400),
+            deposit: None,
+            promotions: Vec::new(),
+        };
+        assert_eq!(
+            actual_sale_result("sale", &product_ids, &remote),
+            (Some(true), Some(100))
+        );
+        assert_eq!(
+            actual_sale_result("sale", &HashSet::from(["2"]), &remote),
+            (Some(false), Some(0))
+        );
+    }
+
+    #[test]
+    fn test_reconcile_partial_sale() {
+        // Three units of a `1 + 1` sale, one pair is complete and a single unit is left over.
+        let groups = [
+            group(SaleGroupStatus::Applied, "1", 2, 100),
+            group(SaleGroupStatus::MissingItems, "1", 1, 0),
+        ];
+        let remote = WggRemoteTally {
+            lines: vec![line("1", 3, 300, 200)],
+            total_price: Some(200),
+            deposit: None,
+            promotions: Vec::new(),
+        };
+
+        let sales = reconcile_sales(&groups, &remote);
+
+        assert_eq!(sales.len(), 1);
+        assert!(sales[0].expected_applied);
+        assert_eq!(sales[0].expected_discount_cents, 100);
+        assert_eq!(sales[0].actual_discount_cents, Some(100));
+        assert!(sales[0].matches);
+
+        // The provider not applying the sale at all is still a mismatch.
+        let remote = WggRemoteTally {
+            lines: vec![line("1", 3, 300, 300)],
+            ..remote
+        };
+        let sales = reconcile_sales(&groups, &remote);
+
+        assert_eq!(sales[0].actual_applied, Some(false));
+        assert!(!sales[0].matches);
+    }
+}
@@ -0,0 +1,248 @@
+use itertools::Itertools;
+use std::collections::HashMap;
+use wgg_providers::models::{CentPrice, SaleResolutionStrategy};
+
+/// The maximum amount of units within a single sale for which the exact solver is used.
+///
+/// The exact solver considers every possible grouping, which grows exponentially with the amount of units.
+/// Larger sales fall back to [greedy_groups].
+const MAX_EXACT_UNITS: usize = 12;
+
+/// Partition the given units into sale groups of `group_size` the same way the provider would.
+///
+/// As many groups as possible are formed, any leftover units are not part of a group.
+///
+/// # Arguments
+///
+/// * `prices` - The original price of every individual unit participating in the sale.
+/// * `group_price` - Calculates the final price of a single group, takes the prices of the units within said group
+///   sorted in ascending order.
+///
+/// # Returns
+///
+/// All groups, as indices into `prices`.
+pub fn resolve_groups(
+    prices: &[CentPrice],
+    group_size: usize,
+    strategy: SaleResolutionStrategy,
+    group_price: &impl Fn(&[CentPrice]) -> CentPrice,
+) -> Vec<Vec<usize>> {
+    if group_size == 0 || prices.len() < group_size {
+        return Vec::new();
+    }
+
+    if prices.len() <= MAX_EXACT_UNITS {
+        ExactSolver::new(prices, group_size, strategy, group_price).solve()
+    } else {
+        greedy_groups(prices, group_size, strategy)
+    }
+}
+
+/// Sort the units on price and fill groups from the front.
+///
+/// This is only exact when the discount of a group is linear in the price of its units, but is very cheap to calculate.
+pub fn greedy_groups(prices: &[CentPrice], group_size: usize, strategy: SaleResolutionStrategy) -> Vec<Vec<usize>> {
+    let sorted = (0..prices.len())
+        .sorted_by(|&a, &b| match strategy {
+            SaleResolutionStrategy::Opportunistic => prices[b].cmp(&prices[a]),
+            SaleResolutionStrategy::Pessimistic => prices[a].cmp(&prices[b]),
+        })
+        .collect_vec();
+
+    sorted.chunks_exact(group_size).map(|chunk| chunk.to_vec()).collect()
+}
+
+/// Find the grouping with the largest ([SaleResolutionStrategy::Opportunistic]) or smallest
+/// ([SaleResolutionStrategy::Pessimistic]) total discount through a memoized search over all groupings.
+struct ExactSolver<'a, F> {
+    prices: &'a [CentPrice],
+    group_size: usize,
+    /// The amount of units which won't be part of any group.
+    leftover: usize,
+    strategy: SaleResolutionStrategy,
+    group_price: &'a F,
+    /// Keyed on `(processed_units_mask, skipped_units)`.
+    memo: HashMap<(u32, usize), Option<(CentPrice, Choice)>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Choice {
+    /// The first unprocessed unit is not part of any group.
+    Skip,
+    /// The first unprocessed unit is part of a group with the given mask.
+    Group(u32),
+}
+
+impl<'a, F: Fn(&[CentPrice]) -> CentPrice> ExactSolver<'a, F> {
+    fn new(prices: &'a [CentPrice], group_size: usize, strategy: SaleResolutionStrategy, group_price: &'a F) -> Self {
+        Self {
+            prices,
+            group_size,
+            leftover: prices.len() % group_size,
+            strategy,
+            group_price,
+            memo: HashMap::new(),
+        }
+    }
+
+    fn solve(mut self) -> Vec<Vec<usize>> {
+        let mut result = Vec::new();
+        let mut processed = 0u32;
+        let mut skipped = 0;
+
+        self.best(processed, skipped);
+
+        while let Some(Some((_, choice))) = self.memo.get(&(processed, skipped)).copied() {
+            match choice {
+                Choice::Skip => {
+                    processed |= 1 << self.first_unprocessed(processed);
+                    skipped += 1;
+                }
+                Choice::Group(group) => {
+                    processed |= group;
+                    result.push(mask_to_indices(group));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Calculate the best total discount for all units not yet in `processed`.
+    ///
+    /// Returns `None` if no valid grouping exists.
+    fn best(&mut self, processed: u32, skipped: usize) -> Option<CentPrice> {
+        let all_units = (1u32 << self.prices.len()) - 1;
+
+        if processed == all_units {
+            return Some(0);
+        }
+
+        if let Some(result) = self.memo.get(&(processed, skipped)) {
+            return result.map(|(discount, _)| discount);
+        }
+
+        let mut best: Option<(CentPrice, Choice)> = None;
+        let first = self.first_unprocessed(processed);
+
+        let skip_discount = if skipped < self.leftover {
+            self.best(processed | (1 << first), skipped + 1)
+        } else {
+            None
+        };
+
+        if let Some(discount) = skip_discount {
+            best = self.pick(best, (discount, Choice::Skip));
+        }
+
+        let candidates = (first + 1..self.prices.len())
+            .filter(|&i| processed & (1 << i) == 0)
+            .collect_vec();
+
+        for others in candidates.into_iter().combinations(self.group_size - 1) {
+            let group = others.iter().fold(1u32 << first, |mask, &i| mask | (1 << i));
+
+            if let Some(discount) = self.best(processed | group, skipped) {
+                let total = discount + self.group_discount(group);
+                best = self.pick(best, (total, Choice::Group(group)));
+            }
+        }
+
+        self.memo.insert((processed, skipped), best);
+
+        best.map(|(discount, _)| discount)
+    }
+
+    fn pick(&self, current: Option<(CentPrice, Choice)>, new: (CentPrice, Choice)) -> Option<(CentPrice, Choice)> {
+        match current {
+            Some(current) => {
+                let is_better = match self.strategy {
+                    SaleResolutionStrategy::Opportunistic => new.0 > current.0,
+                    SaleResolutionStrategy::Pessimistic => new.0 < current.0,
+                };
+
+                Some(if is_better { new } else { current })
+            }
+            None => Some(new),
+        }
+    }
+
+    fn group_discount(&self, group: u32) -> CentPrice {
+        let prices = mask_to_indices(group)
+            .into_iter()
+            .map(|i| self.prices[i])
+            .sorted()
+            .collect_vec();
+        let original_price: CentPrice = prices.iter().sum();
+
+        original_price.saturating_sub((self.group_price)(&prices))
+    }
+
+    fn first_unprocessed(&self, processed: u32) -> usize {
+        (!processed).trailing_zeros() as usize
+    }
+}
+
+fn mask_to_indices(mask: u32) -> Vec<usize> {
+    (0..u32::BITS as usize).filter(|i| mask & (1 << i) != 0).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `1 + 1 free`, where the cheapest product of every pair is free.
+    fn one_plus_one(prices: &[CentPrice]) -> CentPrice {
+        prices.iter().sum::<CentPrice>() - prices[0]
+    }
+
+    fn total_discount(prices: &[CentPrice], groups: &[Vec<usize>]) -> CentPrice {
+        groups
+            .iter()
+            .map(|group| {
+                let group_prices = group.iter().map(|&i| prices[i]).sorted().collect_vec();
+                group_prices.iter().sum::<CentPrice>() - one_plus_one(&group_prices)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_exact_pessimistic_beats_greedy() {
+        let prices = [100, 200, 300, 400];
+
+        let greedy = greedy_groups(&prices, 2, SaleResolutionStrategy::Pessimistic);
+        let exact = resolve_groups(&prices, 2, SaleResolutionStrategy::Pessimistic, &one_plus_one);
+
+        // Greedy pairs (100, 200) and (300, 400), whereas pairing (100, 400) and (200, 300) is cheaper for the
+        // provider.
+        assert_eq!(total_discount(&prices, &greedy), 400);
+        assert_eq!(total_discount(&prices, &exact), 300);
+    }
+
+    #[test]
+    fn test_exact_opportunistic() {
+        let prices = [100, 400, 200, 300, 50];
+
+        let groups = resolve_groups(&prices, 2, SaleResolutionStrategy::Opportunistic, &one_plus_one);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(total_discount(&prices, &groups), 400);
+    }
+
+    #[test]
+    fn test_too_few_units() {
+        let groups = resolve_groups(&[100, 200], 3, SaleResolutionStrategy::Opportunistic, &one_plus_one);
+
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_large_sale_uses_fallback() {
+        let prices = vec![100; MAX_EXACT_UNITS + 3];
+
+        let groups = resolve_groups(&prices, 2, SaleResolutionStrategy::Pessimistic, &one_plus_one);
+
+        assert_eq!(groups.len(), (MAX_EXACT_UNITS + 3) / 2);
+        assert!(groups.iter().all(|group| group.len() == 2));
+    }
+}
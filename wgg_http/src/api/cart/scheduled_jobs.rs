@@ -1,7 +1,8 @@
 use crate::api::{AppState, ProductId};
 use crate::db;
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
-use std::collections::VecDeque;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use std::collections::{BTreeSet, VecDeque};
+use wgg_db_entity::DbId;
 use wgg_providers::models::Provider;
 use wgg_scheduler::schedule::Schedule;
 use wgg_scheduler::Job;
@@ -30,6 +31,65 @@ pub fn create_job_keep_cart_data_fresh(schedule: Schedule, state: AppState) -> J
     .unwrap()
 }
 
+/// Import the delivered orders of all providers which support it.
+///
+/// See [import_delivered_orders](super::import::import_delivered_orders).
+///
+/// Orders are imported for every user who linked an account, and for the first admin from the server-wide account.
+pub fn create_job_import_delivered_orders(schedule: Schedule, state: AppState) -> Job {
+    Job::new(schedule, move |_, _| {
+        let state = state.clone();
+        async move {
+            let span = tracing::span!(tracing::Level::DEBUG, "Scheduled Job - Import Delivered Orders");
+            let _enter = span.enter();
+
+            let providers = wgg_providers::registered_providers()
+                .into_iter()
+                .filter(|provider| provider.get_metadata().supports_delivery);
+
+            for provider in providers {
+                for user_id in get_order_import_users(&state, provider).await? {
+                    if let Err(e) = super::import::import_delivered_orders(&state, user_id, provider).await {
+                        tracing::warn!(user_id, %provider, error = ?e, "Failed to import delivered orders");
+                    }
+                }
+            }
+
+            Ok(())
+        }
+    })
+    .unwrap()
+}
+
+/// Find all users for which the delivered orders of `provider` should be imported.
+async fn get_order_import_users(state: &AppState, provider: Provider) -> anyhow::Result<BTreeSet<DbId>> {
+    let provider_id = state.provider_id_from_provider(&provider);
+    let mut result: BTreeSet<DbId> = db::provider_accounts::Entity::find()
+        .filter(db::provider_accounts::for_provider(provider_id))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|account| account.user_id)
+        .collect();
+
+    // The server-wide account is considered to belong to the first admin.
+    let has_server_account = state
+        .providers
+        .active_providers()
+        .any(|active| active.provider() == provider);
+    if has_server_account {
+        let first_admin = db::users::Entity::find()
+            .filter(db::users::Column::IsAdmin.eq(true))
+            .order_by_asc(db::users::Column::Id)
+            .one(&state.db)
+            .await?;
+
+        result.extend(first_admin.map(|admin| admin.id));
+    }
+
+    Ok(result)
+}
+
 async fn get_all_cart_products(
     queue: &mut VecDeque<(Provider, ProductId)>,
     db: &impl ConnectionTrait,
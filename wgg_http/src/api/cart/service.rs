@@ -1,3 +1,4 @@
+use crate::api::cart::sale_grouping;
 use crate::api::error::GraphqlError;
 use crate::api::{AppState, GraphqlResult, ProductId};
 use crate::db;
@@ -6,7 +7,7 @@ use std::collections::HashMap;
 use wgg_db_entity::DbId;
 use wgg_providers::models::sale_types::SaleType;
 use wgg_providers::models::{
-    CentPrice, PriceInfo, Provider, SaleInformation, SaleResolutionStrategy, SublistId, WggSearchProduct,
+    CentPrice, PriceInfo, Provider, ProviderMetadata, SaleInformation, SublistId, WggSearchProduct,
 };
 
 /// Get the direct quantity (ignoring Aggregate products) of the given product in the given cart.
@@ -103,15 +104,48 @@ pub async fn get_products_from_provider(
 }
 
 /// Calculate the total tally of the given cart for all providers that are part of that cart.
+///
+/// The tally of each provider also contains the sale groups which were resolved for that provider.
 #[tracing::instrument(skip(db, state))]
 pub async fn calculate_tallies(
     db: &impl ConnectionTrait,
     cart_id: DbId,
     state: &AppState,
-) -> GraphqlResult<HashMap<Provider, TallyPriceInfo>> {
-    let mut result: HashMap<Provider, TallyPriceInfo> = HashMap::with_capacity(state.db_providers.len());
-    let mut sale_items: HashMap<SublistId, SaleTracking> = HashMap::new();
+) -> GraphqlResult<HashMap<Provider, ProviderTally>> {
+    let products = get_cart_products(db, cart_id, state).await?;
+    let (tallies, sale_groups) =
+        tally_products_with_sales(state, products.iter().map(|entry| (&entry.product, entry.quantity)))?;
+
+    let mut result: HashMap<Provider, ProviderTally> = tallies
+        .into_iter()
+        .map(|(provider, price_info)| {
+            (
+                provider,
+                ProviderTally {
+                    price_info,
+                    sale_groups: Vec::new(),
+                },
+            )
+        })
+        .collect();
 
+    for group in sale_groups {
+        if let Some(tally) = result.get_mut(&group.provider) {
+            tally.sale_groups.push(group);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Retrieve all (direct and aggregate) products in the given cart.
+///
+/// Note that an aggregate product will return an entry for every provider product linked to that aggregate.
+pub async fn get_cart_products(
+    db: &impl ConnectionTrait,
+    cart_id: DbId,
+    state: &AppState,
+) -> GraphqlResult<Vec<CartProductEntry>> {
     let products = db::cart_contents::raw_product::Entity::find()
         .filter(db::cart_contents::raw_product::Column::CartId.eq(cart_id))
         .all(db);
@@ -122,8 +156,61 @@ pub async fn calculate_tallies(
         .all(db);
 
     let (products, aggregate) = futures::future::try_join(products, aggregate).await?;
+    let mut result = Vec::with_capacity(products.len() + aggregate.len());
+
+    for product in products {
+        let provider = state.provider_from_id(product.provider_id);
+
+        let search_product = state
+            .providers
+            .search_product(provider, &product.provider_product)
+            .await?;
+
+        result.push(CartProductEntry {
+            source: CartEntrySource::Product(product.id),
+            quantity: product.quantity as u32,
+            product: search_product,
+        });
+    }
 
-    let mut add_sale_item = |search_product: WggSearchProduct, quantity: u32| {
+    for (agg_ingredient, products) in aggregate {
+        for product in products {
+            let provider = state.provider_from_id(product.provider_id);
+            let search_product = state
+                .providers
+                .search_product(provider, &product.provider_ingr_id)
+                .await?;
+
+            result.push(CartProductEntry {
+                source: CartEntrySource::Aggregate(agg_ingredient.id),
+                quantity: agg_ingredient.quantity as u32,
+                product: search_product,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Calculate the tally of all the given products, taking into account any sales applicable to the products.
+pub fn tally_products<'a>(
+    state: &AppState,
+    products: impl IntoIterator<Item = (&'a WggSearchProduct, u32)>,
+) -> GraphqlResult<HashMap<Provider, TallyPriceInfo>> {
+    Ok(tally_products_with_sales(state, products)?.0)
+}
+
+/// Calculate the tally of all the given products, and return all the sale groups which were considered.
+///
+/// See [tally_products].
+pub fn tally_products_with_sales<'a>(
+    state: &AppState,
+    products: impl IntoIterator<Item = (&'a WggSearchProduct, u32)>,
+) -> GraphqlResult<(HashMap<Provider, TallyPriceInfo>, Vec<SaleItemGroup>)> {
+    let mut result: HashMap<Provider, TallyPriceInfo> = HashMap::with_capacity(state.db_providers.len());
+    let mut sale_items: HashMap<SublistId, SaleTracking> = HashMap::new();
+
+    let mut add_sale_item = |search_product: &WggSearchProduct, quantity: u32| {
         let Some(sale) = &search_product.sale_information else {
             return Ok(());
         };
@@ -144,7 +231,8 @@ pub async fn calculate_tallies(
                     item: search_product.clone(),
                 })
             })
-            .or_insert_with(|| SaleTracking {
+            .or_insert_with_key(|sale_id| SaleTracking {
+                sale_id: sale_id.clone(),
                 items: vec![ProductWithQuantity {
                     quantity,
                     item: search_product.clone(),
@@ -155,59 +243,112 @@ pub async fn calculate_tallies(
         Ok(())
     };
 
-    for product in products {
-        let provider = state.provider_from_id(product.provider_id);
-
-        let search_product = state
-            .providers
-            .search_product(provider, &product.provider_product)
-            .await?;
-
-        let original_price = product.quantity as u32 * search_product.price_info.original_price;
+    for (search_product, quantity) in products {
+        let original_price = quantity * search_product.price_info.original_price;
+        let deposit = quantity * search_product.price_info.deposit.unwrap_or_default();
 
         // Handle sale look-up.
-        if let Err(e) = add_sale_item(search_product, product.quantity as u32) {
+        if let Err(e) = add_sale_item(search_product, quantity) {
             tracing::warn!(?e, "Failed to handle sale item")
         }
 
-        result
-            .entry(provider)
-            .and_modify(|tally| tally.original_price += original_price)
-            .or_insert(TallyPriceInfo {
-                original_price,
-                discount: 0,
-            });
+        let tally = result.entry(search_product.provider).or_default();
+        tally.original_price += original_price;
+        tally.deposit += deposit;
     }
 
-    for (agg_ingredient, products) in aggregate {
-        for product in products {
-            let provider = state.provider_from_id(product.provider_id);
-            let search_product = state
-                .providers
-                .search_product(provider, &product.provider_ingr_id)
-                .await?;
+    let sale_groups = handle_sale_logic(&mut result, sale_items)?;
 
-            let original_price = agg_ingredient.quantity as u32 * search_product.price_info.original_price;
+    // Fees depend on the final (discounted) price, so they can only be determined at the very end.
+    for (provider, tally) in result.iter_mut() {
+        tally.apply_fees(&provider.get_metadata());
+    }
 
-            // Handle sale look-up.
-            if let Err(e) = add_sale_item(search_product, agg_ingredient.quantity as u32) {
-                tracing::warn!(?e, "Failed to handle aggregate item")
-            }
+    Ok((result, sale_groups))
+}
 
-            result
-                .entry(provider)
-                .and_modify(|tally| tally.original_price += original_price)
-                .or_insert(TallyPriceInfo {
-                    original_price,
-                    discount: 0,
-                });
+/// Determine which sales the cart *almost* qualifies for, based on the [SaleGroupStatus::MissingItems] groups of the
+/// given tallies.
+///
+/// For every such sale the cheapest product participating in the sale is suggested as an addition to the cart.
+/// The exception are [SaleType::MinimumQuantity] sales, whose minimum applies to every product on its own.
+/// For those only more of the product already in the cart can unlock the sale, so that product is suggested instead.
+#[tracing::instrument(skip_all)]
+pub async fn calculate_sale_hints(state: &AppState, tallies: &HashMap<Provider, ProviderTally>) -> Vec<SaleHint> {
+    let almost_qualifying = tallies
+        .values()
+        .flat_map(|tally| &tally.sale_groups)
+        .filter(|group| group.status == SaleGroupStatus::MissingItems);
+
+    let hints = almost_qualifying.map(|group| async move {
+        let suggestion = if let Some(SaleType::MinimumQuantity(_)) = group.sale_info.sale_type {
+            // Groups of a minimum quantity sale always consist of a single product.
+            group.items.first().map(|product| product.item.clone())
+        } else {
+            cheapest_sale_product(state, group).await
+        };
+
+        let potential_discount = suggestion
+            .as_ref()
+            .map(|suggestion| potential_sale_discount(group, suggestion))
+            .unwrap_or_default();
+
+        SaleHint {
+            group: group.clone(),
+            suggestion,
+            potential_discount,
+        }
+    });
+
+    futures::future::join_all(hints).await
+}
+
+/// Retrieve the cheapest available product participating in the sale of the given `group`.
+async fn cheapest_sale_product(state: &AppState, group: &SaleItemGroup) -> Option<WggSearchProduct> {
+    match state.providers.promotions_sublist(group.provider, &group.sale_id).await {
+        Ok(sublist) => sublist
+            .items
+            .into_iter()
+            .filter(|product| product.unavailable_details.is_none())
+            .min_by_key(|product| product.price_info.display_price),
+        Err(e) => {
+            tracing::warn!(
+                ?e,
+                sale_id = %group.sale_id,
+                "Failed to retrieve sale sublist for sale hint"
+            );
+            None
         }
     }
+}
 
-    // TODO: Expose the sale item groups for the front-end to beautify!
-    let _ = handle_sale_logic(&mut result, sale_items)?;
+/// Calculate the discount which would be given if the `missing_quantity` of `suggestion` were added to the `group`.
+fn potential_sale_discount(group: &SaleItemGroup, suggestion: &WggSearchProduct) -> CentPrice {
+    let mut items = group.items.clone();
 
-    Ok(result)
+    match items.iter_mut().find(|product| product.item.id == suggestion.id) {
+        Some(product) => product.quantity += group.missing_quantity,
+        None => items.push(ProductWithQuantity {
+            quantity: group.missing_quantity,
+            item: suggestion.clone(),
+        }),
+    }
+
+    let tracking = SaleTracking {
+        sale_id: group.sale_id.clone(),
+        items,
+        sale_info: group.sale_info.clone(),
+        provider: group.provider,
+    };
+    let mut tally_map = HashMap::from([(group.provider, TallyPriceInfo::default())]);
+
+    match handle_sale_logic(&mut tally_map, HashMap::from([(group.sale_id.clone(), tracking)])) {
+        Ok(_) => tally_map[&group.provider].discount,
+        Err(e) => {
+            tracing::warn!(?e, "Failed to calculate potential sale discount");
+            0
+        }
+    }
 }
 
 fn handle_sale_logic(
@@ -217,8 +358,9 @@ fn handle_sale_logic(
     let mut item_group_results = Vec::new();
 
     for (_, sale) in sale_items {
-        let Some(sale_type) = sale.sale_info.sale_type else {
+        let Some(sale_type) = sale.sale_info.sale_type.clone() else {
             // Can't do anything, assume the pessimistic original price
+            item_group_results.push(SaleItemGroup::unapplied(sale, SaleGroupStatus::UnknownSaleType, 0));
             continue;
         };
 
@@ -227,42 +369,88 @@ fn handle_sale_logic(
                 let required = data.required.get() as u32;
                 let total_required = required + data.free.get() as u32;
 
-                handle_group(
-                    tally_map,
-                    &mut item_group_results,
-                    sale.provider,
-                    total_required,
-                    sale.items,
-                    |total_original_price| (total_original_price / total_required) * required,
-                );
+                let free = data.free.get() as usize;
+
+                // The cheapest items of a group are the free ones.
+                handle_group(tally_map, &mut item_group_results, sale, total_required, |prices| {
+                    prices.iter().skip(free).sum()
+                });
             }
             SaleType::NumthPercentOff(data) => {
-                let required = data.required.get() as u32;
-                let total_required = required;
-
-                handle_group(
-                    tally_map,
-                    &mut item_group_results,
-                    sale.provider,
-                    total_required,
-                    sale.items,
-                    |total_original_price| {
-                        total_original_price
-                            - ((total_original_price * data.last_percent_off.get() as u32) / (100 * required))
-                    },
-                );
+                let total_required = data.required.get() as u32;
+                let percent_off = data.last_percent_off.get() as u32;
+
+                // The cheapest item of a group is the discounted one.
+                handle_group(tally_map, &mut item_group_results, sale, total_required, |prices| {
+                    prices.iter().sum::<CentPrice>() - (prices[0] * percent_off) / 100
+                });
             }
             SaleType::NumForPrice(data) => {
                 let total_required = data.required.get() as u32;
 
-                handle_group(
-                    tally_map,
-                    &mut item_group_results,
-                    sale.provider,
-                    total_required,
-                    sale.items,
-                    |_| data.price,
-                );
+                // A group is never more expensive than its separate products.
+                handle_group(tally_map, &mut item_group_results, sale, total_required, |prices| {
+                    data.price.min(prices.iter().sum())
+                });
+            }
+            SaleType::NumForPercentOff(data) => {
+                let total_required = data.required.get() as u32;
+                let percent_off = data.get_percent_off().get() as u32;
+
+                // The discount applies to every item in the group.
+                handle_group(tally_map, &mut item_group_results, sale, total_required, |prices| {
+                    let total: CentPrice = prices.iter().sum();
+                    total - (total * percent_off) / 100
+                });
+            }
+            SaleType::StackingDiscount(data) => {
+                let Some(first_tier) = data.tiers.first() else {
+                    // Without tiers we can't know the discount, assume the pessimistic original price
+                    item_group_results.push(SaleItemGroup::unapplied(sale, SaleGroupStatus::UnknownSaleType, 0));
+                    continue;
+                };
+                let total_quantity = sale.items.iter().map(|product| product.quantity).sum();
+                // The highest tier reached applies to all items, if no tier is reached we'll report the first.
+                let (total_required, percent_off) = match data.tier_for(total_quantity) {
+                    Some(tier) => (tier.required.get() as u32, tier.percent_off.get() as u32),
+                    None => (first_tier.required.get() as u32, 0),
+                };
+
+                handle_whole_sale(tally_map, &mut item_group_results, sale, total_required, |product| {
+                    let original_price = product.item.price_info.original_price * product.quantity;
+                    original_price - (original_price * percent_off) / 100
+                });
+            }
+            SaleType::MinimumQuantity(data) => {
+                let total_required = data.required.get() as u32;
+
+                // The minimum quantity applies to every product on its own, and the `display_price` is only the sale
+                // price once said product's minimum quantity has been reached.
+                for product in sale.items {
+                    let product_sale = SaleTracking {
+                        sale_id: sale.sale_id.clone(),
+                        items: vec![product],
+                        sale_info: sale.sale_info.clone(),
+                        provider: sale.provider,
+                    };
+
+                    handle_whole_sale(
+                        tally_map,
+                        &mut item_group_results,
+                        product_sale,
+                        total_required,
+                        |product| product.item.price_info.display_price * product.quantity,
+                    );
+                }
+            }
+            SaleType::PricePerUnit(data) => {
+                // Weighted products are priced on their own quantity, other units can only trust the `display_price`.
+                handle_whole_sale(tally_map, &mut item_group_results, sale, 1, |product| {
+                    let price_info = &product.item.price_info;
+                    let unit_price = data.price_for(&product.item.unit_quantity);
+
+                    unit_price.unwrap_or(price_info.display_price) * product.quantity
+                });
             }
             SaleType::NumPercentOff(_) | SaleType::NumEuroPrice(_) | SaleType::NumEuroOff(_) => {
                 // Since this is a single item we can assume the `display_price` property is well preserved.
@@ -275,10 +463,16 @@ fn handle_sale_logic(
                         .and_modify(|tally| tally.discount += original_price - display_price);
 
                     let sale_group = SaleItemGroup {
+                        sale_id: sale.sale_id.clone(),
+                        provider: sale.provider,
+                        sale_info: sale.sale_info.clone(),
+                        status: SaleGroupStatus::Applied,
+                        missing_quantity: 0,
                         price_info: PriceInfo {
                             display_price,
                             original_price,
                             unit_price: None,
+                            deposit: None,
                         },
                         items: vec![ProductWithQuantity {
                             quantity: product.quantity,
@@ -297,112 +491,644 @@ fn handle_sale_logic(
 
 /// Handle a full [SaleTracking] and update the `tally_map`'s `discount`.
 ///
-/// Additionally, the groupings of the items within the `items` [Vec] are pushed to `group_results`.
+/// The items are grouped the same way the provider would (see [sale_grouping::resolve_groups]), and the groupings are
+/// pushed to `group_results`.
+/// Any remaining items which didn't qualify for the sale are pushed as a final [SaleGroupStatus::MissingItems] group.
 ///
 /// # Arguments
 ///
-/// * `final_price_calc` - Takes the `total_original_price` of all items within a [SaleItemGroup] (which is a sub-group of the overal [SaleTracking])
-/// and expects the final price of the current promotion taking into account said original price.
+/// * `group_price` - Takes the original prices of all units within a [SaleItemGroup] (which is a sub-group of the
+///   overall [SaleTracking]), sorted in ascending order, and expects the final price of said group.
 fn handle_group(
     tally_map: &mut HashMap<Provider, TallyPriceInfo>,
     group_results: &mut Vec<SaleItemGroup>,
-    provider: Provider,
+    mut sale: SaleTracking,
     total_required: u32,
-    mut items: Vec<ProductWithQuantity>,
-    mut final_price_calc: impl FnMut(CentPrice) -> CentPrice,
+    group_price: impl Fn(&[CentPrice]) -> CentPrice,
 ) {
-    let mut total_qualifying_items: u32 = items.iter().map(|product| product.quantity).sum();
-    let sort_items_on_price =
-        |a: &WggSearchProduct, b: &WggSearchProduct| a.price_info.original_price.cmp(&b.price_info.original_price);
-
-    if total_qualifying_items >= total_required {
-        match provider.get_metadata().sale_strategy {
-            SaleResolutionStrategy::Opportunistic => {
-                items.sort_by(|a, b| sort_items_on_price(&b.item, &a.item));
-            }
-            SaleResolutionStrategy::Pessimistic => {
-                items.sort_by(|a, b| sort_items_on_price(&a.item, &b.item));
-            }
-        }
-
-        while total_qualifying_items >= total_required {
-            total_qualifying_items -= total_required;
-            let mut still_required = total_required;
-
-            let item_group = items
-                .iter_mut()
-                .filter(|product| product.quantity > 0)
-                .take_while(|product| {
-                    if still_required == 0 {
-                        false
-                    } else {
-                        still_required -= still_required.min(product.quantity);
-                        true
-                    }
-                });
+    let provider = sale.provider;
+    // Every individual unit, referring to the index of its item in `sale.items`.
+    let units = sale
+        .items
+        .iter()
+        .enumerate()
+        .flat_map(|(i, product)| std::iter::repeat_n(i, product.quantity as usize))
+        .collect::<Vec<_>>();
+    let prices = units
+        .iter()
+        .map(|&i| sale.items[i].item.price_info.original_price)
+        .collect::<Vec<_>>();
 
-            let mut final_required = total_required;
-            let mut total_original_price = 0;
-            let mut items = Vec::with_capacity(total_required as usize);
+    let groups = sale_grouping::resolve_groups(
+        &prices,
+        total_required as usize,
+        provider.get_metadata().sale_strategy,
+        &group_price,
+    );
 
-            for product in item_group {
-                let used_quantity = final_required.min(product.quantity);
+    for group in groups {
+        let mut group_prices = group.iter().map(|&unit| prices[unit]).collect::<Vec<_>>();
+        group_prices.sort_unstable();
 
-                final_required -= used_quantity;
-                product.quantity -= used_quantity;
+        let total_original_price: CentPrice = group_prices.iter().sum();
+        let final_price = group_price(&group_prices);
+        let mut items: Vec<ProductWithQuantity> = Vec::with_capacity(group.len());
 
-                total_original_price += product.item.price_info.original_price * used_quantity;
+        for unit in group {
+            let product = &mut sale.items[units[unit]];
+            product.quantity -= 1;
 
-                items.push(ProductWithQuantity {
-                    quantity: used_quantity,
+            match items.iter_mut().find(|item| item.item.id == product.item.id) {
+                Some(item) => item.quantity += 1,
+                None => items.push(ProductWithQuantity {
+                    quantity: 1,
                     item: product.item.clone(),
-                });
+                }),
             }
+        }
 
-            let final_price = final_price_calc(total_original_price);
-            let sale_group = SaleItemGroup {
-                price_info: PriceInfo {
-                    display_price: final_price,
-                    original_price: total_original_price,
-                    unit_price: None,
-                },
-                items,
-            };
+        let sale_group = SaleItemGroup {
+            sale_id: sale.sale_id.clone(),
+            provider,
+            sale_info: sale.sale_info.clone(),
+            status: SaleGroupStatus::Applied,
+            missing_quantity: 0,
+            price_info: PriceInfo {
+                display_price: final_price,
+                original_price: total_original_price,
+                unit_price: None,
+                deposit: None,
+            },
+            items,
+        };
 
-            tally_map
-                .entry(provider)
-                .and_modify(|tally| tally.discount += total_original_price - final_price);
-            group_results.push(sale_group)
-        }
+        tally_map
+            .entry(provider)
+            .and_modify(|tally| tally.discount += total_original_price.saturating_sub(final_price));
+        group_results.push(sale_group)
+    }
+
+    sale.items.retain(|product| product.quantity > 0);
+
+    if !sale.items.is_empty() {
+        let remaining_quantity: u32 = sale.items.iter().map(|product| product.quantity).sum();
+
+        // Only the items needed to complete the next group are missing.
+        group_results.push(SaleItemGroup::unapplied(
+            sale,
+            SaleGroupStatus::MissingItems,
+            total_required - (remaining_quantity % total_required),
+        ));
     }
 }
 
+/// Handle a full [SaleTracking] where the sale applies to all items at once, as long as the sale contains at least
+/// `total_required` items.
+///
+/// The items are pushed to `group_results` as a single group, which is [SaleGroupStatus::MissingItems] if the sale
+/// didn't contain enough items.
+///
+/// # Arguments
+///
+/// * `item_price` - Takes a single item (with its quantity) within the sale, and expects its final price.
+fn handle_whole_sale(
+    tally_map: &mut HashMap<Provider, TallyPriceInfo>,
+    group_results: &mut Vec<SaleItemGroup>,
+    sale: SaleTracking,
+    total_required: u32,
+    item_price: impl Fn(&ProductWithQuantity) -> CentPrice,
+) {
+    let total_quantity: u32 = sale.items.iter().map(|product| product.quantity).sum();
+
+    if total_quantity < total_required {
+        group_results.push(SaleItemGroup::unapplied(
+            sale,
+            SaleGroupStatus::MissingItems,
+            total_required - total_quantity,
+        ));
+        return;
+    }
+
+    let (original_price, final_price) = sale
+        .items
+        .iter()
+        .map(|product| {
+            let original_price = product.item.price_info.original_price * product.quantity;
+            (original_price, item_price(product).min(original_price))
+        })
+        .fold((0, 0), |(original, display), (item_original, item_display)| {
+            (original + item_original, display + item_display)
+        });
+
+    tally_map
+        .entry(sale.provider)
+        .and_modify(|tally| tally.discount += original_price - final_price);
+
+    group_results.push(SaleItemGroup {
+        sale_id: sale.sale_id,
+        provider: sale.provider,
+        sale_info: sale.sale_info,
+        status: SaleGroupStatus::Applied,
+        missing_quantity: 0,
+        price_info: PriceInfo {
+            display_price: final_price,
+            original_price,
+            unit_price: None,
+            deposit: None,
+        },
+        items: sale.items,
+    });
+}
+
 struct SaleTracking {
+    sale_id: SublistId,
     items: Vec<ProductWithQuantity>,
     sale_info: SaleInformation,
     provider: Provider,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SaleItemGroup {
+    /// The sublist id of the sale, which can be used to look up all products participating in the sale.
+    pub sale_id: SublistId,
+    pub provider: Provider,
+    pub sale_info: SaleInformation,
+    pub status: SaleGroupStatus,
+    /// The amount of additional items required for this group to qualify for the sale.
+    pub missing_quantity: u32,
     pub price_info: PriceInfo,
     pub items: Vec<ProductWithQuantity>,
 }
 
+impl SaleItemGroup {
+    /// Create a group for items to which the sale was *not* applied, and which are therefore charged their full price.
+    fn unapplied(sale: SaleTracking, status: SaleGroupStatus, missing_quantity: u32) -> Self {
+        let original_price = sale
+            .items
+            .iter()
+            .map(|product| product.item.price_info.original_price * product.quantity)
+            .sum();
+
+        Self {
+            sale_id: sale.sale_id,
+            provider: sale.provider,
+            sale_info: sale.sale_info,
+            status,
+            missing_quantity,
+            price_info: PriceInfo {
+                display_price: original_price,
+                original_price,
+                unit_price: None,
+                deposit: None,
+            },
+            items: sale.items,
+        }
+    }
+}
+
+/// Whether a sale was applied to a [SaleItemGroup], and if not, why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum SaleGroupStatus {
+    /// The sale was applied to all items in the group.
+    Applied,
+    /// Not enough items are in the cart to qualify for the sale.
+    MissingItems,
+    /// The sale label couldn't be interpreted, the sale is therefore not taken into account.
+    UnknownSaleType,
+}
+
+/// A sale for which the cart *almost* qualifies.
 #[derive(Debug)]
+pub struct SaleHint {
+    /// The items in the cart which currently don't qualify for the sale.
+    pub group: SaleItemGroup,
+    /// The product to add to the cart, `None` if the sale's products couldn't be retrieved.
+    ///
+    /// Usually the cheapest product participating in the sale, see [calculate_sale_hints].
+    pub suggestion: Option<WggSearchProduct>,
+    /// The discount which would be given if the missing quantity of the `suggestion` were added to the cart.
+    pub potential_discount: CentPrice,
+}
+
+#[derive(Debug, Clone)]
 pub struct ProductWithQuantity {
     pub quantity: u32,
     pub item: WggSearchProduct,
 }
 
+/// The cart entry from which a [CartProductEntry] originated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CartEntrySource {
+    /// The id of the direct product cart entry.
+    Product(DbId),
+    /// The id of the aggregate cart entry.
+    Aggregate(DbId),
+}
+
+#[derive(Debug)]
+pub struct CartProductEntry {
+    pub source: CartEntrySource,
+    pub quantity: u32,
+    pub product: WggSearchProduct,
+}
+
 #[derive(Debug)]
 pub struct ProductIdWithQuantity {
     pub product_id: ProductId,
     pub quantity: u32,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Clone, Default)]
+pub struct ProviderTally {
+    pub price_info: TallyPriceInfo,
+    pub sale_groups: Vec<SaleItemGroup>,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
 pub struct TallyPriceInfo {
     pub original_price: CentPrice,
     pub discount: CentPrice,
+    /// The total deposit (statiegeld) of all products, not included in the `original_price`.
+    pub deposit: CentPrice,
+    pub delivery_fee: CentPrice,
+    pub service_fee: CentPrice,
+}
+
+impl TallyPriceInfo {
+    /// The price of all products after discounts, excluding deposits and fees.
+    pub fn price(&self) -> CentPrice {
+        self.original_price - self.discount
+    }
+
+    /// The full amount to be paid, including deposits and fees.
+    pub fn total_price(&self) -> CentPrice {
+        self.price() + self.deposit + self.delivery_fee + self.service_fee
+    }
+
+    /// Set the fees of this tally according to the fee schedules of the provider.
+    ///
+    /// Fees depend on the final price, so this should only be called once all discounts are known.
+    fn apply_fees(&mut self, metadata: &ProviderMetadata) {
+        let price = self.price();
+
+        self.delivery_fee = metadata.delivery_fee.fee_for(price);
+        self.service_fee = metadata.service_fee.fee_for(price);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::num::NonZeroU16;
+    use wgg_providers::models::sale_types::{
+        MinimumQuantity, NumEuroOff, NumEuroPrice, NumForPercentOff, NumForPrice, NumPercentOff, NumPlusNumFree,
+        NumthPercentOff, PricePerUnit, StackingDiscount, StackingTier,
+    };
+    use wgg_providers::models::{FeeSchedule, SaleValidity, Unit, UnitQuantity};
+
+    fn non_zero(value: u16) -> NonZeroU16 {
+        NonZeroU16::new(value).unwrap()
+    }
+
+    fn product(id: &str, price: CentPrice) -> WggSearchProduct {
+        WggSearchProduct {
+            id: id.to_string(),
+            name: id.to_string(),
+            price_info: PriceInfo {
+                display_price: price,
+                original_price: price,
+                unit_price: None,
+                deposit: None,
+            },
+            unit_quantity: UnitQuantity::default(),
+            gtin: None,
+            unavailable_details: None,
+            image_url: None,
+            decorators: Vec::new(),
+            sale_information: None,
+            provider: Provider::PICNIC,
+        }
+    }
+
+    fn sale(sale_type: SaleType, items: &[(&WggSearchProduct, u32)]) -> SaleTracking {
+        SaleTracking {
+            sale_id: "sale".to_string(),
+            items: items
+                .iter()
+                .map(|&(product, quantity)| ProductWithQuantity {
+                    quantity,
+                    item: product.clone(),
+                })
+                .collect(),
+            sale_info: SaleInformation {
+                id: None,
+                label: sale_type.kind().to_string(),
+                additional_label: Vec::new(),
+                sale_validity: SaleValidity {
+                    valid_from: Utc::now(),
+                    valid_until: Utc::now(),
+                },
+                sale_type: Some(sale_type),
+            },
+            provider: Provider::PICNIC,
+        }
+    }
+
+    /// Resolve a single sale, returning the resulting tally and sale groups.
+    fn resolve(sale: SaleTracking) -> (TallyPriceInfo, Vec<SaleItemGroup>) {
+        let mut tally_map = HashMap::from([(sale.provider, TallyPriceInfo::default())]);
+        let groups = handle_sale_logic(&mut tally_map, HashMap::from([(sale.sale_id.clone(), sale)])).unwrap();
+
+        (tally_map[&Provider::PICNIC], groups)
+    }
+
+    #[test]
+    fn test_leftover_missing_quantity() {
+        let one_plus_one = SaleType::NumPlusNumFree(NumPlusNumFree {
+            required: non_zero(1),
+            free: non_zero(1),
+        });
+        let (tally, groups) = resolve(sale(one_plus_one, &[(&product("a", 100), 3)]));
+
+        assert_eq!(tally.discount, 100);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].status, SaleGroupStatus::Applied);
+        assert_eq!(groups[1].status, SaleGroupStatus::MissingItems);
+        assert_eq!(groups[1].missing_quantity, 1);
+
+        let three_for_price = SaleType::NumForPrice(NumForPrice {
+            required: non_zero(3),
+            price: 250,
+        });
+        let (tally, groups) = resolve(sale(three_for_price, &[(&product("a", 100), 4)]));
+
+        assert_eq!(tally.discount, 50);
+        assert_eq!(groups[1].status, SaleGroupStatus::MissingItems);
+        assert_eq!(groups[1].missing_quantity, 2);
+    }
+
+    #[test]
+    fn test_potential_sale_discount() {
+        let one_plus_one = SaleType::NumPlusNumFree(NumPlusNumFree {
+            required: non_zero(1),
+            free: non_zero(1),
+        });
+        let (_, groups) = resolve(sale(one_plus_one, &[(&product("a", 100), 1)]));
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].missing_quantity, 1);
+        assert_eq!(potential_sale_discount(&groups[0], &product("b", 100)), 100);
+    }
+
+    #[test]
+    fn test_potential_sale_discount_minimum_quantity() {
+        let from_three = SaleType::MinimumQuantity(MinimumQuantity { required: non_zero(3) });
+        let mut a = product("a", 100);
+        a.price_info.display_price = 80;
+        let (_, groups) = resolve(sale(from_three, &[(&a, 1)]));
+
+        assert_eq!(groups[0].missing_quantity, 2);
+        // Only more of the same product reaches the minimum, at which point all three get the sale price.
+        assert_eq!(potential_sale_discount(&groups[0], &a), 3 * 20);
+        assert_eq!(potential_sale_discount(&groups[0], &product("b", 100)), 0);
+    }
+
+    #[test]
+    fn test_tally_fees() {
+        let metadata = ProviderMetadata {
+            delivery_fee: FeeSchedule::tiered([(0, 695), (7500, 395)]),
+            service_fee: FeeSchedule::flat(100),
+            minimum_order_value: 4000,
+            ..ProviderMetadata::unregistered(Provider::JUMBO)
+        };
+        let tally_for = |original_price, discount| {
+            let mut tally = TallyPriceInfo {
+                original_price,
+                discount,
+                deposit: 150,
+                ..Default::default()
+            };
+            tally.apply_fees(&metadata);
+            tally
+        };
+
+        // Orders below the minimum order value are still charged the regular fees.
+        let tally = tally_for(1000, 0);
+        assert_eq!((tally.delivery_fee, tally.service_fee), (695, 100));
+        assert_eq!(tally.total_price(), 1000 + 150 + 695 + 100);
+
+        // The tier boundary is inclusive, and the discounted price (excluding deposits) determines the tier.
+        assert_eq!(tally_for(7500, 0).delivery_fee, 395);
+        assert_eq!(tally_for(7499, 0).delivery_fee, 695);
+        assert_eq!(tally_for(8000, 501).delivery_fee, 695);
+
+        let tally = tally_for(8000, 500);
+        assert_eq!(tally.price(), 7500);
+        assert_eq!(tally.total_price(), 7500 + 150 + 395 + 100);
+    }
+
+    #[test]
+    fn test_unknown_sale_type() {
+        let mut unknown = sale(
+            SaleType::NumEuroOff(NumEuroOff { price_off: 100 }),
+            &[(&product("a", 100), 2)],
+        );
+        unknown.sale_info.sale_type = None;
+
+        let (tally, groups) = resolve(unknown);
+
+        assert_eq!(tally.discount, 0);
+        assert_eq!(groups[0].status, SaleGroupStatus::UnknownSaleType);
+        assert_eq!(groups[0].price_info.display_price, 200);
+    }
+
+    #[test]
+    fn test_num_plus_num_free() {
+        let one_plus_one = SaleType::NumPlusNumFree(NumPlusNumFree {
+            required: non_zero(1),
+            free: non_zero(1),
+        });
+        // The cheapest product of the group is free.
+        let (tally, groups) = resolve(sale(
+            one_plus_one.clone(),
+            &[(&product("a", 100), 1), (&product("b", 300), 1)],
+        ));
+
+        assert_eq!(tally.discount, 100);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].price_info.display_price, 300);
+
+        // How the units are grouped determines the discount, Picnic groups them in favour of the customer.
+        // Pairing (100, 200) and (300, 400) gives a larger discount than pairing (100, 400) and (200, 300).
+        let (tally, groups) = resolve(sale(
+            one_plus_one,
+            &[
+                (&product("a", 100), 1),
+                (&product("b", 200), 1),
+                (&product("c", 300), 1),
+                (&product("d", 400), 1),
+            ],
+        ));
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(tally.discount, 400);
+
+        let two_plus_one = SaleType::NumPlusNumFree(NumPlusNumFree {
+            required: non_zero(2),
+            free: non_zero(1),
+        });
+        let (tally, _) = resolve(sale(two_plus_one, &[(&product("a", 100), 3)]));
+
+        assert_eq!(tally.discount, 100);
+    }
+
+    #[test]
+    fn test_numth_percent_off() {
+        let second_half_off = SaleType::NumthPercentOff(NumthPercentOff {
+            required: non_zero(2),
+            last_percent_off: non_zero(50),
+        });
+        let (tally, groups) = resolve(sale(
+            second_half_off,
+            &[(&product("a", 100), 1), (&product("b", 300), 1)],
+        ));
+
+        // Only the cheapest product of the group is half off.
+        assert_eq!(tally.discount, 50);
+        assert_eq!(groups[0].status, SaleGroupStatus::Applied);
+        assert_eq!(groups[0].price_info.display_price, 350);
+    }
+
+    #[test]
+    fn test_num_for_price() {
+        let two_for_price = SaleType::NumForPrice(NumForPrice {
+            required: non_zero(2),
+            price: 250,
+        });
+        let (tally, groups) = resolve(sale(two_for_price, &[(&product("a", 200), 2)]));
+
+        assert_eq!(tally.discount, 150);
+        assert_eq!(groups[0].price_info.display_price, 250);
+
+        // Products which are cheaper than the sale price are bought at their own price.
+        let two_for_price = SaleType::NumForPrice(NumForPrice {
+            required: non_zero(2),
+            price: 250,
+        });
+        let (tally, groups) = resolve(sale(two_for_price, &[(&product("a", 100), 2)]));
+
+        assert_eq!(tally.discount, 0);
+        assert_eq!(groups[0].price_info.display_price, 200);
+    }
+
+    #[test]
+    fn test_single_item_sales() {
+        let mut discounted = product("a", 100);
+        discounted.price_info.display_price = 80;
+
+        let sale_types = [
+            SaleType::NumPercentOff(NumPercentOff::new(20).unwrap()),
+            SaleType::NumEuroOff(NumEuroOff { price_off: 20 }),
+            SaleType::NumEuroPrice(NumEuroPrice { price: 80 }),
+        ];
+
+        // The `display_price` of the product is trusted for all single item sales.
+        for sale_type in sale_types {
+            let (tally, groups) = resolve(sale(sale_type, &[(&discounted, 2)]));
+
+            assert_eq!(tally.discount, 40);
+            assert_eq!(groups.len(), 1);
+            assert_eq!(groups[0].status, SaleGroupStatus::Applied);
+        }
+    }
+
+    #[test]
+    fn test_num_for_percent_off() {
+        let two_for_quarter_off = SaleType::NumForPercentOff(NumForPercentOff::new(non_zero(2), 25).unwrap());
+        let (tally, groups) = resolve(sale(two_for_quarter_off, &[(&product("a", 200), 3)]));
+
+        assert_eq!(tally.discount, 100);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].price_info.display_price, 300);
+        assert_eq!(groups[1].status, SaleGroupStatus::MissingItems);
+        assert_eq!(groups[1].missing_quantity, 1);
+    }
+
+    #[test]
+    fn test_stacking_discount() {
+        let tier = |required, percent_off| StackingTier {
+            required: non_zero(required),
+            percent_off: non_zero(percent_off),
+        };
+        let stacking = SaleType::StackingDiscount(StackingDiscount {
+            tiers: vec![tier(2, 10), tier(3, 20)],
+        });
+
+        let (tally, groups) = resolve(sale(stacking.clone(), &[(&product("a", 100), 1)]));
+        assert_eq!(tally.discount, 0);
+        assert_eq!(groups[0].status, SaleGroupStatus::MissingItems);
+        assert_eq!(groups[0].missing_quantity, 1);
+
+        // The reached tier applies to all items in the sale.
+        let (tally, groups) = resolve(sale(
+            stacking.clone(),
+            &[(&product("a", 100), 1), (&product("b", 300), 1)],
+        ));
+        assert_eq!(tally.discount, 40);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].status, SaleGroupStatus::Applied);
+
+        let (tally, _) = resolve(sale(stacking, &[(&product("a", 100), 3)]));
+        assert_eq!(tally.discount, 60);
+
+        // Without tiers the discount is unknown.
+        let unknown = SaleType::StackingDiscount(StackingDiscount { tiers: Vec::new() });
+        let (tally, groups) = resolve(sale(unknown, &[(&product("a", 100), 3)]));
+        assert_eq!(tally.discount, 0);
+        assert_eq!(groups[0].status, SaleGroupStatus::UnknownSaleType);
+    }
+
+    #[test]
+    fn test_price_per_unit() {
+        let per_kilo = SaleType::PricePerUnit(PricePerUnit {
+            unit: Unit::KiloGram,
+            price: 999,
+        });
+        let mut weighted = product("a", 600);
+        weighted.unit_quantity = UnitQuantity {
+            unit: Unit::Gram,
+            amount: 500.,
+        };
+        let mut per_piece = product("b", 300);
+        per_piece.price_info.display_price = 250;
+
+        let (tally, groups) = resolve(sale(per_kilo, &[(&weighted, 2), (&per_piece, 1)]));
+
+        // 500 grams at 9.99 per kilo is 5.00, other units fall back to their `display_price`.
+        assert_eq!(tally.discount, 2 * 100 + 50);
+        assert_eq!(groups[0].status, SaleGroupStatus::Applied);
+        assert_eq!(groups[0].price_info.display_price, 2 * 500 + 250);
+    }
+
+    #[test]
+    fn test_minimum_quantity() {
+        let from_two = SaleType::MinimumQuantity(MinimumQuantity { required: non_zero(2) });
+        let mut a = product("a", 100);
+        a.price_info.display_price = 80;
+        let mut b = product("b", 200);
+        b.price_info.display_price = 150;
+
+        let (tally, groups) = resolve(sale(from_two.clone(), &[(&a, 2), (&b, 1)]));
+
+        // Only products which reached the minimum quantity on their own get the sale price.
+        assert_eq!(tally.discount, 40);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].status, SaleGroupStatus::Applied);
+        assert_eq!(groups[1].status, SaleGroupStatus::MissingItems);
+        assert_eq!(groups[1].missing_quantity, 1);
+
+        let (tally, _) = resolve(sale(from_two, &[(&a, 1), (&b, 1)]));
+        assert_eq!(tally.discount, 0);
+    }
 }
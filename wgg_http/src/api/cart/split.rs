@@ -0,0 +1,696 @@
+use crate::api::cart::service::{self, CartEntrySource, CartProductEntry, TallyPriceInfo};
+use crate::api::error::GraphqlError;
+use crate::api::{AppState, GraphqlResult};
+use itertools::Itertools;
+use sea_orm::ConnectionTrait;
+use std::collections::{BTreeMap, HashMap};
+use wgg_db_entity::DbId;
+use wgg_providers::models::{CentPrice, Provider, WggSearchProduct};
+
+/// The maximum amount of assignments we're willing to exhaustively evaluate for a single combination of providers.
+///
+/// Larger carts fall back to a local search, which isn't guaranteed to find the optimal split.
+const MAX_EXHAUSTIVE_ASSIGNMENTS: usize = 2048;
+/// Upper bound on the amount of local search rounds, guards against sale interactions which keep flip-flopping.
+const MAX_LOCAL_SEARCH_ROUNDS: usize = 32;
+
+/// Overrides for the costs and constraints a provider imposes on an order.
+///
+/// Any field which is `None` falls back to the provider's [ProviderMetadata](wgg_providers::models::ProviderMetadata).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProviderCosts {
+    /// The total of all fees (delivery, service) charged for an order.
+    pub fees: Option<CentPrice>,
+    /// The minimum value (after discounts) the products of an order need to have.
+    pub minimum_order: Option<CentPrice>,
+}
+
+impl ProviderCosts {
+    fn fees(&self, tally: &TallyPriceInfo) -> CentPrice {
+        self.fees.unwrap_or(tally.delivery_fee + tally.service_fee)
+    }
+
+    fn minimum_order(&self, provider: Provider) -> CentPrice {
+        self.minimum_order
+            .unwrap_or_else(|| provider.get_metadata().minimum_order_value)
+    }
+
+    fn shortfall(&self, provider: Provider, tally: &TallyPriceInfo) -> CentPrice {
+        self.minimum_order(provider).saturating_sub(tally.price())
+    }
+}
+
+#[derive(Debug)]
+pub struct CartSplitResult {
+    pub providers: Vec<ProviderSplit>,
+    /// The total price of all providers, including deposits and fees.
+    pub total_price: CentPrice,
+    /// The cheapest single provider (and its total price) which could supply the entire cart on its own.
+    pub best_single: Option<(Provider, CentPrice)>,
+}
+
+#[derive(Debug)]
+pub struct ProviderSplit {
+    pub provider: Provider,
+    pub tally: TallyPriceInfo,
+    /// The fees charged by this provider, which might differ from the `tally`'s fees if they were overridden.
+    pub fees: CentPrice,
+    /// The amount by which the products fall short of the provider's minimum order value, `0` if they don't.
+    pub shortfall: CentPrice,
+    pub items: Vec<CartProductEntry>,
+}
+
+/// A single cart entry, and all the provider products which could fulfill it.
+///
+/// Direct products only have a single option, aggregate ingredients have one for every linked provider product.
+struct SplitCandidate {
+    source: CartEntrySource,
+    quantity: u32,
+    options: Vec<WggSearchProduct>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct SplitCost {
+    /// The total amount by which the providers fall short of their minimum order value.
+    ///
+    /// Compared first, as any split with a shortfall can't actually be ordered.
+    shortfall: CentPrice,
+    total: CentPrice,
+}
+
+type Assignment = Vec<usize>;
+/// The chosen option of every candidate assigned to a single provider, as `(candidate, option)` pairs.
+type TallyKey = Vec<(usize, usize)>;
+type TallyFn<'a> = dyn Fn(&[(&WggSearchProduct, u32)]) -> GraphqlResult<TallyPriceInfo> + 'a;
+
+/// Remembers the tally of every provider's part of an assignment.
+///
+/// The tallies of providers are independent of each other, and most assignments considered during a search only differ
+/// in a single provider's part, so most tallies can be reused.
+struct TallyCache<'a> {
+    /// Calculates the tally of products which all belong to the same provider.
+    tally: &'a TallyFn<'a>,
+    tallies: HashMap<TallyKey, TallyPriceInfo>,
+}
+
+impl<'a> TallyCache<'a> {
+    fn new(tally: &'a TallyFn<'a>) -> Self {
+        Self {
+            tally,
+            tallies: HashMap::new(),
+        }
+    }
+
+    /// Retrieve the tally of every provider used in the given assignment.
+    fn tallies(
+        &mut self,
+        candidates: &[SplitCandidate],
+        assignment: &[usize],
+    ) -> GraphqlResult<BTreeMap<Provider, TallyPriceInfo>> {
+        let mut parts: BTreeMap<Provider, TallyKey> = BTreeMap::new();
+
+        for (index, (candidate, &option)) in candidates.iter().zip(assignment).enumerate() {
+            parts
+                .entry(candidate.options[option].provider)
+                .or_default()
+                .push((index, option));
+        }
+
+        parts
+            .into_iter()
+            .map(|(provider, key)| Ok((provider, self.provider_tally(candidates, key)?)))
+            .collect()
+    }
+
+    fn provider_tally(&mut self, candidates: &[SplitCandidate], key: TallyKey) -> GraphqlResult<TallyPriceInfo> {
+        if let Some(tally) = self.tallies.get(&key) {
+            return Ok(*tally);
+        }
+
+        let products = key
+            .iter()
+            .map(|&(index, option)| (&candidates[index].options[option], candidates[index].quantity))
+            .collect_vec();
+        let tally = (self.tally)(&products)?;
+
+        self.tallies.insert(key, tally);
+
+        Ok(tally)
+    }
+}
+
+/// Calculate the cheapest way to split the given cart over at most `max_stores` providers.
+///
+/// Notes in the cart are ignored, direct products are always assigned to their own provider, and aggregate ingredients
+/// are assigned to whichever linked provider results in the lowest total.
+///
+/// Should no split satisfy the minimum order values of its providers, the split with the smallest shortfall is
+/// returned, see [ProviderSplit::shortfall].
+#[tracing::instrument(skip(db, state, costs))]
+pub async fn calculate_cart_split(
+    db: &impl ConnectionTrait,
+    cart_id: DbId,
+    state: &AppState,
+    max_stores: usize,
+    costs: &HashMap<Provider, ProviderCosts>,
+) -> GraphqlResult<CartSplitResult> {
+    let entries = service::get_cart_products(db, cart_id, state).await?;
+    let tally_fn = |products: &[(&WggSearchProduct, u32)]| -> GraphqlResult<TallyPriceInfo> {
+        // All products belong to the same provider, so there is only a single tally.
+        Ok(service::tally_products(state, products.iter().copied())?
+            .into_values()
+            .next()
+            .unwrap_or_default())
+    };
+
+    split_candidates(group_candidates(entries), &tally_fn, max_stores, costs)
+}
+
+/// Calculate the cheapest split of the given `candidates`, see [calculate_cart_split].
+fn split_candidates(
+    candidates: Vec<SplitCandidate>,
+    tally_fn: &TallyFn<'_>,
+    max_stores: usize,
+    costs: &HashMap<Provider, ProviderCosts>,
+) -> GraphqlResult<CartSplitResult> {
+    if candidates.is_empty() {
+        return Ok(CartSplitResult {
+            providers: Vec::new(),
+            total_price: 0,
+            best_single: None,
+        });
+    }
+
+    let providers: Vec<Provider> = candidates
+        .iter()
+        .flat_map(|candidate| candidate.options.iter().map(|option| option.provider))
+        .unique()
+        .sorted()
+        .collect();
+    let mut cache = TallyCache::new(tally_fn);
+
+    let Some((cost, assignment)) = cheapest_split(&mut cache, &candidates, &providers, max_stores, costs)? else {
+        return Err(GraphqlError::UserError(format!(
+            "The cart requires products from more than {max_stores} store(s)"
+        )));
+    };
+
+    let mut best_single: Option<(Provider, CentPrice)> = None;
+
+    for provider in &providers {
+        let Some((cost, _)) = optimise_for_providers(&mut cache, &candidates, &[*provider], costs)? else {
+            continue;
+        };
+
+        if cost.shortfall == 0 && best_single.map(|(_, price)| cost.total < price).unwrap_or(true) {
+            best_single = Some((*provider, cost.total));
+        }
+    }
+
+    let tallies = cache.tallies(&candidates, &assignment)?;
+    let mut items_per_provider: HashMap<Provider, Vec<CartProductEntry>> = HashMap::new();
+
+    for (candidate, &option) in candidates.iter().zip(&assignment) {
+        let product = candidate.options[option].clone();
+
+        items_per_provider
+            .entry(product.provider)
+            .or_default()
+            .push(CartProductEntry {
+                source: candidate.source,
+                quantity: candidate.quantity,
+                product,
+            });
+    }
+
+    let providers = tallies
+        .into_iter()
+        .map(|(provider, tally)| {
+            let provider_costs = costs.get(&provider).copied().unwrap_or_default();
+
+            ProviderSplit {
+                provider,
+                tally,
+                fees: provider_costs.fees(&tally),
+                shortfall: provider_costs.shortfall(provider, &tally),
+                items: items_per_provider.remove(&provider).unwrap_or_default(),
+            }
+        })
+        .sorted_by_key(|split| split.provider)
+        .collect();
+
+    Ok(CartSplitResult {
+        providers,
+        total_price: cost.total,
+        best_single,
+    })
+}
+
+/// Group all the cart products by their cart entry, preserving the order of the cart.
+fn group_candidates(entries: Vec<CartProductEntry>) -> Vec<SplitCandidate> {
+    let mut candidates: Vec<SplitCandidate> = Vec::new();
+
+    for entry in entries {
+        match candidates.iter_mut().find(|candidate| candidate.source == entry.source) {
+            Some(candidate) => candidate.options.push(entry.product),
+            None => candidates.push(SplitCandidate {
+                source: entry.source,
+                quantity: entry.quantity,
+                options: vec![entry.product],
+            }),
+        }
+    }
+
+    candidates
+}
+
+/// Find the cheapest assignment of all `candidates` which uses at most `max_stores` of the given `providers`.
+///
+/// Returns `None` if there is no such assignment.
+fn cheapest_split(
+    cache: &mut TallyCache<'_>,
+    candidates: &[SplitCandidate],
+    providers: &[Provider],
+    max_stores: usize,
+    costs: &HashMap<Provider, ProviderCosts>,
+) -> GraphqlResult<Option<(SplitCost, Assignment)>> {
+    let mut best: Option<(SplitCost, Assignment)> = None;
+
+    // An assignment using fewer stores is also an assignment within every larger combination of stores containing them,
+    // so only the largest combinations need to be searched.
+    for allowed in providers.iter().copied().combinations(max_stores.min(providers.len())) {
+        let Some(result) = optimise_for_providers(cache, candidates, &allowed, costs)? else {
+            continue;
+        };
+
+        if best.as_ref().map(|(cost, _)| result.0 < *cost).unwrap_or(true) {
+            best = Some(result);
+        }
+    }
+
+    Ok(best)
+}
+
+/// Find the cheapest assignment of all `candidates` when only the `allowed` providers may be used.
+///
+/// Returns `None` if one of the candidates can't be supplied by any of the `allowed` providers.
+fn optimise_for_providers(
+    cache: &mut TallyCache<'_>,
+    candidates: &[SplitCandidate],
+    allowed: &[Provider],
+    costs: &HashMap<Provider, ProviderCosts>,
+) -> GraphqlResult<Option<(SplitCost, Assignment)>> {
+    let choices = candidates
+        .iter()
+        .map(|candidate| {
+            candidate
+                .options
+                .iter()
+                .positions(|option| allowed.contains(&option.provider))
+                .collect_vec()
+        })
+        .collect_vec();
+
+    if choices.iter().any(|choice| choice.is_empty()) {
+        return Ok(None);
+    }
+
+    let total_assignments = choices
+        .iter()
+        .try_fold(1usize, |acc, choice| acc.checked_mul(choice.len()));
+
+    match total_assignments {
+        Some(total) if total <= MAX_EXHAUSTIVE_ASSIGNMENTS => exhaustive_search(cache, candidates, &choices, costs),
+        _ => local_search(cache, candidates, &choices, costs).map(Some),
+    }
+}
+
+/// Evaluate every possible assignment, guaranteed to find the optimum.
+fn exhaustive_search(
+    cache: &mut TallyCache<'_>,
+    candidates: &[SplitCandidate],
+    choices: &[Vec<usize>],
+    costs: &HashMap<Provider, ProviderCosts>,
+) -> GraphqlResult<Option<(SplitCost, Assignment)>> {
+    let mut best: Option<(SplitCost, Assignment)> = None;
+    let mut indices = vec![0usize; choices.len()];
+
+    loop {
+        let assignment = indices.iter().zip(choices).map(|(&i, choice)| choice[i]).collect_vec();
+        let cost = evaluate(cache, candidates, &assignment, costs)?;
+
+        if best.as_ref().map(|(best_cost, _)| cost < *best_cost).unwrap_or(true) {
+            best = Some((cost, assignment));
+        }
+
+        // Advance to the next assignment, like an odometer.
+        let mut position = 0;
+        loop {
+            if position == indices.len() {
+                return Ok(best);
+            }
+
+            indices[position] += 1;
+
+            if indices[position] < choices[position].len() {
+                break;
+            }
+
+            indices[position] = 0;
+            position += 1;
+        }
+    }
+}
+
+/// Start with every candidate at its cheapest option, and keep moving single candidates to a different provider as
+/// long as that improves the total.
+///
+/// Only used for large carts, where an exhaustive search would take too long.
+fn local_search(
+    cache: &mut TallyCache<'_>,
+    candidates: &[SplitCandidate],
+    choices: &[Vec<usize>],
+    costs: &HashMap<Provider, ProviderCosts>,
+) -> GraphqlResult<(SplitCost, Assignment)> {
+    let mut assignment = candidates
+        .iter()
+        .zip(choices)
+        .map(|(candidate, choice)| {
+            *choice
+                .iter()
+                .min_by_key(|&&option| candidate.options[option].price_info.display_price)
+                .expect("Choices can't be empty")
+        })
+        .collect_vec();
+    let mut current = evaluate(cache, candidates, &assignment, costs)?;
+
+    for _ in 0..MAX_LOCAL_SEARCH_ROUNDS {
+        let mut improved = false;
+
+        for (index, choice) in choices.iter().enumerate() {
+            for &option in choice {
+                if option == assignment[index] {
+                    continue;
+                }
+
+                let previous = std::mem::replace(&mut assignment[index], option);
+                let cost = evaluate(cache, candidates, &assignment, costs)?;
+
+                if cost < current {
+                    current = cost;
+                    improved = true;
+                } else {
+                    assignment[index] = previous;
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    Ok((current, assignment))
+}
+
+/// Calculate the total cost of the given assignment, including sales, deposits, fees, and minimum order shortfalls.
+fn evaluate(
+    cache: &mut TallyCache<'_>,
+    candidates: &[SplitCandidate],
+    assignment: &[usize],
+    costs: &HashMap<Provider, ProviderCosts>,
+) -> GraphqlResult<SplitCost> {
+    let tallies = cache.tallies(candidates, assignment)?;
+
+    Ok(tallies
+        .into_iter()
+        .fold(SplitCost { shortfall: 0, total: 0 }, |mut cost, (provider, tally)| {
+            let provider_costs = costs.get(&provider).copied().unwrap_or_default();
+
+            cost.shortfall += provider_costs.shortfall(provider, &tally);
+            cost.total += tally.price() + tally.deposit + provider_costs.fees(&tally);
+            cost
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::cart::objects::CartSplit;
+    use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+    use std::cell::Cell;
+    use wgg_providers::models::{PriceInfo, UnitQuantity};
+
+    const FEE: CentPrice = 300;
+
+    fn product(provider: Provider, price: CentPrice) -> WggSearchProduct {
+        WggSearchProduct {
+            id: format!("{provider}-{price}"),
+            name: "Product".to_string(),
+            price_info: PriceInfo {
+                display_price: price,
+                original_price: price,
+                unit_price: None,
+                deposit: None,
+            },
+            unit_quantity: UnitQuantity::default(),
+            gtin: None,
+            unavailable_details: None,
+            image_url: None,
+            decorators: Vec::new(),
+            sale_information: None,
+            provider,
+        }
+    }
+
+    /// A cart entry of a single item, with one option for every `(provider, price)`.
+    fn candidate(id: DbId, options: &[(Provider, CentPrice)]) -> SplitCandidate {
+        SplitCandidate {
+            source: CartEntrySource::Aggregate(id),
+            quantity: 1,
+            options: options
+                .iter()
+                .map(|&(provider, price)| product(provider, price))
+                .collect(),
+        }
+    }
+
+    /// Tally without any sales, where every order has a flat delivery fee.
+    fn flat_tally(products: &[(&WggSearchProduct, u32)]) -> GraphqlResult<TallyPriceInfo> {
+        Ok(TallyPriceInfo {
+            original_price: products
+                .iter()
+                .map(|(product, quantity)| product.price_info.original_price * quantity)
+                .sum(),
+            delivery_fee: FEE,
+            ..Default::default()
+        })
+    }
+
+    fn split_cost(shortfall: CentPrice, total: CentPrice) -> SplitCost {
+        SplitCost { shortfall, total }
+    }
+
+    fn no_minimum() -> HashMap<Provider, ProviderCosts> {
+        [Provider::PICNIC, Provider::JUMBO]
+            .into_iter()
+            .map(|provider| {
+                let costs = ProviderCosts {
+                    fees: None,
+                    minimum_order: Some(0),
+                };
+                (provider, costs)
+            })
+            .collect()
+    }
+
+    /// Picking the cheapest option for every entry means paying the fees of two stores.
+    fn fee_sensitive_cart() -> Vec<SplitCandidate> {
+        vec![
+            candidate(1, &[(Provider::PICNIC, 100), (Provider::JUMBO, 150)]),
+            candidate(2, &[(Provider::PICNIC, 300), (Provider::JUMBO, 200)]),
+            candidate(3, &[(Provider::JUMBO, 100)]),
+        ]
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let calls = Cell::new(0);
+        let counting_tally = |products: &[(&WggSearchProduct, u32)]| {
+            calls.set(calls.get() + 1);
+            flat_tally(products)
+        };
+        let mut cache = TallyCache::new(&counting_tally);
+        let candidates = fee_sensitive_cart();
+        let mut costs = no_minimum();
+        costs.insert(
+            Provider::JUMBO,
+            ProviderCosts {
+                fees: Some(50),
+                minimum_order: Some(400),
+            },
+        );
+
+        let cost = evaluate(&mut cache, &candidates, &[0, 1, 0], &costs).unwrap();
+
+        // Picnic: 100 + 300 fee, Jumbo: 200 + 100 + 50 overridden fee, 100 short of its minimum.
+        assert_eq!(cost, split_cost(100, 750));
+        assert_eq!(calls.get(), 2);
+
+        let cost = evaluate(&mut cache, &candidates, &[1, 1, 0], &costs).unwrap();
+
+        assert_eq!(cost, split_cost(0, 500));
+        assert_eq!(calls.get(), 3);
+
+        // Both parts were tallied before, so nothing needs to be recalculated.
+        let cost = evaluate(&mut cache, &candidates, &[0, 1, 0], &costs).unwrap();
+
+        assert_eq!(cost, split_cost(100, 750));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_exhaustive_search() {
+        let mut cache = TallyCache::new(&flat_tally);
+        let candidates = fee_sensitive_cart();
+        let choices = vec![vec![0, 1], vec![0, 1], vec![0]];
+
+        let (cost, assignment) = exhaustive_search(&mut cache, &candidates, &choices, &no_minimum())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(assignment, vec![1, 1, 0]);
+        assert_eq!(cost, split_cost(0, 750));
+    }
+
+    #[test]
+    fn test_local_search() {
+        let mut cache = TallyCache::new(&flat_tally);
+        let candidates = fee_sensitive_cart();
+        let choices = vec![vec![0, 1], vec![0, 1], vec![0]];
+
+        // Starts at the cheapest options (1000 in total), and moves the first entry to Jumbo to save a fee.
+        let (cost, assignment) = local_search(&mut cache, &candidates, &choices, &no_minimum()).unwrap();
+
+        assert_eq!(assignment, vec![1, 1, 0]);
+        assert_eq!(cost, split_cost(0, 750));
+    }
+
+    #[test]
+    fn test_shortfall_beats_total() {
+        let mut cache = TallyCache::new(&flat_tally);
+        let candidates = vec![
+            candidate(1, &[(Provider::PICNIC, 100), (Provider::JUMBO, 150)]),
+            candidate(2, &[(Provider::JUMBO, 400)]),
+        ];
+        let mut costs = no_minimum();
+        costs.insert(
+            Provider::JUMBO,
+            ProviderCosts {
+                fees: Some(0),
+                minimum_order: Some(500),
+            },
+        );
+        costs.insert(
+            Provider::PICNIC,
+            ProviderCosts {
+                fees: Some(0),
+                minimum_order: Some(0),
+            },
+        );
+
+        let (cost, assignment) = exhaustive_search(&mut cache, &candidates, &[vec![0, 1], vec![0]], &costs)
+            .unwrap()
+            .unwrap();
+
+        // Splitting would be cheaper, but leaves Jumbo below its minimum order value.
+        assert_eq!(assignment, vec![1, 0]);
+        assert_eq!(cost, split_cost(0, 550));
+    }
+
+    #[test]
+    fn test_cheapest_split() {
+        let mut cache = TallyCache::new(&flat_tally);
+        let candidates = vec![
+            candidate(1, &[(Provider::PICNIC, 100), (Provider::JUMBO, 900)]),
+            candidate(2, &[(Provider::PICNIC, 900), (Provider::JUMBO, 120)]),
+        ];
+        let providers = [Provider::JUMBO, Provider::PICNIC];
+        let costs = no_minimum();
+
+        let (cost, assignment) = cheapest_split(&mut cache, &candidates, &providers, 2, &costs)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(assignment, vec![0, 1]);
+        assert_eq!(cost.total, 100 + 120 + 2 * FEE);
+
+        let (cost, assignment) = cheapest_split(&mut cache, &candidates, &providers, 1, &costs)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(assignment, vec![0, 0]);
+        assert_eq!(cost.total, 100 + 900 + FEE);
+
+        // Entries which are only available at different stores can't be bought from a single store.
+        let candidates = vec![
+            candidate(1, &[(Provider::PICNIC, 100)]),
+            candidate(2, &[(Provider::JUMBO, 100)]),
+        ];
+        assert!(cheapest_split(&mut cache, &candidates, &providers, 1, &costs)
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_split_with_shortfall() {
+        struct Query;
+
+        #[async_graphql::Object]
+        impl Query {
+            async fn split(&self) -> GraphqlResult<CartSplit> {
+                let candidates = vec![
+                    candidate(1, &[(Provider::PICNIC, 100)]),
+                    candidate(2, &[(Provider::JUMBO, 400)]),
+                ];
+                let costs = HashMap::from([
+                    (
+                        Provider::PICNIC,
+                        ProviderCosts {
+                            fees: Some(0),
+                            minimum_order: Some(0),
+                        },
+                    ),
+                    (
+                        Provider::JUMBO,
+                        ProviderCosts {
+                            fees: Some(0),
+                            minimum_order: Some(500),
+                        },
+                    ),
+                ]);
+
+                split_candidates(candidates, &flat_tally, 2, &costs).map(CartSplit)
+            }
+        }
+
+        let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+        let response = schema
+            .execute("{ split { totalPriceCents shortfallCents savingsCents providers { shortfallCents } } }")
+            .await;
+
+        // The Jumbo order can't reach its minimum, but the split should still be returned.
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            serde_json::json!({
+                "split": {
+                    "totalPriceCents": 500,
+                    "shortfallCents": 100,
+                    "savingsCents": null,
+                    "providers": [{ "shortfallCents": 100 }, { "shortfallCents": 0 }],
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,297 @@
+//! Synchronise the current cart with the remote cart of a provider.
+use crate::api::auth::AuthContext;
+use crate::api::cart::service;
+use crate::api::{AppState, GraphqlResult, ProductId};
+use crate::db;
+use crate::db::cart_contents::raw_product;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ActiveValue, ColumnTrait, EntityTrait, IntoActiveValue, QueryFilter, TransactionTrait};
+use std::collections::{BTreeMap, HashMap};
+use wgg_db_entity::DbId;
+use wgg_providers::models::{ProductIdRef, Provider};
+
+/// The direction in which [sync_cart] synchronises the current cart with the remote cart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum CartSyncDirection {
+    /// Set the remote quantity of every product in the current cart to its quantity in the current cart.
+    ///
+    /// Products which are only in the remote cart are left untouched, unless they were removed from the current cart
+    /// since the last export.
+    Export,
+    /// Add the products in the remote cart to the current cart, raising any quantities which are lower locally.
+    ///
+    /// Nothing is removed from the current cart. Products which were removed from the current cart since the last
+    /// export are only added again if the remote cart has more of them than was exported.
+    Import,
+    /// Import, and then export, the cart.
+    ///
+    /// Afterwards both carts contain every product of either cart, with the highest quantity of the two. Except for
+    /// products which were removed from the current cart since the last export, which are removed remotely as well.
+    Both,
+}
+
+/// The changes made by [sync_cart].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CartSyncSummary {
+    /// The amount of products whose quantity was changed in the current cart.
+    pub imported: u64,
+    /// The amount of products whose quantity was changed in the remote cart.
+    pub exported: u64,
+}
+
+/// Synchronise the current cart of the given user with the remote cart of `provider`.
+///
+/// Unlike a plain export only the difference between the two carts is applied, so repeatedly syncing a cart neither
+/// doubles quantities nor removes products which were added in the provider's own app.
+/// The remote cart of the account the user linked for `provider` is used, see [user_cart_for] for when the server-wide
+/// account is used instead.
+///
+/// [user_cart_for]: crate::api::accounts::user_cart_for
+pub async fn sync_cart(
+    state: &AppState,
+    user: &AuthContext,
+    provider: Provider,
+    direction: CartSyncDirection,
+) -> GraphqlResult<CartSyncSummary> {
+    let user_id = user.id;
+    let account = crate::api::accounts::user_cart_for(state, user, provider).await?;
+    let provider_id = state.provider_id_from_provider(&provider);
+
+    let remote = match &account {
+        Some(account) => account.cart_contents().await?,
+        None => state.providers.cart_contents(provider).await?,
+    };
+    let remote = sum_quantities(remote.into_iter().map(|item| (item.product_id, item.quantity)));
+
+    let tx = state.db.begin().await?;
+    let cart = db::cart::get_active_cart_for_user(user_id, &tx).await?;
+    let mut local = sum_quantities(
+        service::get_products_from_provider(&tx, cart.id, provider_id)
+            .await?
+            .into_iter()
+            .map(|item| (item.product_id, item.quantity)),
+    );
+    // What both carts contained after the last export, to recognise products which were removed locally since.
+    let snapshot: BTreeMap<ProductId, u32> = db::cart_sync_snapshots::Entity::find()
+        .filter(db::cart_sync_snapshots::for_cart_provider(cart.id, provider_id))
+        .all(&tx)
+        .await?
+        .into_iter()
+        .map(|item| (item.provider_product, item.quantity as u32))
+        .collect();
+    let mut summary = CartSyncSummary::default();
+
+    if direction != CartSyncDirection::Export {
+        let direct: HashMap<String, u32> = raw_product::Entity::find()
+            .filter(raw_product::Column::CartId.eq(cart.id))
+            .filter(raw_product::Column::ProviderId.eq(provider_id))
+            .all(&tx)
+            .await?
+            .into_iter()
+            .map(|item| (item.provider_product, item.quantity as u32))
+            .collect();
+
+        for (product_id, missing) in import_changes(&local, &remote, &snapshot) {
+            // Products can also be in the cart through an aggregate, only the direct quantity can be raised.
+            let quantity = direct.get(&product_id).copied().unwrap_or_default() + missing;
+
+            let to_insert = raw_product::ActiveModel {
+                id: ActiveValue::NotSet,
+                cart_id: cart.id.into_active_value(),
+                provider_id: provider_id.into_active_value(),
+                provider_product: product_id.clone().into_active_value(),
+                quantity: (quantity as i32).into_active_value(),
+                created_at: ActiveValue::NotSet,
+            };
+
+            let _ = raw_product::Entity::insert(to_insert)
+                .on_conflict(
+                    OnConflict::columns([
+                        raw_product::Column::CartId,
+                        raw_product::Column::ProviderId,
+                        raw_product::Column::ProviderProduct,
+                    ])
+                    .update_columns([raw_product::Column::Quantity])
+                    .to_owned(),
+                )
+                .exec(&tx)
+                .await?;
+
+            *local.entry(product_id).or_default() += missing;
+            summary.imported += 1;
+        }
+    }
+
+    tx.commit().await?;
+
+    if direction != CartSyncDirection::Import {
+        let (to_add, to_remove) = export_changes(&local, &remote, &snapshot);
+
+        match &account {
+            Some(account) => {
+                account.remove_from_cart(&to_remove).await?;
+                account.add_to_cart(&to_add).await?;
+            }
+            None => {
+                state.providers.remove_from_cart(provider, &to_remove).await?;
+                state.providers.add_to_cart(provider, &to_add).await?;
+            }
+        }
+
+        summary.exported = (to_add.len() + to_remove.len()) as u64;
+        save_snapshot(state, cart.id, provider_id, &local).await?;
+    }
+
+    tracing::debug!(user_id, %provider, ?direction, ?summary, "Synchronised cart");
+
+    Ok(summary)
+}
+
+pub(super) fn sum_quantities(items: impl IntoIterator<Item = (ProductId, u32)>) -> BTreeMap<ProductId, u32> {
+    let mut result = BTreeMap::new();
+
+    for (product_id, quantity) in items {
+        *result.entry(product_id).or_default() += quantity;
+    }
+
+    result
+}
+
+/// Replace the snapshot of the given cart for `provider_id` with the `synced` quantities, which both carts now contain.
+async fn save_snapshot(
+    state: &AppState,
+    cart_id: DbId,
+    provider_id: DbId,
+    synced: &BTreeMap<ProductId, u32>,
+) -> GraphqlResult<()> {
+    let tx = state.db.begin().await?;
+
+    db::cart_sync_snapshots::Entity::delete_many()
+        .filter(db::cart_sync_snapshots::for_cart_provider(cart_id, provider_id))
+        .exec(&tx)
+        .await?;
+
+    if !synced.is_empty() {
+        let to_insert = synced
+            .iter()
+            .map(|(product_id, &quantity)| db::cart_sync_snapshots::ActiveModel {
+                cart_id: cart_id.into_active_value(),
+                provider_id: provider_id.into_active_value(),
+                provider_product: product_id.clone().into_active_value(),
+                quantity: (quantity as i32).into_active_value(),
+            });
+
+        db::cart_sync_snapshots::Entity::insert_many(to_insert)
+            .exec(&tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Calculate how much of each product has to be added to the `local` cart to contain everything in the `remote` cart.
+///
+/// Products which were removed from the `local` cart since the last export, according to `snapshot`, are only
+/// imported if the `remote` cart has more of them than was exported.
+fn import_changes(
+    local: &BTreeMap<ProductId, u32>,
+    remote: &BTreeMap<ProductId, u32>,
+    snapshot: &BTreeMap<ProductId, u32>,
+) -> Vec<(ProductId, u32)> {
+    remote
+        .iter()
+        .filter_map(|(product_id, &remote_quantity)| {
+            let local_quantity = match local.get(product_id) {
+                Some(&quantity) => quantity,
+                None => snapshot.get(product_id).copied().unwrap_or_default(),
+            };
+
+            (remote_quantity > local_quantity).then(|| (product_id.clone(), remote_quantity - local_quantity))
+        })
+        .collect()
+}
+
+/// Products, and their quantity, to add to or remove from a cart.
+type CartChanges<'a> = Vec<(&'a ProductIdRef, u32)>;
+
+/// Calculate the products which have to be added to, and removed from, the `remote` cart for every product in the
+/// `local` cart to have the same quantity in both.
+///
+/// Products which were removed from the `local` cart since the last export, according to `snapshot`, are removed from
+/// the `remote` cart as well, up to the exported quantity.
+fn export_changes<'a>(
+    local: &'a BTreeMap<ProductId, u32>,
+    remote: &BTreeMap<ProductId, u32>,
+    snapshot: &'a BTreeMap<ProductId, u32>,
+) -> (CartChanges<'a>, CartChanges<'a>) {
+    let mut to_add = Vec::new();
+    let mut to_remove = Vec::new();
+
+    for (product_id, &local_quantity) in local {
+        let remote_quantity = remote.get(product_id).copied().unwrap_or_default();
+
+        if local_quantity > remote_quantity {
+            to_add.push((product_id.as_str(), local_quantity - remote_quantity));
+        } else if local_quantity < remote_quantity {
+            to_remove.push((product_id.as_str(), remote_quantity - local_quantity));
+        }
+    }
+
+    for (product_id, &exported_quantity) in snapshot {
+        if local.contains_key(product_id) {
+            continue;
+        }
+
+        let remote_quantity = remote.get(product_id).copied().unwrap_or_default();
+        let quantity = exported_quantity.min(remote_quantity);
+
+        if quantity > 0 {
+            to_remove.push((product_id.as_str(), quantity));
+        }
+    }
+
+    (to_add, to_remove)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_changes, import_changes, sum_quantities};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_sync_changes() {
+        let local = sum_quantities([("1".to_string(), 2), ("2".to_string(), 1), ("2".to_string(), 1)]);
+        let remote = sum_quantities([("1".to_string(), 3), ("2".to_string(), 1), ("3".to_string(), 1)]);
+        let snapshot = BTreeMap::new();
+
+        assert_eq!(
+            import_changes(&local, &remote, &snapshot),
+            vec![("1".to_string(), 1), ("3".to_string(), 1)]
+        );
+
+        let (to_add, to_remove) = export_changes(&local, &remote, &snapshot);
+        assert_eq!(to_add, vec![("2", 1)]);
+        assert_eq!(to_remove, vec![("1", 1)]);
+    }
+
+    #[test]
+    fn test_sync_removed_products() {
+        // `2` was removed locally since the last export, `3` was then added once more in the provider's app.
+        let snapshot = sum_quantities([("1".to_string(), 1), ("2".to_string(), 2), ("3".to_string(), 1)]);
+        let local = sum_quantities([("1".to_string(), 1)]);
+        let remote = sum_quantities([("1".to_string(), 1), ("2".to_string(), 2), ("3".to_string(), 2)]);
+
+        assert_eq!(import_changes(&local, &remote, &snapshot), vec![("3".to_string(), 1)]);
+
+        let (to_add, to_remove) = export_changes(&local, &remote, &snapshot);
+        assert!(to_add.is_empty());
+        assert_eq!(to_remove, vec![("2", 2), ("3", 1)]);
+
+        // Products which were never exported are left untouched.
+        let never_exported = BTreeMap::new();
+        let (_, to_remove) = export_changes(&local, &remote, &never_exported);
+        assert!(to_remove.is_empty());
+    }
+}
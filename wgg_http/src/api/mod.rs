@@ -1,8 +1,12 @@
+use crate::accounts::{AccountCarts, CredentialsCipher};
+use crate::api::accounts::{AccountMutation, AccountQuery};
 use crate::api::aggregate_ingredients::{AggregateMutation, AggregateQuery};
 use crate::api::auth::{AuthMutation, AuthQuery};
 use crate::api::cart::{CartMutation, CartQuery};
 use crate::api::error::GraphqlError;
-use crate::api::providers::ProviderQuery;
+use crate::api::notifications::{NotificationMutation, NotificationQuery};
+use crate::api::providers::{ProviderMutation, ProviderQuery};
+use crate::api::watchlist::{WatchlistMutation, WatchlistQuery};
 use crate::config::SharedConfig;
 use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute};
 use async_graphql::{async_trait, EmptySubscription, MergedObject, Response, Schema};
@@ -14,6 +18,7 @@ use wgg_providers::models::Provider;
 use wgg_providers::WggProvider;
 use wgg_scheduler::JobScheduler;
 
+mod accounts;
 mod aggregate_ingredients;
 mod auth;
 mod cart;
@@ -22,10 +27,12 @@ mod ctx;
 pub(crate) mod dataloader;
 mod error;
 mod macros;
+mod notifications;
 mod pagination;
 mod providers;
 mod routes;
 pub mod scheduled_jobs;
+mod watchlist;
 
 pub use auth::{create_user, UserCreateInput};
 pub(crate) use ctx::*;
@@ -49,6 +56,9 @@ pub struct AppState {
     ///
     /// This assumes no external modification of the database *whilst* the application is running!
     pub(crate) db_providers: BTreeMap<Provider, DbId>,
+    /// Used to encrypt the credentials of linked provider accounts.
+    pub(crate) credentials_cipher: CredentialsCipher,
+    pub(crate) account_carts: AccountCarts,
 }
 
 impl AppState {
@@ -71,10 +81,26 @@ impl AppState {
 }
 
 #[derive(MergedObject, Default)]
-pub struct QueryRoot(ProviderQuery, AuthQuery, AggregateQuery, CartQuery);
+pub struct QueryRoot(
+    ProviderQuery,
+    AuthQuery,
+    AggregateQuery,
+    CartQuery,
+    WatchlistQuery,
+    NotificationQuery,
+    AccountQuery,
+);
 
 #[derive(MergedObject, Default)]
-pub struct MutationRoot(AuthMutation, AggregateMutation, CartMutation);
+pub struct MutationRoot(
+    ProviderMutation,
+    AuthMutation,
+    AggregateMutation,
+    CartMutation,
+    WatchlistMutation,
+    NotificationMutation,
+    AccountMutation,
+);
 
 pub struct ErrorTraceExtension;
 
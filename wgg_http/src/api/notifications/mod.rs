@@ -0,0 +1,7 @@
+mod mutation;
+mod objects;
+mod query;
+pub mod scheduled_jobs;
+
+pub use mutation::NotificationMutation;
+pub use query::NotificationQuery;
@@ -0,0 +1,201 @@
+use crate::api::error::GraphqlError;
+use crate::api::notifications::objects::UserNotificationChannel;
+use crate::api::{ContextExt, GraphqlResult, MAX_AMOUNT_DELETE};
+use crate::db;
+use crate::notifications::{
+    self, ChannelConfig, Notification, NotificationKind, PushConfig, SmtpConfig, WebhookConfig,
+};
+use async_graphql::{Context, Object};
+use sea_orm::{ActiveModelTrait, EntityTrait, IntoActiveModel, IntoActiveValue, QueryFilter};
+use wgg_db_entity::{DbId, EntityExt, SelectExt};
+
+#[derive(Default)]
+pub struct NotificationMutation;
+
+#[Object]
+impl NotificationMutation {
+    /// Create a new notification channel for the current user.
+    /// Exactly one of `webhook`, `smtp`, or `push` should be given.
+    ///
+    /// # Returns
+    ///
+    /// The newly created channel.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx, input))]
+    async fn notification_channel_create(
+        &self,
+        ctx: &Context<'_>,
+        input: NotificationChannelCreateInput,
+    ) -> GraphqlResult<NotificationChannelCreatePayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let config = match (input.webhook, input.smtp, input.push) {
+            (Some(webhook), None, None) => ChannelConfig::Webhook(webhook),
+            (None, Some(smtp), None) => ChannelConfig::Smtp(smtp),
+            (None, None, Some(push)) => ChannelConfig::Push(push),
+            _ => {
+                return Err(GraphqlError::InvalidInput(
+                    "Exactly one of `webhook`, `smtp`, or `push` should be set".to_string(),
+                ));
+            }
+        };
+
+        // Ensure the configuration is usable before storing it.
+        let _ = config
+            .clone()
+            .into_channel(notifications::http_client())
+            .map_err(|e| GraphqlError::InvalidInput(format!("Invalid channel configuration: {e:#}")))?;
+
+        let (config, secrets) = config.to_db_values(&state.credentials_cipher)?;
+        let new_channel = db::notification_channels::ActiveModel {
+            user_id: user.id.into_active_value(),
+            name: input.name.into_active_value(),
+            config: config.into_active_value(),
+            encrypted_secrets: Some(secrets).into_active_value(),
+            ..Default::default()
+        };
+        let model = new_channel.insert(&state.db).await?;
+
+        tracing::debug!(channel_id = model.id, "New notification channel created");
+
+        Ok(NotificationChannelCreatePayload { data: model.into() })
+    }
+
+    /// Enable or disable a notification channel.
+    /// Disabled channels don't receive new notifications, and already queued notifications are held until re-enabled.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone. One can only update channels owned by the current viewer.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn notification_channel_set_enabled(
+        &self,
+        ctx: &Context<'_>,
+        id: DbId,
+        enabled: bool,
+    ) -> GraphqlResult<NotificationChannelUpdatePayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let channel = db::notification_channels::Entity::find_by_id(id)
+            .filter(db::notification_channels::owned_by(user.id))
+            .one_or_err(&state.db)
+            .await?;
+
+        let mut update = channel.into_active_model();
+        update.enabled = enabled.into_active_value();
+        let model = update.update(&state.db).await?;
+
+        Ok(NotificationChannelUpdatePayload { data: model.into() })
+    }
+
+    /// Delete notification channels of the current user.
+    /// All notifications still queued for these channels will be deleted as well.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone. One can only delete channels owned by the current viewer.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn notification_channel_delete(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "All channel ids to delete")] ids: Vec<DbId>,
+    ) -> GraphqlResult<NotificationChannelDeletePayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        if ids.len() > MAX_AMOUNT_DELETE {
+            return Err(GraphqlError::InvalidInput(format!(
+                "One can delete at most `{}` items at a time, not `{}`",
+                MAX_AMOUNT_DELETE,
+                ids.len()
+            )));
+        }
+
+        let result = db::notification_channels::Entity::delete_by_ids(ids.iter().copied())
+            .filter(db::notification_channels::owned_by(user.id))
+            .exec(&state.db)
+            .await?;
+
+        tracing::debug!(deleted = result.rows_affected, "Deleted notification channels");
+
+        Ok(NotificationChannelDeletePayload {
+            deleted: result.rows_affected,
+        })
+    }
+
+    /// Immediately send a test notification to the given channel, bypassing the queue.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone. One can only test channels owned by the current viewer.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn notification_channel_test(
+        &self,
+        ctx: &Context<'_>,
+        id: DbId,
+    ) -> GraphqlResult<NotificationChannelTestPayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let channel = db::notification_channels::Entity::find_by_id(id)
+            .filter(db::notification_channels::owned_by(user.id))
+            .one_or_err(&state.db)
+            .await?;
+
+        let notification = Notification::new(
+            NotificationKind::Test,
+            "Wgg test notification",
+            format!("This channel (`{}`) is set up correctly.", channel.name),
+        );
+        let result = match ChannelConfig::from_model(&channel, &state.credentials_cipher)?
+            .into_channel(notifications::http_client())
+        {
+            Ok(sender) => sender.send(&notification).await,
+            Err(e) => Err(e),
+        };
+
+        Ok(NotificationChannelTestPayload {
+            success: result.is_ok(),
+            error: result.err().map(|e| format!("{e:#}")),
+        })
+    }
+}
+
+#[derive(Debug, async_graphql::InputObject)]
+pub struct NotificationChannelCreateInput {
+    /// A name to recognise this channel by.
+    pub name: String,
+    pub webhook: Option<WebhookConfig>,
+    pub smtp: Option<SmtpConfig>,
+    pub push: Option<PushConfig>,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct NotificationChannelCreatePayload {
+    /// The newly created channel
+    pub data: UserNotificationChannel,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct NotificationChannelUpdatePayload {
+    /// The updated channel
+    pub data: UserNotificationChannel,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct NotificationChannelDeletePayload {
+    /// The amount of channels deleted.
+    pub deleted: u64,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct NotificationChannelTestPayload {
+    pub success: bool,
+    /// The reason the test notification couldn't be delivered, if any.
+    pub error: Option<String>,
+}
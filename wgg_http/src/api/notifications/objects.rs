@@ -0,0 +1,98 @@
+use crate::api::{ContextExt, GraphqlResult};
+use crate::db;
+use crate::notifications::{ChannelConfig, NotificationKind};
+use async_graphql::{ComplexObject, Context, SimpleObject};
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use wgg_db_entity::DbId;
+
+/// A channel through which the user receives notifications, such as watchlist alerts.
+///
+/// The configuration itself is not exposed, as it can contain secrets.
+#[derive(Clone, Debug, SimpleObject)]
+#[graphql(complex)]
+pub struct UserNotificationChannel {
+    pub id: DbId,
+    pub name: String,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    #[graphql(skip)]
+    pub model: db::notification_channels::Model,
+}
+
+#[ComplexObject]
+impl UserNotificationChannel {
+    /// The kind of channel.
+    pub async fn kind(&self, ctx: &Context<'_>) -> GraphqlResult<NotificationChannelKind> {
+        let config = ChannelConfig::from_model(&self.model, &ctx.wgg_state().credentials_cipher)?;
+
+        Ok(match config {
+            ChannelConfig::Webhook(_) => NotificationChannelKind::Webhook,
+            ChannelConfig::Smtp(_) => NotificationChannelKind::Smtp,
+            ChannelConfig::Push(_) => NotificationChannelKind::Push,
+        })
+    }
+
+    /// All notifications which are still waiting to be delivered to this channel, including failed deliveries.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn queued(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<QueuedNotification>> {
+        let state = ctx.wgg_state();
+
+        let queued = db::notification_queue::Entity::find()
+            .filter(db::notification_queue::Column::ChannelId.eq(self.id))
+            .order_by_asc(db::notification_queue::Column::CreatedAt)
+            .all(&state.db)
+            .await?;
+
+        Ok(queued.into_iter().map(|i| i.into()).collect())
+    }
+}
+
+impl From<db::notification_channels::Model> for UserNotificationChannel {
+    fn from(model: db::notification_channels::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name.clone(),
+            enabled: model.enabled,
+            created_at: model.created_at,
+            model,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum NotificationChannelKind {
+    Webhook,
+    Smtp,
+    Push,
+}
+
+/// A notification waiting to be delivered.
+#[derive(Clone, Debug, SimpleObject)]
+pub struct QueuedNotification {
+    pub id: DbId,
+    pub kind: Option<NotificationKind>,
+    pub title: String,
+    /// The amount of failed delivery attempts so far.
+    pub attempts: i32,
+    /// The error of the most recent failed delivery attempt.
+    pub last_error: Option<String>,
+    /// When the next delivery will be attempted.
+    /// If the maximum amount of attempts has been reached no further attempts will be made.
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<db::notification_queue::Model> for QueuedNotification {
+    fn from(model: db::notification_queue::Model) -> Self {
+        Self {
+            id: model.id,
+            kind: model.kind.parse().ok(),
+            title: model.title,
+            attempts: model.attempts,
+            last_error: model.last_error,
+            next_attempt_at: model.next_attempt_at,
+            created_at: model.created_at,
+        }
+    }
+}
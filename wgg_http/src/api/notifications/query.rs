@@ -0,0 +1,30 @@
+use crate::api::notifications::objects::UserNotificationChannel;
+use crate::api::{ContextExt, GraphqlResult};
+use crate::db;
+use async_graphql::{Context, Object};
+use sea_orm::{EntityTrait, QueryFilter, QueryOrder};
+
+#[derive(Default)]
+pub struct NotificationQuery;
+
+#[Object]
+impl NotificationQuery {
+    /// Returns all notification channels of the current user.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn notification_channels(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<UserNotificationChannel>> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let channels = db::notification_channels::Entity::find()
+            .filter(db::notification_channels::owned_by(user.id))
+            .order_by_asc(db::notification_channels::Column::CreatedAt)
+            .all(&state.db)
+            .await?;
+
+        Ok(channels.into_iter().map(|i| i.into()).collect())
+    }
+}
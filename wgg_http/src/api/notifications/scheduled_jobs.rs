@@ -0,0 +1,26 @@
+use crate::api::AppState;
+use crate::notifications;
+use wgg_scheduler::schedule::Schedule;
+use wgg_scheduler::Job;
+
+pub fn create_job_deliver_notifications(schedule: Schedule, state: AppState) -> Job {
+    let client = notifications::http_client();
+
+    Job::new(schedule, move |_, _| {
+        let state = state.clone();
+        let client = client.clone();
+        async move {
+            let span = tracing::span!(tracing::Level::DEBUG, "Scheduled Job - Deliver Notifications");
+            let _enter = span.enter();
+
+            let report = notifications::deliver_pending(&state.db, &client, &state.credentials_cipher).await?;
+
+            if report.delivered + report.failed + report.abandoned > 0 {
+                tracing::debug!(?report, "Processed notification queue");
+            }
+
+            Ok(())
+        }
+    })
+    .unwrap()
+}
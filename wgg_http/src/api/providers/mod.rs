@@ -1,5 +1,26 @@
+use crate::api::{ContextExt, GraphqlResult};
+use async_graphql::Context;
+use wgg_providers::models::Provider;
+use wgg_providers::AccountCart;
+
+mod mutation;
 mod object;
 mod query;
 
+pub use mutation::ProviderMutation;
 pub use object::*;
 pub use query::ProviderQuery;
+
+/// Retrieve the Picnic account the viewer linked, whose login the Picnic login operations should act on.
+///
+/// Returns `None` if the server-wide Picnic login should be used instead, which only admins can act on.
+async fn viewer_picnic_account(ctx: &Context<'_>) -> GraphqlResult<Option<AccountCart>> {
+    let state = ctx.wgg_state();
+    let account = crate::api::accounts::user_cart_for(state, ctx.wgg_user()?, Provider::PICNIC).await?;
+
+    if account.is_none() {
+        let _ = ctx.wgg_admin()?;
+    }
+
+    Ok(account)
+}
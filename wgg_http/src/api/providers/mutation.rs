@@ -0,0 +1,70 @@
+use crate::api::providers::object::PicnicLoginStatus;
+use crate::api::providers::viewer_picnic_account;
+use crate::api::{ContextExt, GraphqlResult};
+use async_graphql::{Context, Object};
+
+#[derive(Default)]
+pub struct ProviderMutation;
+
+#[Object]
+impl ProviderMutation {
+    /// Submit the code Picnic sent by SMS to finish logging in to the Picnic account linked by the viewer, or the
+    /// server-wide Picnic account if the viewer didn't link one.
+    ///
+    /// Should the code be rejected the login keeps waiting, and another code can be submitted. Once the code expired
+    /// the login has to be restarted with `picnicRestartLogin`.
+    ///
+    /// # Accessible By
+    ///
+    /// Users with a linked Picnic account, and admins.
+    #[tracing::instrument(skip(self, ctx, code))]
+    async fn picnic_submit_second_factor(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The code sent by SMS", secret)] code: String,
+    ) -> GraphqlResult<PicnicSecondFactorPayload> {
+        let state = ctx.wgg_state();
+
+        let status = match viewer_picnic_account(ctx).await? {
+            Some(account) => {
+                account.picnic_submit_second_factor(code.trim()).await?;
+                account.picnic_login_status().await?
+            }
+            None => {
+                state.providers.picnic_submit_second_factor(code.trim()).await?;
+                state.providers.picnic_login_status().await?
+            }
+        };
+
+        tracing::info!("Picnic login completed with a second factor code");
+
+        Ok(PicnicSecondFactorPayload { status: status.into() })
+    }
+
+    /// Discard the pending login of the Picnic account linked by the viewer, or the server-wide Picnic account if the
+    /// viewer didn't link one, and log in again.
+    ///
+    /// Picnic sends a new code by SMS if the login requires one, which should then be submitted with
+    /// `picnicSubmitSecondFactor`.
+    ///
+    /// # Accessible By
+    ///
+    /// Users with a linked Picnic account, and admins.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn picnic_restart_login(&self, ctx: &Context<'_>) -> GraphqlResult<PicnicSecondFactorPayload> {
+        let state = ctx.wgg_state();
+
+        let status = match viewer_picnic_account(ctx).await? {
+            Some(account) => account.picnic_restart_login().await?,
+            None => state.providers.picnic_restart_login().await?,
+        };
+
+        Ok(PicnicSecondFactorPayload { status: status.into() })
+    }
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct PicnicSecondFactorPayload {
+    /// The state of the login after submitting the code, or restarting the login.
+    pub status: PicnicLoginStatus,
+}
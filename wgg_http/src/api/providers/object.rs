@@ -1,15 +1,195 @@
 use crate::api::aggregate_ingredients::AggregateIngredient;
 use crate::api::{ContextExt, GraphqlResult};
+use crate::db;
 use async_graphql::Context;
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder};
 use std::borrow::Cow;
 use wgg_db_entity::DbId;
 use wgg_providers::models::{
-    Provider, SublistId, UnavailableItem, WggProduct, WggSaleCategory, WggSaleGroupComplete, WggSaleGroupLimited,
-    WggSaleItem, WggSearchProduct,
+    MultiProviderResult, OutcomeStatus, PriceInfo, Provider, ProviderInfo, ProviderOutcome, SublistId, UnavailableItem,
+    WggProduct, WggSaleCategory, WggSaleGroupComplete, WggSaleGroupLimited, WggSaleItem, WggSearchProduct,
 };
+use wgg_providers::pagination::MultiProviderPagination;
+use wgg_providers::ProductMatch;
 
 // ** Implementations **
 
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+pub struct PriceHistoryEntry {
+    pub price_info: PriceInfo,
+    /// The label of the sale active at the time, if any.
+    pub sale_label: Option<String>,
+    pub observed_at: DateTime<Utc>,
+}
+
+impl From<db::price_history::Model> for PriceHistoryEntry {
+    fn from(model: db::price_history::Model) -> Self {
+        Self {
+            price_info: db::price_history::price_info(&model),
+            sale_label: model.sale_label,
+            observed_at: model.observed_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+#[graphql(complex)]
+pub struct UnmatchedSaleLabel {
+    #[graphql(skip)]
+    pub provider: Provider,
+    /// The sale label for which no [SaleType](wgg_providers::models::sale_types::SaleType) could be derived.
+    pub label: String,
+    /// The amount of promotions currently using this label.
+    pub occurrences: u32,
+}
+
+#[async_graphql::ComplexObject]
+impl UnmatchedSaleLabel {
+    /// Grocery store information associated with this label
+    async fn provider_info(&self) -> ProviderInfo {
+        self.provider.as_provider_info()
+    }
+}
+
+/// A provider which is active on this server, and its current health.
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+pub struct ActiveProvider {
+    #[graphql(flatten)]
+    pub info: ProviderInfo,
+    /// `DEGRADED` if requests to the provider are known to fail until some action is taken.
+    pub status: OutcomeStatus,
+    /// Why the provider is degraded, `None` if it isn't.
+    pub message: Option<String>,
+}
+
+impl From<ProviderOutcome> for ActiveProvider {
+    fn from(outcome: ProviderOutcome) -> Self {
+        Self {
+            info: outcome.provider.as_provider_info(),
+            status: outcome.status,
+            message: outcome.message,
+        }
+    }
+}
+
+/// The state of a Picnic login, either of the account linked by the viewer or of the server-wide account.
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+pub struct PicnicLoginStatus {
+    pub state: PicnicLoginState,
+    /// When the second factor code was sent, if the login is waiting for it.
+    pub second_factor_requested_at: Option<DateTime<Utc>>,
+    /// When the second factor code expires, if the login is waiting for it.
+    pub second_factor_expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum PicnicLoginState {
+    LoggedIn,
+    /// Not logged in yet, the login will be attempted on the next Picnic request.
+    LoggedOut,
+    /// The login is waiting for the code Picnic sent by SMS, see `picnicSubmitSecondFactor`.
+    ///
+    /// The Picnic provider is degraded until then, all requests to it fail.
+    AwaitingSecondFactor,
+    /// The code Picnic sent by SMS expired before it was submitted, see `picnicRestartLogin`.
+    ///
+    /// The Picnic provider is degraded until then, all requests to it fail.
+    SecondFactorExpired,
+    /// Picnic is not configured on this server.
+    Unavailable,
+}
+
+impl From<wgg_providers::PicnicLoginStatus> for PicnicLoginStatus {
+    fn from(status: wgg_providers::PicnicLoginStatus) -> Self {
+        match status {
+            wgg_providers::PicnicLoginStatus::LoggedIn => Self::new(PicnicLoginState::LoggedIn),
+            wgg_providers::PicnicLoginStatus::LoggedOut => Self::new(PicnicLoginState::LoggedOut),
+            wgg_providers::PicnicLoginStatus::AwaitingSecondFactor {
+                requested_at,
+                expires_at,
+            } => Self {
+                state: PicnicLoginState::AwaitingSecondFactor,
+                second_factor_requested_at: Some(requested_at),
+                second_factor_expires_at: Some(expires_at),
+            },
+            wgg_providers::PicnicLoginStatus::SecondFactorExpired { requested_at } => Self {
+                state: PicnicLoginState::SecondFactorExpired,
+                second_factor_requested_at: Some(requested_at),
+                second_factor_expires_at: None,
+            },
+        }
+    }
+}
+
+impl PicnicLoginStatus {
+    pub fn new(state: PicnicLoginState) -> Self {
+        Self {
+            state,
+            second_factor_requested_at: None,
+            second_factor_expires_at: None,
+        }
+    }
+}
+
+/// A product at another provider which is likely equivalent to the product it was found for.
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+pub struct ProductEquivalent {
+    pub product: WggSearchProductWrapper,
+    /// How likely the product is an equivalent, between `0.0` and `1.0`.
+    pub score: f64,
+}
+
+impl From<ProductMatch> for ProductEquivalent {
+    fn from(value: ProductMatch) -> Self {
+        Self {
+            product: value.product.into(),
+            score: value.score,
+        }
+    }
+}
+
+/// A page of search results gathered from all providers.
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+pub struct SearchAllPage {
+    /// The results of all providers, most relevant first.
+    pub items: Vec<WggSearchProductWrapper>,
+    /// The sum of the total amount of results of all providers.
+    pub total_count: u64,
+    /// Pass this as `after` to retrieve the next page, `null` if all results have been returned.
+    pub next_cursor: Option<String>,
+    /// All providers which failed to respond, or whose results are likely incomplete.
+    pub errors: Vec<ProviderOutcome>,
+}
+
+impl From<MultiProviderPagination<WggSearchProduct>> for SearchAllPage {
+    fn from(value: MultiProviderPagination<WggSearchProduct>) -> Self {
+        Self {
+            items: value.items.into_iter().map(|i| i.into()).collect(),
+            total_count: value.total_items as u64,
+            next_cursor: value.next.map(|cursor| cursor.to_string()),
+            errors: value.outcomes.into_iter().filter(|outcome| !outcome.is_ok()).collect(),
+        }
+    }
+}
+
+/// The promotions of all providers.
+#[derive(Debug, Clone, async_graphql::SimpleObject)]
+pub struct AllPromotions {
+    pub items: Vec<WggSaleCategoryWrapper>,
+    /// All providers which failed to respond, or whose promotions are likely incomplete.
+    pub errors: Vec<ProviderOutcome>,
+}
+
+impl From<MultiProviderResult<WggSaleCategory>> for AllPromotions {
+    fn from(value: MultiProviderResult<WggSaleCategory>) -> Self {
+        Self {
+            items: value.items.into_iter().map(|i| i.into()).collect(),
+            errors: value.outcomes.into_iter().filter(|outcome| !outcome.is_ok()).collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ProductAppInfo<'a> {
     pub product_id: &'a str,
@@ -65,6 +245,18 @@ impl WggSearchProductWrapper {
         let state = ctx.wgg_state();
         state.providers.product_sale_id(self.item.provider, &self.item.id)
     }
+
+    /// Return all observed prices of this product, oldest first.
+    ///
+    /// Can be used to judge whether the current sale is actually a good deal.
+    #[tracing::instrument(skip(ctx))]
+    pub async fn price_history(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Only return observations made after this moment")] since: Option<DateTime<Utc>>,
+    ) -> GraphqlResult<Vec<PriceHistoryEntry>> {
+        get_price_history(ctx, self.item.provider, &self.item.id, since).await
+    }
 }
 
 #[async_graphql::ComplexObject]
@@ -83,6 +275,52 @@ impl WggProductWrapper {
         let state = ctx.wgg_state();
         state.providers.product_sale_id(self.item.provider, &self.item.id)
     }
+
+    /// Return all observed prices of this product, oldest first.
+    ///
+    /// Can be used to judge whether the current sale is actually a good deal.
+    #[tracing::instrument(skip(ctx))]
+    pub async fn price_history(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Only return observations made after this moment")] since: Option<DateTime<Utc>>,
+    ) -> GraphqlResult<Vec<PriceHistoryEntry>> {
+        get_price_history(ctx, self.item.provider, &self.item.id, since).await
+    }
+
+    /// Return products at the other providers which are likely equivalent to this product, best match first.
+    #[tracing::instrument(skip(ctx))]
+    pub async fn equivalents(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<ProductEquivalent>> {
+        let state = ctx.wgg_state();
+        let matches = state.providers.equivalents(&self.item.clone().into()).await?;
+
+        Ok(matches.into_iter().map(ProductEquivalent::from).collect())
+    }
+}
+
+async fn get_price_history(
+    ctx: &Context<'_>,
+    provider: Provider,
+    product_id: &str,
+    since: Option<DateTime<Utc>>,
+) -> GraphqlResult<Vec<PriceHistoryEntry>> {
+    // Assert that the user is logged in.
+    let _ = ctx.wgg_user()?;
+    let state = ctx.wgg_state();
+    let provider_id = state.provider_id_from_provider(&provider);
+
+    let mut query = db::price_history::find_for_product(provider_id, product_id);
+
+    if let Some(since) = since {
+        query = query.filter(db::price_history::Column::ObservedAt.gte(since));
+    }
+
+    let result = query
+        .order_by_asc(db::price_history::Column::ObservedAt)
+        .all(&state.db)
+        .await?;
+
+    Ok(result.into_iter().map(|model| model.into()).collect())
 }
 
 // ** Wrapper Objects **
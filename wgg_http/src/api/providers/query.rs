@@ -1,9 +1,18 @@
-use crate::api::pagination::{ConnectionResult, QueryResult};
-use crate::api::providers::object::{WggProductWrapper, WggSaleCategoryWrapper, WggSaleGroupCompleteWrapper};
+use crate::api::error::GraphqlError;
+use crate::api::pagination::{ConnectionResult, QueryResult, DEFAULT_PAGE_SIZE};
+use crate::api::providers::object::{
+    ActiveProvider, AllPromotions, PicnicLoginState, PicnicLoginStatus, SearchAllPage, UnmatchedSaleLabel,
+    WggProductWrapper, WggSaleCategoryWrapper, WggSaleGroupCompleteWrapper,
+};
+use crate::api::providers::viewer_picnic_account;
 use crate::api::providers::WggSearchProductWrapper;
 use crate::api::{ContextExt, GraphqlResult};
 use async_graphql::{Context, Object};
-use wgg_providers::models::{Provider, ProviderInfo, WggAutocomplete};
+use itertools::Itertools;
+use std::collections::HashMap;
+use wgg_providers::models::{parse_gtin, Provider, WggAutocomplete, WggCategory, WggSaleItem};
+use wgg_providers::pagination::MultiProviderCursor;
+use wgg_providers::ProviderError;
 
 #[derive(Default)]
 pub struct ProviderQuery;
@@ -14,7 +23,7 @@ impl ProviderQuery {
     async fn pro_autocomplete(
         &self,
         ctx: &Context<'_>,
-        #[graphql(desc = "The product vendor/provider", default_with = "Provider::Picnic")] provider: Provider,
+        #[graphql(desc = "The product vendor/provider", default_with = "Provider::PICNIC")] provider: Provider,
         #[graphql(desc = "The current user input")] query: String,
     ) -> GraphqlResult<Vec<WggAutocomplete>> {
         let state = ctx.wgg_state();
@@ -50,23 +59,38 @@ impl ProviderQuery {
         .await
     }
 
+    /// Search all active providers at the same time, with the results of all providers interleaved by relevance.
+    ///
+    /// Providers which fail to respond are listed in `errors`, their results are missing from the page.
     #[tracing::instrument(skip(self, ctx))]
     async fn pro_search_all(
         &self,
         ctx: &Context<'_>,
         #[graphql(desc = "The product query")] query: String,
-    ) -> GraphqlResult<Vec<WggSearchProductWrapper>> {
+        #[graphql(desc = "The `nextCursor` of the previous page")] after: Option<String>,
+        #[graphql(desc = "The maximum amount of results to return")] first: Option<i32>,
+    ) -> GraphqlResult<SearchAllPage> {
         let state = ctx.wgg_state();
-        let response = state.providers.search_all(query).await?;
+        let cursor = after
+            .map(|cursor| cursor.parse::<MultiProviderCursor>())
+            .transpose()
+            .map_err(|e| GraphqlError::InvalidInput(format!("Invalid cursor: {e}")))?;
+        let limit = first
+            .map(usize::try_from)
+            .transpose()
+            .map_err(|_| GraphqlError::InvalidInput("`first` can't be negative".to_string()))?
+            .unwrap_or(DEFAULT_PAGE_SIZE);
+
+        let response = state.providers.search_all(query, cursor.as_ref(), limit).await;
 
-        Ok(response.items.into_iter().map(|i| i.into()).collect())
+        Ok(response.into())
     }
 
     #[tracing::instrument(skip(self, ctx))]
     async fn pro_product(
         &self,
         ctx: &Context<'_>,
-        #[graphql(desc = "The product vendor/provider", default_with = "Provider::Picnic")] provider: Provider,
+        #[graphql(desc = "The product vendor/provider", default_with = "Provider::PICNIC")] provider: Provider,
         #[graphql(desc = "The product id")] product_id: String,
     ) -> GraphqlResult<WggProductWrapper> {
         let state = ctx.wgg_state();
@@ -75,6 +99,79 @@ impl ProviderQuery {
         Ok(response.into())
     }
 
+    /// Find the product with the given barcode at every active provider.
+    ///
+    /// Providers which don't sell the product are left out, the result is empty if none of them do.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn pro_by_barcode(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The scanned GTIN/EAN barcode")] gtin: String,
+    ) -> GraphqlResult<Vec<WggSearchProductWrapper>> {
+        // Assert that the user is logged in.
+        let _ = ctx.wgg_user()?;
+        let state = ctx.wgg_state();
+        let gtin =
+            parse_gtin(&gtin).ok_or_else(|| GraphqlError::InvalidInput(format!("`{gtin}` is not a GTIN barcode")))?;
+        let response = state.providers.products_by_gtin(&gtin).await?;
+
+        Ok(response.into_iter().map(|i| i.into()).collect())
+    }
+
+    /// Return the category tree of the catalogue of the given provider.
+    ///
+    /// Browse the products of a category with `proCategoryProducts`.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn pro_categories(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The product vendor/provider", default_with = "Provider::PICNIC")] provider: Provider,
+        #[graphql(desc = "Only return the sub-categories of this category")] parent_id: Option<String>,
+    ) -> GraphqlResult<Vec<WggCategory>> {
+        // Assert that the user is logged in.
+        let _ = ctx.wgg_user()?;
+        let state = ctx.wgg_state();
+        let categories = state.providers.categories(provider).await?;
+
+        let Some(parent_id) = parent_id else {
+            return Ok(categories);
+        };
+
+        categories
+            .iter()
+            .find_map(|category| category.find(&parent_id))
+            .map(|parent| parent.children.clone())
+            .ok_or_else(|| GraphqlError::InvalidInput(format!("Category `{parent_id}` does not exist")))
+    }
+
+    /// Return the products in the given category, including those of its sub-categories.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn pro_category_products(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+        #[graphql(desc = "Filters for the collection")] filters: CategoryFilter,
+    ) -> ConnectionResult<WggSearchProductWrapper> {
+        // Assert that the user is logged in.
+        let _ = ctx.wgg_user()?;
+        let state = ctx.wgg_state();
+
+        crate::api::pagination::offset_query(after, first, |offset, limit| async move {
+            let response = state
+                .providers
+                .category_products(filters.provider, filters.category_id, offset.map(|i| i.index() as u32))
+                .await?;
+            let total_count = response.total_items as u64;
+
+            Ok(QueryResult {
+                iter: response.items.into_iter().take(limit).map(|i| i.into()),
+                total_count,
+            })
+        })
+        .await
+    }
+
     #[tracing::instrument(skip(self, ctx))]
     async fn pro_promotions(
         &self,
@@ -87,7 +184,7 @@ impl ProviderQuery {
         let _ = ctx.wgg_user()?;
         let state = ctx.wgg_state();
         let filter = filters.unwrap_or(PromotionsFilter {
-            provider: Provider::Picnic,
+            provider: Provider::PICNIC,
         });
 
         crate::api::pagination::offset_query(after, first, |offset, limit| async move {
@@ -106,19 +203,22 @@ impl ProviderQuery {
         .await
     }
 
+    /// Return the promotions of all active providers.
+    ///
+    /// Providers which fail to respond are listed in `errors`, their promotions are missing from the result.
     #[tracing::instrument(skip(self, ctx))]
-    async fn pro_promotions_all(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<WggSaleCategoryWrapper>> {
+    async fn pro_promotions_all(&self, ctx: &Context<'_>) -> AllPromotions {
         let state = ctx.wgg_state();
-        let response = state.providers.promotions_all().await?;
+        let response = state.providers.promotions_all().await;
 
-        Ok(response.into_iter().map(|i| i.into()).collect())
+        response.into()
     }
 
     #[tracing::instrument(skip(self, ctx))]
     async fn pro_promotions_sublist(
         &self,
         ctx: &Context<'_>,
-        #[graphql(desc = "The product vendor/provider", default_with = "Provider::Picnic")] provider: Provider,
+        #[graphql(desc = "The product vendor/provider", default_with = "Provider::PICNIC")] provider: Provider,
         #[graphql(desc = "The sublist id")] sublist_id: String,
     ) -> GraphqlResult<WggSaleGroupCompleteWrapper> {
         let state = ctx.wgg_state();
@@ -127,18 +227,86 @@ impl ProviderQuery {
         Ok(response.into())
     }
 
+    /// Return all labels of the current promotions which don't match any of the sale label rules.
+    ///
+    /// Sales with such a label can't be taken into account for cart tallies, see the `sale_rules.toml` file in the
+    /// config directory for the rules.
+    ///
+    /// # Accessible By
+    ///
+    /// Admins.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn pro_unmatched_sale_labels(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<UnmatchedSaleLabel>> {
+        let _ = ctx.wgg_admin()?;
+        let state = ctx.wgg_state();
+        let categories = state.providers.promotions_all().await.items;
+
+        let mut occurrences: HashMap<(Provider, String), u32> = HashMap::new();
+
+        let labels = categories
+            .iter()
+            .flat_map(|category| &category.items)
+            .flat_map(|item| match item {
+                WggSaleItem::Product(product) => product
+                    .sale_information
+                    .as_ref()
+                    .map(|sale| (product.provider, &sale.label)),
+                WggSaleItem::Group(group) => Some((group.provider, &group.sale_info.label)),
+            });
+
+        for (provider, label) in labels {
+            if state.providers.parse_sale_label(label).is_none() {
+                *occurrences.entry((provider, label.clone())).or_default() += 1;
+            }
+        }
+
+        Ok(occurrences
+            .into_iter()
+            .map(|((provider, label), occurrences)| UnmatchedSaleLabel {
+                provider,
+                label,
+                occurrences,
+            })
+            .sorted_by(|a, b| a.provider.cmp(&b.provider).then(b.occurrences.cmp(&a.occurrences)))
+            .collect())
+    }
+
+    /// Return the state of the login of the Picnic account linked by the viewer, or the server-wide Picnic login if
+    /// the viewer didn't link one.
+    ///
+    /// # Accessible By
+    ///
+    /// Users with a linked Picnic account, and admins.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn picnic_login_status(&self, ctx: &Context<'_>) -> GraphqlResult<PicnicLoginStatus> {
+        let state = ctx.wgg_state();
+
+        if let Some(account) = viewer_picnic_account(ctx).await? {
+            return Ok(account.picnic_login_status().await?.into());
+        }
+
+        match state.providers.picnic_login_status().await {
+            Ok(status) => Ok(status.into()),
+            Err(ProviderError::ProviderUninitialised(_)) => Ok(PicnicLoginStatus::new(PicnicLoginState::Unavailable)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Return all providers which are currently active for this server.
+    ///
+    /// A provider is `DEGRADED` if its requests are known to fail, such as Picnic whilst the server-wide login waits
+    /// for its second factor code.
     #[tracing::instrument(skip(self, ctx))]
-    async fn pro_providers(&self, ctx: &Context<'_>) -> Vec<ProviderInfo> {
+    async fn pro_providers(&self, ctx: &Context<'_>) -> Vec<ActiveProvider> {
         let state = ctx.wgg_state();
 
         state
             .providers
-            .active_providers()
-            .map(|prov| ProviderInfo {
-                provider: prov.provider(),
-                logo_url: prov.metadata().logo_url,
-            })
+            .provider_statuses()
+            .await
+            .into_iter()
+            .map(ActiveProvider::from)
+            .sorted_by(|a, b| a.info.cmp(&b.info))
             .collect()
     }
 }
@@ -148,6 +316,14 @@ struct PromotionsFilter {
     pub provider: Provider,
 }
 
+#[derive(Debug, Clone, async_graphql::InputObject)]
+struct CategoryFilter {
+    /// The provider to browse
+    pub provider: Provider,
+    /// The id of the category, see `proCategories`
+    pub category_id: String,
+}
+
 #[derive(Debug, Clone, async_graphql::InputObject)]
 struct SearchFilter {
     /// The provider to search in
@@ -1,15 +1,26 @@
 //! Re-exports all scheduled jobs of sub-features.
-use crate::api::{auth, cart, AppState};
+use crate::api::{auth, cart, notifications, watchlist, AppState};
 use wgg_scheduler::JobScheduler;
 
 /// Schedule all relevant jobs for this API.
 pub fn schedule_all_jobs(scheduler: &JobScheduler, state: AppState) {
     let cart_data_schedule = "0 0 * * * * *".try_into().unwrap();
     let auth_token_schedule = "0 0 * * * * *".try_into().unwrap();
+    let watchlist_schedule = "0 30 * * * * *".try_into().unwrap();
+    let notification_schedule = "0/30 * * * * * *".try_into().unwrap();
+    let order_import_schedule = "0 15 * * * * *".try_into().unwrap();
 
     let cart_job = cart::scheduled_jobs::create_job_keep_cart_data_fresh(cart_data_schedule, state.clone());
-    let token_job = auth::scheduled_jobs::create_remove_expired_auth_tokens(auth_token_schedule, state);
+    let order_import_job =
+        cart::scheduled_jobs::create_job_import_delivered_orders(order_import_schedule, state.clone());
+    let token_job = auth::scheduled_jobs::create_remove_expired_auth_tokens(auth_token_schedule, state.clone());
+    let watchlist_job = watchlist::scheduled_jobs::create_job_check_watchlist(watchlist_schedule, state.clone());
+    let notification_job =
+        notifications::scheduled_jobs::create_job_deliver_notifications(notification_schedule, state);
 
     scheduler.push(cart_job);
+    scheduler.push(order_import_job);
     scheduler.push(token_job);
+    scheduler.push(watchlist_job);
+    scheduler.push(notification_job);
 }
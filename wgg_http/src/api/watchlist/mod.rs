@@ -0,0 +1,8 @@
+mod mutation;
+mod objects;
+mod query;
+pub mod scheduled_jobs;
+mod service;
+
+pub use mutation::WatchlistMutation;
+pub use query::WatchlistQuery;
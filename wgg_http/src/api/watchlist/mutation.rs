@@ -0,0 +1,187 @@
+use crate::api::error::GraphqlError;
+use crate::api::watchlist::objects::WatchlistItem;
+use crate::api::{ContextExt, GraphqlResult, ProductId, MAX_AMOUNT_DELETE};
+use crate::db;
+use async_graphql::{Context, Object};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveValue, QueryFilter, TransactionTrait};
+use wgg_db_entity::{DbId, EntityExt, SelectExt};
+use wgg_providers::models::{CentPrice, Provider};
+
+#[derive(Default)]
+pub struct WatchlistMutation;
+
+#[Object]
+impl WatchlistMutation {
+    /// Add a product, or an aggregate ingredient, to the watchlist of the current user.
+    /// Exactly one of `product` or `aggregate_id` should be given, and at least one condition should be set.
+    ///
+    /// # Returns
+    ///
+    /// The newly created watchlist item.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone. One can only watch aggregate ingredients owned by the current viewer.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn watchlist_add(&self, ctx: &Context<'_>, input: WatchlistAddInput) -> GraphqlResult<WatchlistAddPayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        if input.target_price.is_none() && !input.any_sale {
+            return Err(GraphqlError::InvalidInput(
+                "Either a `targetPrice` or `anySale` should be set".to_string(),
+            ));
+        }
+
+        let mut new_item = db::watchlist::ActiveModel {
+            user_id: user.id.into_active_value(),
+            target_price: input.target_price.map(|price| price as i32).into_active_value(),
+            any_sale: input.any_sale.into_active_value(),
+            ..Default::default()
+        };
+
+        match (input.product, input.aggregate_id) {
+            (Some(product), None) => {
+                // Ensure the product actually exists
+                let _ = state.providers.search_product(product.provider, &product.id).await?;
+
+                new_item.provider_id = Some(state.provider_id_from_provider(&product.provider)).into_active_value();
+                new_item.product_id = Some(product.id).into_active_value();
+            }
+            (None, Some(aggregate_id)) => {
+                let aggregate = db::agg_ingredients::Entity::find_by_id(aggregate_id)
+                    .one_or_err(&state.db)
+                    .await?;
+
+                if aggregate.created_by != user.id {
+                    return Err(GraphqlError::Unauthorized);
+                }
+
+                new_item.aggregate_id = Some(aggregate_id).into_active_value();
+            }
+            _ => {
+                return Err(GraphqlError::InvalidInput(
+                    "Exactly one of `product` or `aggregateId` should be set".to_string(),
+                ));
+            }
+        }
+
+        let model = new_item.insert(&state.db).await?;
+
+        tracing::debug!(new_item=?model, "New watchlist item created");
+
+        Ok(WatchlistAddPayload { data: model.into() })
+    }
+
+    /// Remove items from the watchlist of the current user.
+    /// All alerts of these items will be deleted as well.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone. One can only remove watchlist items owned by the current viewer.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn watchlist_remove(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "All watchlist item ids to remove")] ids: Vec<DbId>,
+    ) -> GraphqlResult<WatchlistRemovePayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        if ids.len() > MAX_AMOUNT_DELETE {
+            return Err(GraphqlError::InvalidInput(format!(
+                "One can delete at most `{}` items at a time, not `{}`",
+                MAX_AMOUNT_DELETE,
+                ids.len()
+            )));
+        }
+
+        let tx = state.db.begin().await?;
+
+        let result = db::watchlist::Entity::delete_by_ids(ids.iter().copied())
+            .filter(db::watchlist::owned_by(user.id))
+            .exec(&tx)
+            .await?;
+
+        if result.rows_affected < ids.len() as u64 {
+            return Err(GraphqlError::InvalidInput(format!(
+                "One can only delete records belonging to the current user, \
+                wanted to delete `{}`, but could only delete `{}`",
+                ids.len(),
+                result.rows_affected
+            )));
+        }
+
+        tx.commit().await?;
+
+        tracing::debug!(deleted = result.rows_affected, "Deleted watchlist items");
+
+        Ok(WatchlistRemovePayload {
+            deleted: result.rows_affected,
+        })
+    }
+
+    /// Mark the given watchlist alerts as seen.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone. One can only mark alerts of watchlist items owned by the current viewer.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn watchlist_alerts_mark_seen(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "All alert ids to mark as seen")] ids: Vec<DbId>,
+    ) -> GraphqlResult<WatchlistAlertsMarkSeenPayload> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let result = db::watchlist_alerts::Entity::update_many()
+            .col_expr(db::watchlist_alerts::Column::Seen, Expr::value(true))
+            .filter(db::watchlist_alerts::Column::Id.is_in(ids))
+            .filter(db::watchlist_alerts::owned_by(user.id))
+            .exec(&state.db)
+            .await?;
+
+        Ok(WatchlistAlertsMarkSeenPayload {
+            updated: result.rows_affected,
+        })
+    }
+}
+
+#[derive(Debug, async_graphql::InputObject)]
+pub struct WatchlistAddInput {
+    /// The provider product to watch.
+    pub product: Option<WatchlistProductInput>,
+    /// The aggregate ingredient to watch, all its constituent products will be checked.
+    pub aggregate_id: Option<DbId>,
+    /// Alert as soon as the price is at, or below, this price.
+    pub target_price: Option<CentPrice>,
+    /// Alert as soon as any sale applies.
+    #[graphql(default)]
+    pub any_sale: bool,
+}
+
+#[derive(Debug, async_graphql::InputObject)]
+pub struct WatchlistProductInput {
+    pub id: ProductId,
+    pub provider: Provider,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct WatchlistAddPayload {
+    /// The newly created watchlist item
+    pub data: WatchlistItem,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct WatchlistRemovePayload {
+    /// The amount of watchlist items deleted.
+    pub deleted: u64,
+}
+
+#[derive(Debug, async_graphql::SimpleObject)]
+pub struct WatchlistAlertsMarkSeenPayload {
+    /// The amount of alerts marked as seen.
+    pub updated: u64,
+}
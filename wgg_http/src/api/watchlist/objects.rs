@@ -0,0 +1,147 @@
+use crate::api::aggregate_ingredients::AggregateIngredient;
+use crate::api::providers::WggSearchProductWrapper;
+use crate::api::{ContextExt, GraphqlResult, ProductId};
+use crate::db;
+use async_graphql::{ComplexObject, Context, SimpleObject};
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use wgg_db_entity::{DbId, SelectExt};
+use wgg_providers::models::{CentPrice, Provider, ProviderInfo};
+
+/// A product, or aggregate ingredient, the user wants to be alerted about.
+///
+/// An alert is recorded whenever the price drops to (or below) the `target_price`, or if `any_sale` is set, whenever a
+/// sale applies.
+#[derive(Clone, Debug, SimpleObject)]
+#[graphql(complex)]
+pub struct WatchlistItem {
+    pub id: DbId,
+    /// Alert as soon as the price is at, or below, this price.
+    pub target_price: Option<CentPrice>,
+    /// Alert as soon as any sale applies.
+    pub any_sale: bool,
+    pub created_at: DateTime<Utc>,
+    #[graphql(skip)]
+    pub model: db::watchlist::Model,
+}
+
+#[ComplexObject]
+impl WatchlistItem {
+    /// The watched provider product, if a product is watched instead of an aggregate ingredient.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn product(&self, ctx: &Context<'_>) -> GraphqlResult<Option<WggSearchProductWrapper>> {
+        let state = ctx.wgg_state();
+
+        let Some((provider_id, product_id)) = self.model.provider_id.zip(self.model.product_id.as_ref()) else {
+            return Ok(None);
+        };
+
+        let product = state
+            .providers
+            .search_product(state.provider_from_id(provider_id), product_id)
+            .await?;
+
+        Ok(Some(product.into()))
+    }
+
+    /// The watched aggregate ingredient, if an aggregate ingredient is watched instead of a product.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn aggregate(&self, ctx: &Context<'_>) -> GraphqlResult<Option<AggregateIngredient>> {
+        let state = ctx.wgg_state();
+
+        let Some(aggregate_id) = self.model.aggregate_id else {
+            return Ok(None);
+        };
+
+        let aggregate = db::agg_ingredients::Entity::find_by_id(aggregate_id)
+            .one_or_err(&state.db)
+            .await?;
+
+        Ok(Some(aggregate.into()))
+    }
+
+    /// All alerts recorded for this watched item, most recent first.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn alerts(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<WatchlistAlert>> {
+        let state = ctx.wgg_state();
+
+        let alerts = db::watchlist_alerts::Entity::find()
+            .filter(db::watchlist_alerts::Column::WatchId.eq(self.id))
+            .order_by_desc(db::watchlist_alerts::Column::CreatedAt)
+            .all(&state.db)
+            .await?;
+
+        Ok(alerts
+            .into_iter()
+            .map(|alert| {
+                let provider = state.provider_from_id(alert.provider_id);
+                WatchlistAlert::from_model(alert, provider)
+            })
+            .collect())
+    }
+}
+
+impl From<db::watchlist::Model> for WatchlistItem {
+    fn from(model: db::watchlist::Model) -> Self {
+        Self {
+            id: model.id,
+            target_price: model.target_price.map(|price| price.max(0) as CentPrice),
+            any_sale: model.any_sale,
+            created_at: model.created_at,
+            model,
+        }
+    }
+}
+
+/// A moment in time where the condition of a watched item held for a particular product.
+#[derive(Clone, Debug, SimpleObject)]
+#[graphql(complex)]
+pub struct WatchlistAlert {
+    pub id: DbId,
+    pub watch_id: DbId,
+    #[graphql(skip)]
+    pub provider: Provider,
+    /// The provider product which matched. For aggregate ingredients this is the constituent product.
+    pub product_id: ProductId,
+    pub display_price: CentPrice,
+    pub original_price: CentPrice,
+    pub sale_label: Option<String>,
+    /// Whether the user has marked this alert as seen.
+    pub seen: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[ComplexObject]
+impl WatchlistAlert {
+    async fn provider_info(&self) -> ProviderInfo {
+        self.provider.as_provider_info()
+    }
+
+    /// The product this alert was recorded for.
+    #[tracing::instrument(skip(self, ctx))]
+    pub async fn product(&self, ctx: &Context<'_>) -> GraphqlResult<WggSearchProductWrapper> {
+        let state = ctx.wgg_state();
+
+        Ok(state
+            .providers
+            .search_product(self.provider, &self.product_id)
+            .await?
+            .into())
+    }
+}
+
+impl WatchlistAlert {
+    pub fn from_model(model: db::watchlist_alerts::Model, provider: Provider) -> Self {
+        Self {
+            id: model.id,
+            watch_id: model.watch_id,
+            provider,
+            product_id: model.product_id,
+            display_price: model.display_price.max(0) as CentPrice,
+            original_price: model.original_price.max(0) as CentPrice,
+            sale_label: model.sale_label,
+            seen: model.seen,
+            created_at: model.created_at,
+        }
+    }
+}
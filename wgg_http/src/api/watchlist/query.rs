@@ -0,0 +1,64 @@
+use crate::api::watchlist::objects::{WatchlistAlert, WatchlistItem};
+use crate::api::{ContextExt, GraphqlResult};
+use crate::db;
+use async_graphql::{Context, Object};
+use sea_orm::{EntityTrait, QueryFilter, QueryOrder};
+
+#[derive(Default)]
+pub struct WatchlistQuery;
+
+#[Object]
+impl WatchlistQuery {
+    /// Returns all items on the watchlist of the current user.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn watchlist(&self, ctx: &Context<'_>) -> GraphqlResult<Vec<WatchlistItem>> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let items = db::watchlist::Entity::find()
+            .filter(db::watchlist::owned_by(user.id))
+            .order_by_desc(db::watchlist::Column::CreatedAt)
+            .all(&state.db)
+            .await?;
+
+        Ok(items.into_iter().map(|i| i.into()).collect())
+    }
+
+    /// Returns all alerts for the watchlist of the current user, most recent first.
+    ///
+    /// # Accessible By
+    ///
+    /// Everyone.
+    #[tracing::instrument(skip(self, ctx))]
+    async fn watchlist_alerts(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Only return alerts which haven't been marked as seen", default)] unseen_only: bool,
+    ) -> GraphqlResult<Vec<WatchlistAlert>> {
+        let state = ctx.wgg_state();
+        let user = ctx.wgg_user()?;
+
+        let mut query = db::watchlist_alerts::Entity::find().filter(db::watchlist_alerts::owned_by(user.id));
+
+        if unseen_only {
+            query = query.filter(db::watchlist_alerts::unseen());
+        }
+
+        let alerts = query
+            .order_by_desc(db::watchlist_alerts::Column::CreatedAt)
+            .all(&state.db)
+            .await?;
+
+        Ok(alerts
+            .into_iter()
+            .map(|alert| {
+                let provider = state.provider_from_id(alert.provider_id);
+                WatchlistAlert::from_model(alert, provider)
+            })
+            .collect())
+    }
+}
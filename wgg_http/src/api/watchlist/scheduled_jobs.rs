@@ -0,0 +1,18 @@
+use crate::api::AppState;
+use wgg_scheduler::schedule::Schedule;
+use wgg_scheduler::Job;
+
+pub fn create_job_check_watchlist(schedule: Schedule, state: AppState) -> Job {
+    Job::new(schedule, move |_, _| {
+        let state = state.clone();
+        async move {
+            let span = tracing::span!(tracing::Level::DEBUG, "Scheduled Job - Check Watchlist");
+            let _enter = span.enter();
+
+            super::service::check_watchlist(&state).await?;
+
+            Ok(())
+        }
+    })
+    .unwrap()
+}
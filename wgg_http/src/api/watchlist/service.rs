@@ -0,0 +1,202 @@
+use crate::api::{AppState, ProductId};
+use crate::db;
+use crate::notifications::{self, Notification, NotificationKind};
+use chrono::Utc;
+use itertools::Itertools;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveValue, QueryFilter, QueryOrder};
+use std::collections::HashMap;
+use wgg_db_entity::DbId;
+use wgg_providers::models::{CentPrice, Provider, WggSearchProduct};
+
+/// Check all watched items against the latest product information, and record an alert for every product whose
+/// watch condition holds.
+///
+/// An alert is only recorded again if the price or sale label changed since the last alert for that product.
+///
+/// # Returns
+///
+/// The amount of newly recorded alerts.
+pub async fn check_watchlist(state: &AppState) -> anyhow::Result<usize> {
+    use futures::stream::StreamExt;
+
+    let watches = db::watchlist::Entity::find().all(&state.db).await?;
+
+    if watches.is_empty() {
+        return Ok(0);
+    }
+
+    let aggregate_ids = watches.iter().flat_map(|w| w.aggregate_id).unique().collect::<Vec<_>>();
+    let mut aggregate_products: HashMap<DbId, Vec<(Provider, ProductId)>> = HashMap::new();
+
+    if !aggregate_ids.is_empty() {
+        let links = db::agg_ingredients_links::Entity::find()
+            .filter(db::agg_ingredients_links::Column::AggregateId.is_in(aggregate_ids))
+            .all(&state.db)
+            .await?;
+
+        for link in links {
+            aggregate_products
+                .entry(link.aggregate_id)
+                .or_default()
+                .push((state.provider_from_id(link.provider_id), link.provider_ingr_id));
+        }
+    }
+
+    // Watches are referred to by index, as the stream of searches below can't hold on to borrowed watches.
+    let to_check = watches
+        .iter()
+        .enumerate()
+        .flat_map(|(index, watch)| {
+            let products = match (watch.provider_id, &watch.product_id, watch.aggregate_id) {
+                (Some(provider_id), Some(product_id), _) => {
+                    vec![(state.provider_from_id(provider_id), product_id.clone())]
+                }
+                (_, _, Some(aggregate_id)) => aggregate_products.get(&aggregate_id).cloned().unwrap_or_default(),
+                _ => Vec::new(),
+            };
+
+            products
+                .into_iter()
+                .map(move |(provider, product_id)| (index, provider, product_id))
+        })
+        .collect::<Vec<_>>();
+
+    tracing::debug!(watches = watches.len(), products = to_check.len(), "Checking watchlist");
+
+    let mut stream = futures::stream::iter(to_check)
+        .map(|(index, provider, product_id)| async move {
+            let product = state.providers.search_product(provider, &product_id).await;
+            (index, provider, product)
+        })
+        .buffer_unordered(2);
+
+    let mut alerts = 0;
+
+    while let Some((index, provider, product)) = stream.next().await {
+        let watch = &watches[index];
+        let product = match product {
+            Ok(product) => product,
+            Err(e) => {
+                tracing::warn!(error = ?e, watch_id = watch.id, ?provider, "Failed to retrieve watched product");
+                continue;
+            }
+        };
+
+        let on_sale = is_on_sale(state, &product);
+
+        if !condition_holds(watch, product.price_info.display_price, on_sale) {
+            continue;
+        }
+
+        if record_alert(&state.db, watch, state.provider_id_from_provider(&provider), &product).await? {
+            alerts += 1;
+        }
+    }
+
+    tracing::debug!(alerts, "Recorded watchlist alerts");
+
+    Ok(alerts)
+}
+
+/// Whether the given product currently has a sale, either directly or through a sale group it's part of.
+fn is_on_sale(state: &AppState, product: &WggSearchProduct) -> bool {
+    product.sale_information.is_some()
+        || state
+            .providers
+            .product_sale_association(product.provider, &product.id)
+            .map(|sale| sale.is_valid_at(Utc::now()))
+            .unwrap_or_default()
+}
+
+/// Whether the condition of the given `watch` holds for a product with the given price and sale status.
+fn condition_holds(watch: &db::watchlist::Model, display_price: CentPrice, on_sale: bool) -> bool {
+    let below_target = watch
+        .target_price
+        .is_some_and(|target| display_price <= target.max(0) as CentPrice);
+
+    below_target || (watch.any_sale && on_sale)
+}
+
+/// Record an alert for the given product, unless the most recent alert for the same product was identical.
+///
+/// # Returns
+///
+/// Whether a new alert was recorded.
+async fn record_alert(
+    db: &impl ConnectionTrait,
+    watch: &db::watchlist::Model,
+    provider_id: DbId,
+    product: &WggSearchProduct,
+) -> anyhow::Result<bool> {
+    let sale_label = product.sale_information.as_ref().map(|sale| sale.label.clone());
+    let display_price = product.price_info.display_price as i32;
+
+    let latest = db::watchlist_alerts::Entity::find()
+        .filter(db::watchlist_alerts::Column::WatchId.eq(watch.id))
+        .filter(db::watchlist_alerts::Column::ProviderId.eq(provider_id))
+        .filter(db::watchlist_alerts::Column::ProductId.eq(product.id.as_str()))
+        .order_by_desc(db::watchlist_alerts::Column::CreatedAt)
+        .one(db)
+        .await?;
+
+    if latest.is_some_and(|alert| alert.display_price == display_price && alert.sale_label == sale_label) {
+        return Ok(false);
+    }
+
+    let alert = db::watchlist_alerts::ActiveModel {
+        watch_id: watch.id.into_active_value(),
+        provider_id: provider_id.into_active_value(),
+        product_id: product.id.clone().into_active_value(),
+        display_price: display_price.into_active_value(),
+        original_price: (product.price_info.original_price as i32).into_active_value(),
+        sale_label: sale_label.clone().into_active_value(),
+        ..Default::default()
+    };
+    let _ = alert.insert(db).await?;
+
+    let mut body = format!(
+        "{} ({}) now costs {}",
+        product.name,
+        product.provider.get_metadata().display_name,
+        notifications::format_price(product.price_info.display_price)
+    );
+    if let Some(label) = &sale_label {
+        body.push_str(&format!(", sale: {label}"));
+    }
+    let notification = Notification::new(
+        NotificationKind::WatchlistAlert,
+        format!("Watchlist: {}", product.name),
+        body,
+    );
+    notifications::notify_user(db, watch.user_id, &notification).await?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::condition_holds;
+    use crate::db;
+
+    fn watch(target_price: Option<i32>, any_sale: bool) -> db::watchlist::Model {
+        db::watchlist::Model {
+            id: 1,
+            user_id: 1,
+            provider_id: Some(1),
+            product_id: Some("s1001".to_string()),
+            aggregate_id: None,
+            target_price,
+            any_sale,
+            created_at: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_condition_holds() {
+        assert!(condition_holds(&watch(Some(200), false), 200, false));
+        assert!(!condition_holds(&watch(Some(200), false), 201, true));
+        assert!(condition_holds(&watch(None, true), 500, true));
+        assert!(!condition_holds(&watch(None, true), 500, false));
+        assert!(condition_holds(&watch(Some(100), true), 500, true));
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use arc_swap::ArcSwap;
 use figment::providers::{Env, Format, Serialized, Toml};
 use figment::Figment;
@@ -12,10 +12,12 @@ use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
+use wgg_providers::SaleLabelRules;
 
 pub type SharedConfig = Arc<ArcSwap<Config>>;
 
 static CONFIG_FILE: &str = "config.toml";
+static SALE_RULES_FILE: &str = "sale_rules.toml";
 
 /// Initialise the config file.
 ///
@@ -56,6 +58,23 @@ pub fn save_config(app_settings: &Config) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Load the sale label rules from the config directory.
+///
+/// Creates a new rules file with the built-in defaults if it doesn't yet exist.
+/// The rules are only read on start-up, changes to the file require a restart.
+pub fn initialise_sale_rules() -> anyhow::Result<SaleLabelRules> {
+    let path = get_config_directory().join(SALE_RULES_FILE);
+
+    if !path.exists() {
+        std::fs::create_dir_all(get_config_directory())?;
+        std::fs::write(&path, toml::to_string_pretty(&SaleLabelRules::default())?)?;
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+
+    toml::from_str(&content).with_context(|| format!("Failed to parse sale label rules at {}", path.display()))
+}
+
 #[derive(Serialize, Deserialize, Clone, Default)]
 #[serde(default)]
 pub struct Config {
@@ -89,6 +108,16 @@ pub struct AppConfig {
     pub port: u16,
     /// The secret key used to encrypt session cookies. This should remain private.
     pub cookie_secret_key: String,
+    /// The secret key used to encrypt stored provider credentials, such as those of linked provider accounts and the
+    /// Picnic session. This should remain private.
+    ///
+    /// To change this key move the old one to `previous_credentials_secret_keys`, otherwise all stored credentials
+    /// become unreadable.
+    pub credentials_secret_key: String,
+    /// Former values of `credentials_secret_key`, only used to decrypt existing credentials.
+    ///
+    /// All stored credentials are re-encrypted with the current key on startup, after which these can be removed.
+    pub previous_credentials_secret_keys: Vec<String>,
     /// The directory where the front-end is located.
     pub static_dir: PathBuf,
     /// The directory where the provider product cache is stored between runs
@@ -118,6 +147,7 @@ pub struct DbConfig {
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
 pub struct ProviderConfig {
     pub picnic: PicnicConfig,
+    pub jumbo: JumboConfig,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -134,16 +164,25 @@ pub struct PicnicConfig {
     pub picnic_password: Option<SecretString>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct JumboConfig {
+    /// The email associated with the Jumbo account, only required for cart support.
+    ///
+    /// Both the email and password should be provided through environment variables
+    #[serde(skip_serializing)]
+    pub jumbo_email: Option<String>,
+    #[serde(skip_serializing)]
+    pub jumbo_password: Option<SecretString>,
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         AppConfig {
             host: "0.0.0.0".to_string(),
             port: 8080,
-            cookie_secret_key: rand::thread_rng()
-                .sample_iter(rand::distributions::Alphanumeric)
-                .take(128)
-                .map(char::from)
-                .collect(),
+            cookie_secret_key: random_secret_key(),
+            credentials_secret_key: random_secret_key(),
+            previous_credentials_secret_keys: Vec::new(),
             static_dir: std::env::current_dir()
                 .expect("Can't get current directory")
                 .join("static"),
@@ -154,6 +193,14 @@ impl Default for AppConfig {
     }
 }
 
+fn random_secret_key() -> String {
+    rand::thread_rng()
+        .sample_iter(rand::distributions::Alphanumeric)
+        .take(128)
+        .map(char::from)
+        .collect()
+}
+
 impl Debug for Config {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Config")
@@ -232,6 +279,18 @@ impl TryFrom<PicnicConfig> for wgg_providers::PicnicCredentials {
     }
 }
 
+impl TryFrom<JumboConfig> for wgg_providers::JumboCredentials {
+    type Error = anyhow::Error;
+
+    fn try_from(value: JumboConfig) -> Result<Self, Self::Error> {
+        let (Some(email), Some(password)) = (value.jumbo_email, value.jumbo_password) else {
+            anyhow::bail!("Either the email or password was missing for Jumbo Credentials initialisation");
+        };
+
+        Ok(Self::new(email, password))
+    }
+}
+
 /// Retrieve the *full* path to the config file.
 ///
 /// This is just [get_config_directory] + [CONFIG_FILE]
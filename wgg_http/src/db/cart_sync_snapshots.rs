@@ -0,0 +1,12 @@
+use sea_orm::sea_query::IntoCondition;
+use sea_orm::{ColumnTrait, Condition};
+pub use wgg_db_entity::cart_sync_snapshots::*;
+use wgg_db_entity::DbId;
+
+/// Condition for selecting the snapshot of the given cart for the given provider.
+pub fn for_cart_provider(cart_id: DbId, provider_id: DbId) -> Condition {
+    Column::CartId
+        .eq(cart_id)
+        .and(Column::ProviderId.eq(provider_id))
+        .into_condition()
+}
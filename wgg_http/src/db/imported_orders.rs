@@ -0,0 +1,9 @@
+use sea_orm::sea_query::IntoCondition;
+use sea_orm::{ColumnTrait, Condition};
+pub use wgg_db_entity::imported_orders::*;
+use wgg_db_entity::DbId;
+
+/// Condition for selecting the orders imported from the given provider.
+pub fn for_provider(provider_id: DbId) -> Condition {
+    Column::ProviderId.eq(provider_id).into_condition()
+}
@@ -4,8 +4,17 @@ pub mod agg_ingredients;
 pub mod agg_ingredients_links;
 pub mod cart;
 pub mod cart_contents;
+pub mod cart_sync_snapshots;
 pub mod cart_tally;
+pub mod imported_orders;
+pub mod notification_channels;
+pub mod notification_queue;
+pub mod price_history;
+pub mod provider_accounts;
 pub mod providers;
+pub mod sale_mismatches;
 pub mod search_utils;
 pub mod users;
 pub mod users_tokens;
+pub mod watchlist;
+pub mod watchlist_alerts;
@@ -0,0 +1,26 @@
+use crate::db;
+use sea_orm::sea_query::{IntoCondition, Query};
+use sea_orm::{ColumnTrait, Condition, IntoSimpleExpr};
+pub use wgg_db_entity::notification_channels::*;
+use wgg_db_entity::DbId;
+
+/// Condition for selecting entities owned by the provided user.
+pub fn owned_by(user_id: DbId) -> Condition {
+    Column::UserId.eq(user_id).into_condition()
+}
+
+/// Condition for selecting channels owned by any admin.
+pub fn owned_by_admin() -> Condition {
+    let subquery = Query::select()
+        .expr(db::users::Column::Id.into_simple_expr())
+        .from(db::users::Entity)
+        .cond_where(db::users::Column::IsAdmin.eq(true))
+        .to_owned();
+
+    Column::UserId.in_subquery(subquery).into_condition()
+}
+
+/// Condition for selecting channels which are enabled.
+pub fn enabled() -> Condition {
+    Column::Enabled.eq(true).into_condition()
+}
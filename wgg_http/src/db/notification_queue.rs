@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::IntoCondition;
+use sea_orm::{ColumnTrait, Condition};
+pub use wgg_db_entity::notification_queue::*;
+
+/// Condition for selecting queued notifications which should be (re)tried at the given moment.
+pub fn due(now: DateTime<Utc>, max_attempts: u32) -> Condition {
+    Column::NextAttemptAt
+        .lte(now)
+        .and(Column::Attempts.lt(max_attempts))
+        .into_condition()
+}
+
+/// Condition for selecting queued notifications which exhausted all their attempts before the given moment.
+pub fn abandoned_before(moment: DateTime<Utc>, max_attempts: u32) -> Condition {
+    Column::Attempts
+        .gte(max_attempts)
+        .and(Column::CreatedAt.lt(moment))
+        .into_condition()
+}
@@ -0,0 +1,171 @@
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use sea_orm::sea_query::IntoCondition;
+use sea_orm::{
+    ActiveValue, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, Select,
+};
+use std::collections::HashMap;
+pub use wgg_db_entity::price_history::*;
+use wgg_db_entity::DbId;
+use wgg_providers::models::{CentPrice, PriceInfo, PriceObservation, Unit, UnitPrice};
+
+/// Store all the given observations.
+///
+/// An observation is skipped if the latest stored observation of the same product has the same prices and sale label,
+/// and is less than a day old. This keeps frequently searched products from flooding the table.
+#[tracing::instrument(skip_all, fields(observations = observations.len()))]
+pub async fn record_observations(
+    db: &impl ConnectionTrait,
+    observations: Vec<(DbId, PriceObservation)>,
+) -> Result<(), DbErr> {
+    let Some(oldest) = observations
+        .iter()
+        .map(|(_, observation)| observation.observed_at)
+        .min()
+    else {
+        return Ok(());
+    };
+
+    // Observations older than a day are never duplicates, so only the recent ones of the batch's products are loaded.
+    let recent = Entity::find()
+        .filter(Column::ProviderId.is_in(observations.iter().map(|(provider_id, _)| *provider_id).unique()))
+        .filter(
+            Column::ProductId.is_in(
+                observations
+                    .iter()
+                    .map(|(_, observation)| &observation.product_id)
+                    .unique(),
+            ),
+        )
+        .filter(Column::ObservedAt.gt(oldest - chrono::Duration::days(1)))
+        .order_by_asc(Column::ObservedAt)
+        .all(db)
+        .await?;
+
+    let mut latest: HashMap<(DbId, String), LatestObservation> = recent
+        .into_iter()
+        .map(|model| {
+            let observation = LatestObservation {
+                price_info: price_info(&model),
+                observed_at: model.observed_at,
+                sale_label: model.sale_label,
+            };
+
+            ((model.provider_id, model.product_id), observation)
+        })
+        .collect();
+    let mut to_insert = Vec::with_capacity(observations.len());
+
+    for (provider_id, observation) in observations {
+        // Deposits aren't stored, so shouldn't be compared either.
+        let observed_price = PriceInfo {
+            deposit: None,
+            ..observation.price_info.clone()
+        };
+        let key = (provider_id, observation.product_id.clone());
+        let is_duplicate = latest.get(&key).is_some_and(|latest| {
+            observation.observed_at - latest.observed_at < chrono::Duration::days(1)
+                && latest.price_info == observed_price
+                && latest.sale_label == observation.sale_label
+        });
+
+        if is_duplicate {
+            continue;
+        }
+
+        // Later observations of the same product within this batch are compared against this one.
+        latest.insert(
+            key,
+            LatestObservation {
+                price_info: observed_price,
+                observed_at: observation.observed_at,
+                sale_label: observation.sale_label.clone(),
+            },
+        );
+
+        to_insert.push(ActiveModel {
+            provider_id: ActiveValue::Set(provider_id),
+            product_id: ActiveValue::Set(observation.product_id),
+            display_price: ActiveValue::Set(observation.price_info.display_price as i32),
+            original_price: ActiveValue::Set(observation.price_info.original_price as i32),
+            unit_price: ActiveValue::Set(observation.price_info.unit_price.as_ref().map(|unit| unit.price as i32)),
+            unit_price_unit: ActiveValue::Set(
+                observation
+                    .price_info
+                    .unit_price
+                    .as_ref()
+                    .map(|unit| unit_to_str(unit.unit).to_string()),
+            ),
+            sale_label: ActiveValue::Set(observation.sale_label),
+            observed_at: ActiveValue::Set(observation.observed_at),
+            ..Default::default()
+        });
+    }
+
+    if !to_insert.is_empty() {
+        Entity::insert_many(to_insert).exec(db).await?;
+    }
+
+    Ok(())
+}
+
+/// The parts of the latest observation of a product which determine whether a new observation is a duplicate.
+struct LatestObservation {
+    price_info: PriceInfo,
+    observed_at: DateTime<Utc>,
+    sale_label: Option<String>,
+}
+
+/// Create a select for all observations of the given product.
+pub fn find_for_product(provider_id: DbId, product_id: &str) -> Select<Entity> {
+    Entity::find().filter(is_product(provider_id, product_id))
+}
+
+/// Condition for selecting entities which are observations of the given product.
+pub fn is_product(provider_id: DbId, product_id: &str) -> Condition {
+    Column::ProviderId
+        .eq(provider_id)
+        .and(Column::ProductId.eq(product_id))
+        .into_condition()
+}
+
+/// Reconstruct the [PriceInfo] of the given observation.
+///
+/// Deposits aren't stored, as they don't say anything about a deal.
+pub fn price_info(observation: &Model) -> PriceInfo {
+    let unit_price = observation
+        .unit_price
+        .zip(observation.unit_price_unit.as_deref().and_then(unit_from_str))
+        .map(|(price, unit)| UnitPrice {
+            unit,
+            price: price as CentPrice,
+        });
+
+    PriceInfo {
+        display_price: observation.display_price as CentPrice,
+        original_price: observation.original_price as CentPrice,
+        unit_price,
+        deposit: None,
+    }
+}
+
+fn unit_to_str(unit: Unit) -> &'static str {
+    match unit {
+        Unit::Piece => "piece",
+        Unit::Liter => "liter",
+        Unit::MilliLiter => "milliliter",
+        Unit::KiloGram => "kilogram",
+        Unit::Gram => "gram",
+    }
+}
+
+fn unit_from_str(unit: &str) -> Option<Unit> {
+    match unit {
+        "piece" => Some(Unit::Piece),
+        "liter" => Some(Unit::Liter),
+        "milliliter" => Some(Unit::MilliLiter),
+        "kilogram" => Some(Unit::KiloGram),
+        "gram" => Some(Unit::Gram),
+        _ => None,
+    }
+}
@@ -0,0 +1,14 @@
+use sea_orm::sea_query::IntoCondition;
+use sea_orm::{ColumnTrait, Condition};
+pub use wgg_db_entity::provider_accounts::*;
+use wgg_db_entity::DbId;
+
+/// Condition for selecting entities owned by the provided user.
+pub fn owned_by(user_id: DbId) -> Condition {
+    Column::UserId.eq(user_id).into_condition()
+}
+
+/// Condition for selecting the account of the given provider.
+pub fn for_provider(provider_id: DbId) -> Condition {
+    Column::ProviderId.eq(provider_id).into_condition()
+}
@@ -1,12 +1,14 @@
-use async_graphql::EnumType;
 use sea_orm::{ConnectionTrait, EntityTrait};
 use std::collections::BTreeMap;
 pub use wgg_db_entity::providers::*;
 use wgg_db_entity::DbId;
 use wgg_providers::models::Provider;
 
+/// The ids of all registered providers.
 pub fn all_providers() -> impl Iterator<Item = String> {
-    Provider::items().iter().map(|i| i.name.to_string())
+    wgg_providers::registered_providers()
+        .into_iter()
+        .map(|provider| provider.id().to_string())
 }
 
 pub async fn all_db_providers(db: &impl ConnectionTrait) -> anyhow::Result<BTreeMap<Provider, DbId>> {
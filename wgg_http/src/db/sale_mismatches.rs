@@ -0,0 +1,16 @@
+use sea_orm::sea_query::IntoCondition;
+use sea_orm::{ColumnTrait, Condition};
+pub use wgg_db_entity::sale_mismatches::*;
+use wgg_db_entity::DbId;
+
+/// Condition for selecting the mismatches found in the given cart for the given provider.
+pub fn for_cart(cart_id: DbId, provider_id: DbId) -> Condition {
+    Condition::all()
+        .add(Column::CartId.eq(cart_id))
+        .add(Column::ProviderId.eq(provider_id))
+}
+
+/// Condition for selecting the mismatches found for the given provider.
+pub fn for_provider(provider_id: DbId) -> Condition {
+    Column::ProviderId.eq(provider_id).into_condition()
+}
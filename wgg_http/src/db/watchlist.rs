@@ -0,0 +1,9 @@
+use sea_orm::sea_query::IntoCondition;
+use sea_orm::{ColumnTrait, Condition};
+pub use wgg_db_entity::watchlist::*;
+use wgg_db_entity::DbId;
+
+/// Condition for selecting entities owned by the provided user.
+pub fn owned_by(user_id: DbId) -> Condition {
+    Column::UserId.eq(user_id).into_condition()
+}
@@ -0,0 +1,21 @@
+use crate::db;
+use sea_orm::sea_query::{IntoCondition, Query};
+use sea_orm::{ColumnTrait, Condition, IntoSimpleExpr};
+pub use wgg_db_entity::watchlist_alerts::*;
+use wgg_db_entity::DbId;
+
+/// Condition for selecting alerts of watched items owned by the provided user.
+pub fn owned_by(user_id: DbId) -> Condition {
+    let subquery = Query::select()
+        .expr(db::watchlist::Column::Id.into_simple_expr())
+        .from(db::watchlist::Entity)
+        .cond_where(db::watchlist::owned_by(user_id))
+        .to_owned();
+
+    Column::WatchId.in_subquery(subquery).into_condition()
+}
+
+/// Condition for selecting alerts which haven't been seen yet.
+pub fn unseen() -> Condition {
+    Column::Seen.eq(false).into_condition()
+}
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 use tokio::sync::Notify;
 
+mod accounts;
 pub mod api;
 pub mod config;
 mod cross_system;
 mod db;
+mod notifications;
 pub mod setup;
 pub mod telemetry;
 mod utils;
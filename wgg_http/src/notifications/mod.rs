@@ -0,0 +1,667 @@
+//! Delivery of notifications to users outside the web UI.
+//!
+//! Users configure one or more [ChannelConfig]s. Notifications are first queued for every enabled channel of a user
+//! with [notify_user], after which they're delivered (and retried on failure) by [deliver_pending].
+use crate::accounts::CredentialsCipher;
+use crate::db;
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ConnectionTrait, EntityTrait, IntoActiveValue, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+use wgg_db_entity::DbId;
+use wgg_providers::models::CentPrice;
+
+mod push;
+mod smtp;
+mod webhook;
+
+pub use push::{PushChannel, PushConfig};
+pub use smtp::{SmtpChannel, SmtpConfig};
+use webhook::HttpHeader;
+pub use webhook::{WebhookChannel, WebhookConfig};
+
+/// The maximum amount of times we'll try to deliver a single notification before giving up.
+pub const MAX_ATTEMPTS: u32 = 8;
+/// How many days notifications which exhausted all their attempts are kept around for inspection.
+const ABANDONED_RETENTION_DAYS: i64 = 7;
+/// The maximum time a single HTTP based delivery may take.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A channel through which a [Notification] can be delivered to a user.
+#[async_trait::async_trait]
+pub trait NotificationChannel: Send + Sync {
+    /// Deliver the given notification.
+    ///
+    /// Should return an error if the receiving end did not accept the notification, it will then be retried later.
+    async fn send(&self, notification: &Notification) -> anyhow::Result<()>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+}
+
+impl Notification {
+    pub fn new(kind: NotificationKind, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            kind,
+            title: title.into(),
+            body: body.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// The condition of an item on the watchlist held.
+    WatchlistAlert,
+    /// A cart was completed.
+    CartCompleted,
+    /// A provider requires attention to be able to log in.
+    ProviderLogin,
+    /// A test notification requested by the user.
+    Test,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::WatchlistAlert => "watchlist_alert",
+            NotificationKind::CartCompleted => "cart_completed",
+            NotificationKind::ProviderLogin => "provider_login",
+            NotificationKind::Test => "test",
+        }
+    }
+}
+
+impl FromStr for NotificationKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "watchlist_alert" => Ok(NotificationKind::WatchlistAlert),
+            "cart_completed" => Ok(NotificationKind::CartCompleted),
+            "provider_login" => Ok(NotificationKind::ProviderLogin),
+            "test" => Ok(NotificationKind::Test),
+            _ => Err(anyhow::anyhow!("Unknown notification kind: `{}`", s)),
+        }
+    }
+}
+
+/// The configuration of a single notification channel, as stored in the database.
+///
+/// Its secrets (see [ChannelSecrets]) are stored separately, encrypted with the [CredentialsCipher].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChannelConfig {
+    Webhook(WebhookConfig),
+    Smtp(SmtpConfig),
+    Push(PushConfig),
+}
+
+impl ChannelConfig {
+    /// Parse the configuration of the given database channel, decrypting its secrets with `cipher`.
+    pub fn from_model(model: &db::notification_channels::Model, cipher: &CredentialsCipher) -> anyhow::Result<Self> {
+        let mut config: Self = serde_json::from_str(&model.config)?;
+
+        // Channels created before secrets were encrypted still have them in their configuration.
+        if let Some(secrets) = &model.encrypted_secrets {
+            config.insert_secrets(cipher.decrypt_json(secrets)?);
+        }
+
+        Ok(config)
+    }
+
+    /// Serialise this configuration for storage in the database.
+    ///
+    /// # Returns
+    ///
+    /// The configuration without its secrets, and said secrets encrypted with `cipher`.
+    pub fn to_db_values(&self, cipher: &CredentialsCipher) -> anyhow::Result<(String, Vec<u8>)> {
+        let mut config = self.clone();
+        let secrets = config.take_secrets();
+
+        Ok((serde_json::to_string(&config)?, cipher.encrypt_json(&secrets)?))
+    }
+
+    fn take_secrets(&mut self) -> ChannelSecrets {
+        match self {
+            ChannelConfig::Webhook(config) => ChannelSecrets {
+                headers: std::mem::take(&mut config.headers),
+                ..Default::default()
+            },
+            ChannelConfig::Smtp(config) => ChannelSecrets {
+                password: config.password.take(),
+                ..Default::default()
+            },
+            ChannelConfig::Push(config) => ChannelSecrets {
+                token: config.token.take(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn insert_secrets(&mut self, secrets: ChannelSecrets) {
+        match self {
+            ChannelConfig::Webhook(config) => config.headers = secrets.headers,
+            ChannelConfig::Smtp(config) => config.password = secrets.password,
+            ChannelConfig::Push(config) => config.token = secrets.token,
+        }
+    }
+
+    /// Create the channel described by this configuration.
+    ///
+    /// HTTP based channels will use the given `client`.
+    pub fn into_channel(self, client: reqwest::Client) -> anyhow::Result<Box<dyn NotificationChannel>> {
+        Ok(match self {
+            ChannelConfig::Webhook(config) => Box::new(WebhookChannel::new(config, client)),
+            ChannelConfig::Smtp(config) => Box::new(SmtpChannel::new(config)?),
+            ChannelConfig::Push(config) => Box::new(PushChannel::new(config, client)),
+        })
+    }
+}
+
+/// The secret fields of a [ChannelConfig], which are only stored encrypted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct ChannelSecrets {
+    /// The SMTP password.
+    #[serde(default)]
+    password: Option<String>,
+    /// The push service access token.
+    #[serde(default)]
+    token: Option<String>,
+    /// The webhook headers, as these are commonly used for authentication.
+    #[serde(default)]
+    headers: Vec<HttpHeader>,
+}
+
+/// Encrypt the secrets of all notification channels with the current key of `cipher`.
+///
+/// This covers both secrets which were encrypted with one of the previous keys, and channels which were created before
+/// secrets were encrypted at all.
+///
+/// # Returns
+///
+/// The amount of channels which were updated.
+pub async fn rotate_channel_secrets(db: &impl ConnectionTrait, cipher: &CredentialsCipher) -> anyhow::Result<u64> {
+    let channels = db::notification_channels::Entity::find().all(db).await?;
+    let mut updated = 0;
+
+    for channel in channels {
+        let values = match &channel.encrypted_secrets {
+            Some(secrets) => cipher
+                .reencrypt(secrets)
+                .map(|secrets| secrets.map(|secrets| (None, secrets))),
+            None => ChannelConfig::from_model(&channel, cipher)
+                .and_then(|config| config.to_db_values(cipher))
+                .map(|(config, secrets)| Some((Some(config), secrets))),
+        };
+        let (config, secrets) = match values {
+            Ok(Some(values)) => values,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!(channel_id = channel.id, error = ?e, "Unreadable notification channel secrets");
+                continue;
+            }
+        };
+
+        let mut update = db::notification_channels::ActiveModel {
+            id: channel.id.into_active_value(),
+            encrypted_secrets: Some(secrets).into_active_value(),
+            ..Default::default()
+        };
+        if let Some(config) = config {
+            update.config = config.into_active_value();
+        }
+        update.update(db).await?;
+
+        updated += 1;
+    }
+
+    Ok(updated)
+}
+
+/// Create the HTTP client used by all HTTP based channels.
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .expect("Failed to create notification HTTP client")
+}
+
+/// Format the given price for display in a notification, e.g `€1.50`.
+pub fn format_price(price: CentPrice) -> String {
+    format!("€{}.{:02}", price / 100, price % 100)
+}
+
+/// Queue the given notification for delivery on all enabled channels of the given user.
+///
+/// # Returns
+///
+/// The amount of channels the notification was queued for.
+pub async fn notify_user(
+    db: &impl ConnectionTrait,
+    user_id: DbId,
+    notification: &Notification,
+) -> anyhow::Result<usize> {
+    let channels = db::notification_channels::Entity::find()
+        .filter(db::notification_channels::owned_by(user_id))
+        .filter(db::notification_channels::enabled())
+        .all(db)
+        .await?;
+
+    queue_for_channels(db, channels.iter().map(|channel| channel.id), notification).await
+}
+
+/// Queue the given notification for delivery on all enabled channels of all admins.
+///
+/// Used for instance-wide problems, such as a provider which requires attention to be able to log in.
+///
+/// # Returns
+///
+/// The amount of channels the notification was queued for.
+pub async fn notify_admins(db: &impl ConnectionTrait, notification: &Notification) -> anyhow::Result<usize> {
+    let channels = db::notification_channels::Entity::find()
+        .filter(db::notification_channels::owned_by_admin())
+        .filter(db::notification_channels::enabled())
+        .all(db)
+        .await?;
+
+    queue_for_channels(db, channels.iter().map(|channel| channel.id), notification).await
+}
+
+async fn queue_for_channels(
+    db: &impl ConnectionTrait,
+    channel_ids: impl IntoIterator<Item = DbId>,
+    notification: &Notification,
+) -> anyhow::Result<usize> {
+    let to_insert = channel_ids
+        .into_iter()
+        .map(|channel_id| db::notification_queue::ActiveModel {
+            channel_id: channel_id.into_active_value(),
+            kind: notification.kind.as_str().to_string().into_active_value(),
+            title: notification.title.clone().into_active_value(),
+            body: notification.body.clone().into_active_value(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    let queued = to_insert.len();
+
+    if queued > 0 {
+        let _ = db::notification_queue::Entity::insert_many(to_insert).exec(db).await?;
+        tracing::debug!(queued, kind = ?notification.kind, "Queued notification");
+    }
+
+    Ok(queued)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeliveryReport {
+    pub delivered: usize,
+    pub failed: usize,
+    pub abandoned: usize,
+}
+
+/// Try to deliver all queued notifications which are due.
+///
+/// Delivered notifications are removed from the queue, failed deliveries are rescheduled with an exponential backoff
+/// until [MAX_ATTEMPTS] is reached.
+pub async fn deliver_pending(
+    db: &impl ConnectionTrait,
+    client: &reqwest::Client,
+    cipher: &CredentialsCipher,
+) -> anyhow::Result<DeliveryReport> {
+    let now = Utc::now();
+    let mut report = DeliveryReport::default();
+
+    let pending = db::notification_queue::Entity::find()
+        .filter(db::notification_queue::due(now, MAX_ATTEMPTS))
+        .find_also_related(db::notification_channels::Entity)
+        .filter(db::notification_channels::enabled())
+        .all(db)
+        .await?;
+
+    for (queued, channel) in pending {
+        let Some(channel) = channel else {
+            continue;
+        };
+
+        let error = match deliver(&queued, &channel, client, cipher).await {
+            Ok(()) => {
+                let _ = db::notification_queue::Entity::delete_by_id(queued.id).exec(db).await?;
+                report.delivered += 1;
+                continue;
+            }
+            Err(e) => e,
+        };
+
+        let attempts = queued.attempts.max(0) as u32 + 1;
+
+        if attempts >= MAX_ATTEMPTS {
+            tracing::warn!(?error, queue_id = queued.id, "Giving up on notification delivery");
+            report.abandoned += 1;
+        } else {
+            tracing::debug!(?error, queue_id = queued.id, attempts, "Failed to deliver notification");
+            report.failed += 1;
+        }
+
+        let update = db::notification_queue::ActiveModel {
+            id: queued.id.into_active_value(),
+            attempts: (attempts as i32).into_active_value(),
+            last_error: Some(format!("{error:#}")).into_active_value(),
+            next_attempt_at: (now + retry_delay(attempts)).into_active_value(),
+            ..Default::default()
+        };
+        let _ = db::notification_queue::Entity::update(update).exec(db).await?;
+    }
+
+    let _ = db::notification_queue::Entity::delete_many()
+        .filter(db::notification_queue::abandoned_before(
+            now - chrono::Duration::days(ABANDONED_RETENTION_DAYS),
+            MAX_ATTEMPTS,
+        ))
+        .exec(db)
+        .await?;
+
+    Ok(report)
+}
+
+async fn deliver(
+    queued: &db::notification_queue::Model,
+    channel: &db::notification_channels::Model,
+    client: &reqwest::Client,
+    cipher: &CredentialsCipher,
+) -> anyhow::Result<()> {
+    let notification = Notification {
+        kind: queued.kind.parse()?,
+        title: queued.title.clone(),
+        body: queued.body.clone(),
+    };
+
+    ChannelConfig::from_model(channel, cipher)?
+        .into_channel(client.clone())?
+        .send(&notification)
+        .await
+}
+
+/// The delay before the next delivery attempt, doubling with every failed attempt up to a maximum of six hours.
+fn retry_delay(attempts: u32) -> chrono::Duration {
+    let minutes = 1i64 << attempts.saturating_sub(1).min(9);
+
+    chrono::Duration::minutes(minutes.min(6 * 60))
+}
+
+/// The payload sent by HTTP based channels which support structured content.
+#[derive(Debug, Clone, Serialize)]
+struct NotificationPayload<'a> {
+    kind: NotificationKind,
+    title: &'a str,
+    body: &'a str,
+    sent_at: DateTime<Utc>,
+}
+
+impl<'a> From<&'a Notification> for NotificationPayload<'a> {
+    fn from(value: &'a Notification) -> Self {
+        Self {
+            kind: value.kind,
+            title: &value.title,
+            body: &value.body,
+            sent_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChannelConfig, Notification, NotificationKind};
+    use crate::accounts::CredentialsCipher;
+    use crate::db;
+    use crate::notifications::push::PushStyle;
+    use crate::notifications::smtp::SmtpSecurity;
+    use crate::notifications::webhook::HttpHeader;
+    use crate::notifications::{PushConfig, SmtpConfig, WebhookConfig};
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use tokio::sync::oneshot;
+
+    /// A request as received by the [http_stand_in] server.
+    #[derive(Debug)]
+    struct ReceivedRequest {
+        /// The request line and headers, lowercased.
+        head: String,
+        body: String,
+    }
+
+    /// Start a stand-in HTTP server which accepts a single request, responds with `200 OK`, and reports the request.
+    async fn http_stand_in() -> (String, oneshot::Receiver<ReceivedRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let (send, recv) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = Vec::new();
+            let mut chunk = [0u8; 1024];
+
+            let head_end = loop {
+                let read = stream.read(&mut chunk).await.unwrap();
+                buffer.extend_from_slice(&chunk[..read]);
+
+                if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break pos + 4;
+                }
+            };
+
+            let head = String::from_utf8_lossy(&buffer[..head_end]).to_lowercase();
+            let content_length = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .map(|len| len.trim().parse::<usize>().unwrap())
+                .unwrap_or_default();
+
+            while buffer.len() < head_end + content_length {
+                let read = stream.read(&mut chunk).await.unwrap();
+                buffer.extend_from_slice(&chunk[..read]);
+            }
+
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                .await
+                .unwrap();
+
+            let body = String::from_utf8_lossy(&buffer[head_end..]).to_string();
+            let _ = send.send(ReceivedRequest { head, body });
+        });
+
+        (address, recv)
+    }
+
+    /// Start a stand-in SMTP server which accepts a single connection, and reports the content of the first mail.
+    async fn smtp_stand_in() -> (u16, oneshot::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (send, recv) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let mut send = Some(send);
+
+            write.write_all(b"220 stand-in ESMTP\r\n").await.unwrap();
+
+            while let Ok(Some(line)) = lines.next_line().await {
+                let command = line.to_ascii_uppercase();
+
+                if command.starts_with("DATA") {
+                    write
+                        .write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n")
+                        .await
+                        .unwrap();
+                    let mut data = String::new();
+
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        if line == "." {
+                            break;
+                        }
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+
+                    write.write_all(b"250 OK\r\n").await.unwrap();
+
+                    if let Some(send) = send.take() {
+                        let _ = send.send(data);
+                    }
+                } else if command.starts_with("QUIT") {
+                    write.write_all(b"221 Bye\r\n").await.unwrap();
+                    break;
+                } else if command.starts_with("EHLO") || command.starts_with("HELO") {
+                    write.write_all(b"250 stand-in\r\n").await.unwrap();
+                } else {
+                    write.write_all(b"250 OK\r\n").await.unwrap();
+                }
+            }
+        });
+
+        (port, recv)
+    }
+
+    fn notification() -> Notification {
+        Notification::new(NotificationKind::Test, "Test title", "Test body")
+    }
+
+    #[tokio::test]
+    async fn test_webhook_channel() {
+        let (address, received) = http_stand_in().await;
+        let config = ChannelConfig::Webhook(WebhookConfig {
+            url: format!("{address}/hook"),
+            headers: vec![HttpHeader {
+                name: "X-Secret".to_string(),
+                value: "hunter2".to_string(),
+            }],
+        });
+
+        let channel = config.into_channel(super::http_client()).unwrap();
+        channel.send(&notification()).await.unwrap();
+
+        let request = received.await.unwrap();
+        let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+
+        assert!(request.head.starts_with("post /hook"));
+        assert!(request.head.contains("x-secret: hunter2"));
+        assert_eq!(body["kind"], "test");
+        assert_eq!(body["title"], "Test title");
+        assert_eq!(body["body"], "Test body");
+    }
+
+    #[tokio::test]
+    async fn test_ntfy_push_channel() {
+        let (address, received) = http_stand_in().await;
+        let config = ChannelConfig::Push(PushConfig {
+            url: format!("{address}/wgg"),
+            style: PushStyle::Ntfy,
+            token: Some("tk_123".to_string()),
+            priority: Some(4),
+        });
+
+        let channel = config.into_channel(super::http_client()).unwrap();
+        channel.send(&notification()).await.unwrap();
+
+        let request = received.await.unwrap();
+
+        assert!(request.head.starts_with("post /wgg"));
+        assert!(request.head.contains("title: test title"));
+        assert!(request.head.contains("priority: 4"));
+        assert!(request.head.contains("authorization: bearer tk_123"));
+        assert_eq!(request.body, "Test body");
+    }
+
+    #[tokio::test]
+    async fn test_gotify_push_channel() {
+        let (address, received) = http_stand_in().await;
+        let config = ChannelConfig::Push(PushConfig {
+            url: format!("{address}/message"),
+            style: PushStyle::Gotify,
+            token: Some("app_token".to_string()),
+            priority: None,
+        });
+
+        let channel = config.into_channel(super::http_client()).unwrap();
+        channel.send(&notification()).await.unwrap();
+
+        let request = received.await.unwrap();
+        let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+
+        assert!(request.head.contains("x-gotify-key: app_token"));
+        assert_eq!(body["title"], "Test title");
+        assert_eq!(body["message"], "Test body");
+    }
+
+    #[tokio::test]
+    async fn test_smtp_channel() {
+        let (port, received) = smtp_stand_in().await;
+        let config = ChannelConfig::Smtp(SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            security: SmtpSecurity::None,
+            username: None,
+            password: None,
+            from: "wgg@localhost".to_string(),
+            to: "user@localhost".to_string(),
+        });
+
+        let channel = config.into_channel(super::http_client()).unwrap();
+        channel.send(&notification()).await.unwrap();
+
+        let mail = received.await.unwrap();
+
+        assert!(mail.contains("Subject: Test title"));
+        assert!(mail.contains("To: user@localhost"));
+        assert!(mail.contains("Test body"));
+    }
+
+    #[test]
+    fn test_channel_secrets() {
+        let cipher = CredentialsCipher::new("secret");
+        let config = ChannelConfig::Smtp(SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port: 25,
+            security: SmtpSecurity::None,
+            username: Some("user".to_string()),
+            password: Some("hunter2".to_string()),
+            from: "wgg@localhost".to_string(),
+            to: "user@localhost".to_string(),
+        });
+        let (stored_config, secrets) = config.to_db_values(&cipher).unwrap();
+
+        assert!(!stored_config.contains("hunter2"));
+
+        let mut model = db::notification_channels::Model {
+            id: 1,
+            user_id: 1,
+            name: "Mail".to_string(),
+            config: stored_config,
+            enabled: true,
+            created_at: chrono::Utc::now(),
+            encrypted_secrets: Some(secrets),
+        };
+        assert_eq!(ChannelConfig::from_model(&model, &cipher).unwrap(), config);
+
+        // Channels created before secrets were encrypted keep them in their configuration.
+        model.config = serde_json::to_string(&config).unwrap();
+        model.encrypted_secrets = None;
+        assert_eq!(ChannelConfig::from_model(&model, &cipher).unwrap(), config);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(super::retry_delay(1), chrono::Duration::minutes(1));
+        assert_eq!(super::retry_delay(3), chrono::Duration::minutes(4));
+        assert_eq!(super::retry_delay(20), chrono::Duration::hours(6));
+    }
+}
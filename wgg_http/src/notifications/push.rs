@@ -0,0 +1,77 @@
+use crate::notifications::{Notification, NotificationChannel};
+use serde::{Deserialize, Serialize};
+
+/// Sends notifications to a push service such as [ntfy](https://ntfy.sh) or [Gotify](https://gotify.net).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, async_graphql::InputObject)]
+#[graphql(name = "PushConfigInput")]
+pub struct PushConfig {
+    /// The full URL to post to, for ntfy this includes the topic (`https://ntfy.sh/my-topic`),
+    /// for Gotify this is the message endpoint (`https://gotify.example.com/message`).
+    pub url: String,
+    pub style: PushStyle,
+    /// Access token, sent as a bearer token for ntfy and as an application token for Gotify.
+    pub token: Option<String>,
+    /// The priority of the notification, if not set the default of the push service is used.
+    pub priority: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum PushStyle {
+    /// Plain text body, with the title and priority as headers.
+    Ntfy,
+    /// JSON body containing the title, message, and priority.
+    Gotify,
+}
+
+pub struct PushChannel {
+    config: PushConfig,
+    client: reqwest::Client,
+}
+
+impl PushChannel {
+    pub fn new(config: PushConfig, client: reqwest::Client) -> Self {
+        Self { config, client }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for PushChannel {
+    async fn send(&self, notification: &Notification) -> anyhow::Result<()> {
+        let request = self.client.post(&self.config.url);
+
+        let request = match self.config.style {
+            PushStyle::Ntfy => {
+                let mut request = request
+                    .header("Title", &notification.title)
+                    .body(notification.body.clone());
+
+                if let Some(priority) = self.config.priority {
+                    request = request.header("Priority", priority.to_string());
+                }
+                if let Some(token) = &self.config.token {
+                    request = request.bearer_auth(token);
+                }
+
+                request
+            }
+            PushStyle::Gotify => {
+                let mut request = request.json(&serde_json::json!({
+                    "title": notification.title,
+                    "message": notification.body,
+                    "priority": self.config.priority,
+                }));
+
+                if let Some(token) = &self.config.token {
+                    request = request.header("X-Gotify-Key", token);
+                }
+
+                request
+            }
+        };
+
+        request.send().await?.error_for_status()?;
+
+        Ok(())
+    }
+}
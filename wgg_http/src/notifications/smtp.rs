@@ -0,0 +1,73 @@
+use crate::notifications::{Notification, NotificationChannel};
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+
+/// Sends every notification as a plain-text email.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, async_graphql::InputObject)]
+#[graphql(name = "SmtpConfigInput")]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// The address the emails are sent from, e.g `Wgg <wgg@example.com>`.
+    pub from: String,
+    /// The address the emails are sent to.
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Unencrypted, should only be used for servers on the local network.
+    None,
+    /// Upgrade the connection with `STARTTLS`.
+    StartTls,
+    /// Connect with TLS from the start.
+    Tls,
+}
+
+pub struct SmtpChannel {
+    config: SmtpConfig,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpChannel {
+    pub fn new(config: SmtpConfig) -> anyhow::Result<Self> {
+        let builder = match config.security {
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+        };
+        let mut builder = builder.port(config.port);
+
+        if let Some(username) = &config.username {
+            let password = config.password.clone().unwrap_or_default();
+            builder = builder.credentials(Credentials::new(username.clone(), password));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            config,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for SmtpChannel {
+    async fn send(&self, notification: &Notification) -> anyhow::Result<()> {
+        let email = Message::builder()
+            .from(self.config.from.parse()?)
+            .to(self.config.to.parse()?)
+            .subject(&notification.title)
+            .header(ContentType::TEXT_PLAIN)
+            .body(notification.body.clone())?;
+
+        self.transport.send(email).await?;
+
+        Ok(())
+    }
+}
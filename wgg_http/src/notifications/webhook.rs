@@ -0,0 +1,49 @@
+use crate::notifications::{Notification, NotificationChannel, NotificationPayload};
+use serde::{Deserialize, Serialize};
+
+/// Posts every notification as JSON to an arbitrary URL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, async_graphql::InputObject)]
+#[graphql(name = "WebhookConfigInput")]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Additional headers to send along with every request, for example for authentication.
+    #[serde(default)]
+    #[graphql(default)]
+    pub headers: Vec<HttpHeader>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, async_graphql::InputObject)]
+#[graphql(name = "HttpHeaderInput")]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+pub struct WebhookChannel {
+    config: WebhookConfig,
+    client: reqwest::Client,
+}
+
+impl WebhookChannel {
+    pub fn new(config: WebhookConfig, client: reqwest::Client) -> Self {
+        Self { config, client }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for WebhookChannel {
+    async fn send(&self, notification: &Notification) -> anyhow::Result<()> {
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .json(&NotificationPayload::from(notification));
+
+        for header in &self.config.headers {
+            request = request.header(&header.name, &header.value);
+        }
+
+        request.send().await?.error_for_status()?;
+
+        Ok(())
+    }
+}
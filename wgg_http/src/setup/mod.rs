@@ -1,3 +1,4 @@
+use crate::accounts::CredentialsCipher;
 use crate::api::dataloader::DataLoaders;
 use crate::api::AppState;
 use crate::config::{Config, DbConfig, SharedConfig};
@@ -34,6 +35,7 @@ use wgg_scheduler::JobScheduler;
 mod caching;
 mod first_time;
 mod picnic;
+mod price_history;
 
 pub struct Application {
     pub tcp: TcpListener,
@@ -53,12 +55,15 @@ impl Application {
         setup_db_schema(&db).await?;
 
         let sea_db = SqlxSqliteConnector::from_sqlx_sqlite_pool(db);
-        first_time::first_time_setup(&sea_db).await?;
 
         tracing::debug!("Creating Providers...");
         let cache = caching::setup_cache(&config).await;
+        let sale_rules = crate::config::initialise_sale_rules()?;
+        let (price_observer, price_observations) = tokio::sync::mpsc::channel(price_history::OBSERVATION_QUEUE_SIZE);
         let mut providers_builder = WggProvider::builder()
             .with_product_cache(cache)
+            .with_sale_label_rules(sale_rules)
+            .with_price_observer(price_observer)
             .with_startup_sale_validation(config.app.startup_sale_validation)
             .with_jumbo(Default::default())
             .with_picnic_rps(Some(config.pd.picnic.requests_per_second));
@@ -66,19 +71,35 @@ impl Application {
         // Try to initialise the Picnic provider.
         match config.pd.picnic.clone().try_into() {
             Ok(picnic_creds) => {
-                let cache_provider = picnic::PicnicCredentialsCache::new(config.app.cache_dir.join("picnic.json"));
+                let cipher = CredentialsCipher::from_config(&config.app);
+                let cache_provider = picnic::PicnicCredentialsCache::new(&config.app.cache_dir, cipher, sea_db.clone());
 
                 providers_builder = providers_builder.with_picnic(picnic_creds, cache_provider);
             }
             Err(e) => tracing::debug!(error = %e, "Not using Picnic Provider"),
         }
 
+        // Try to enable cart support for the Jumbo provider.
+        match config.pd.jumbo.clone().try_into() {
+            Ok(jumbo_creds) => providers_builder = providers_builder.with_jumbo_credentials(jumbo_creds),
+            Err(e) => tracing::debug!(error = %e, "Not using Jumbo cart support"),
+        }
+
         let providers = providers_builder.build().await?;
 
+        // Only done after the providers are built, as that is when all providers have been registered.
+        first_time::first_time_setup(&sea_db).await?;
+
         let scheduler = JobScheduler::new(Duration::from_millis(500)).await;
 
         let db_providers = crate::db::providers::all_db_providers(&sea_db).await?;
 
+        tokio::spawn(price_history::persist_price_observations(
+            sea_db.clone(),
+            db_providers.clone(),
+            price_observations,
+        ));
+
         let result = Application {
             tcp,
             config: Arc::new(ArcSwap::from_pointee(config)),
@@ -152,6 +173,24 @@ async fn construct_server(
     db_providers: BTreeMap<Provider, DbId>,
     scheduler: JobScheduler,
 ) -> anyhow::Result<Router> {
+    // The config guard isn't `Send`, and therefore can't be held across the rotation below.
+    let (credentials_cipher, rotate_credentials) = {
+        let cfg: DynGuard<Config> = config.load();
+        let rotate = !cfg.app.previous_credentials_secret_keys.is_empty();
+        (CredentialsCipher::from_config(&cfg.app), rotate)
+    };
+
+    if rotate_credentials {
+        let rotated = crate::accounts::rotate_account_credentials(&db, &credentials_cipher).await?;
+        tracing::info!(rotated, "Re-encrypted linked account credentials with the current key");
+    }
+
+    // Also encrypts the secrets of channels which were created before secrets were encrypted.
+    let rotated = crate::notifications::rotate_channel_secrets(&db, &credentials_cipher).await?;
+    if rotated > 0 {
+        tracing::info!(rotated, "Encrypted notification channel secrets with the current key");
+    }
+
     let cfg: DynGuard<Config> = config.load();
     let secret_key = tower_cookies::Key::from(cfg.app.cookie_secret_key.as_bytes());
 
@@ -161,7 +200,10 @@ async fn construct_server(
         providers,
         scheduler,
         db_providers,
+        credentials_cipher,
+        account_carts: Default::default(),
     };
+
     let schema = create_graphql_schema(state.clone(), secret_key.clone());
 
     // Schedule all API jobs
@@ -1,53 +1,65 @@
+use crate::accounts::{CredentialsCipher, EncryptedFileCache};
+use crate::notifications::{self, Notification, NotificationKind};
 use async_graphql::futures_util::future::BoxFuture;
-use std::io;
-use std::path::PathBuf;
+use sea_orm::DatabaseConnection;
+use std::path::Path;
 use std::sync::Arc;
 use tracing_subscriber::filter::FilterExt;
-use wgg_providers::wgg_picnic::credentials::cache::JsonFileCache;
 use wgg_providers::wgg_picnic::credentials::{Credentials, CredentialsCache};
+use wgg_providers::wgg_picnic::ApiError;
 
 pub struct PicnicCredentialsCache {
-    json_cache: JsonFileCache,
+    file_cache: EncryptedFileCache,
+    /// Used to notify admins when Picnic requires attention to log in.
+    db: DatabaseConnection,
 }
 
 impl PicnicCredentialsCache {
-    pub fn new(json_path: impl Into<PathBuf>) -> Self {
-        Self {
-            json_cache: JsonFileCache::from_file(json_path),
+    /// Load the encrypted credentials stored in `cache_dir`.
+    ///
+    /// Credentials stored in plaintext by earlier versions are migrated, and removed, first.
+    pub fn new(cache_dir: &Path, cipher: CredentialsCipher, db: DatabaseConnection) -> Self {
+        let mut file_cache = EncryptedFileCache::from_file(cache_dir.join("picnic.credentials"), cipher);
+
+        if let Err(e) = file_cache.migrate_plaintext(cache_dir.join("picnic.json")) {
+            tracing::warn!(error = ?e, "Failed to migrate plaintext Picnic credentials");
         }
+
+        Self { file_cache, db }
     }
 }
 
 impl CredentialsCache for PicnicCredentialsCache {
     fn request_credentials(&self) -> BoxFuture<wgg_providers::wgg_picnic::Result<Option<Arc<Credentials>>>> {
-        self.json_cache.request_credentials()
+        self.file_cache.request_credentials()
     }
 
     fn persist_credentials(
         &mut self,
         credentials: Arc<Credentials>,
     ) -> BoxFuture<wgg_providers::wgg_picnic::Result<()>> {
-        self.json_cache.persist_credentials(credentials)
+        self.file_cache.persist_credentials(credentials)
     }
 
+    /// Never provides a code directly, as the server might not have an attached terminal (e.g, Docker).
+    ///
+    /// Admins are notified instead, after which they can submit the code through the `picnicSubmitSecondFactor`
+    /// mutation. Picnic stays degraded until then.
     fn request_2fa_code(&self) -> BoxFuture<wgg_providers::wgg_picnic::Result<String>> {
         use futures::FutureExt;
         async move {
-            tracing::warn!("Required to provide a 2FA code from Picnic to login, please enter it into the terminal!");
-            let mut buffer = String::new();
-            io::stdin().read_line(&mut buffer).map_err(|e| anyhow::anyhow!(e))?;
-            trim_newline(&mut buffer);
-            Ok(buffer)
-        }
-        .boxed()
-    }
-}
+            let notification = Notification::new(
+                NotificationKind::ProviderLogin,
+                "Picnic login requires a 2FA code",
+                "Picnic sent a 2FA code by SMS, submit it in the admin settings of Wgg to finish logging in.",
+            );
+            if let Err(e) = notifications::notify_admins(&self.db, &notification).await {
+                tracing::warn!(error = ?e, "Failed to notify admins of Picnic 2FA request");
+            }
 
-fn trim_newline(s: &mut String) {
-    if s.ends_with('\n') {
-        s.pop();
-        if s.ends_with('\r') {
-            s.pop();
+            tracing::warn!("Picnic requires a 2FA code, it has to be submitted through `picnicSubmitSecondFactor`");
+            Err(ApiError::NoSecondFactorCode)
         }
+        .boxed()
     }
 }
@@ -0,0 +1,39 @@
+use sea_orm::DatabaseConnection;
+use std::collections::BTreeMap;
+use tokio::sync::mpsc::Receiver;
+use wgg_db_entity::DbId;
+use wgg_providers::models::{PriceObservation, Provider};
+
+/// The maximum amount of observations which can be queued before new observations are dropped.
+pub const OBSERVATION_QUEUE_SIZE: usize = 4096;
+/// The maximum amount of observations stored in a single batch.
+const MAX_BATCH_SIZE: usize = 256;
+
+/// Persist all price observations made by the providers into the `price_history` table.
+///
+/// Runs until all senders have been dropped.
+pub async fn persist_price_observations(
+    db: DatabaseConnection,
+    db_providers: BTreeMap<Provider, DbId>,
+    mut observations: Receiver<PriceObservation>,
+) {
+    while let Some(first) = observations.recv().await {
+        let mut batch = vec![first];
+
+        while batch.len() < MAX_BATCH_SIZE {
+            match observations.try_recv() {
+                Ok(observation) => batch.push(observation),
+                Err(_) => break,
+            }
+        }
+
+        let batch = batch
+            .into_iter()
+            .flat_map(|observation| Some((*db_providers.get(&observation.provider)?, observation)))
+            .collect();
+
+        if let Err(e) = crate::db::price_history::record_observations(&db, batch).await {
+            tracing::warn!(error = ?e, "Failed to persist price observations");
+        }
+    }
+}
@@ -1,7 +1,7 @@
-use crate::ids::{Id, ProductId, PromotionId, RuntimeId, TabId};
+use crate::ids::{CategoryId, Id, ProductId, PromotionId, RuntimeId, TabId};
 use crate::models::{
-    AutoCompleteResponse, FullProductResponse, ProductList, Promotion, PromotionCompletion, PromotionCompletionRequest,
-    PromotionContent, PromotionTabs, SortedByQuery,
+    AutoCompleteResponse, CategoryList, FullProductResponse, ProductList, Promotion, PromotionCompletion,
+    PromotionCompletionRequest, PromotionContent, PromotionTabs, SortedByQuery,
 };
 use crate::{ApiError, Result};
 use crate::{Config, Query};
@@ -134,6 +134,30 @@ pub trait BaseApi {
         Ok(response.json().await?)
     }
 
+    /// Retrieve the direct sub-categories of `category_id`, or all top-level categories if it is `None`.
+    async fn categories(&self, category_id: Option<&CategoryId>) -> Result<CategoryList> {
+        let query = crate::utils::build_map([("id", category_id.map(|i| i.id()))]);
+        let response = self.endpoint_get("/categories", &query).await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve the products in the given category, including those in its sub-categories.
+    ///
+    /// Like [BaseApi::products_promotion] both `count` and `offset` have to be provided.
+    async fn products_category(&self, category_id: &CategoryId, count: u32, offset: u32) -> Result<ProductList> {
+        let count = count.to_string();
+        let offset = offset.to_string();
+        let query = crate::utils::build_map([
+            ("count", Some(count.as_ref())),
+            ("offset", Some(offset.as_ref())),
+            ("categoryId", Some(category_id.id())),
+        ]);
+        let response = self.endpoint_get("/products", &query).await?;
+
+        Ok(response.json().await?)
+    }
+
     /// Retrieve all products.
     ///
     /// Note this route sends *a lot* of additional info that isn't relevant or parsed.
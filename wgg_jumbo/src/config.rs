@@ -1,5 +1,6 @@
 use reqwest::Url;
 
+#[derive(Clone)]
 pub struct Config {
     pub(crate) url: Url,
     pub(crate) user_agent: String,
@@ -21,6 +21,10 @@ pub struct TabId(String);
 #[serde(transparent)]
 pub struct PromotionId(String);
 
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Hash)]
+#[serde(transparent)]
+pub struct CategoryId(String);
+
 macro_rules! impl_types {
     ($($id:ident),+) => {
         $(
@@ -76,4 +80,4 @@ macro_rules! impl_types {
     };
 }
 
-impl_types!(ProductId, RuntimeId, TabId, PromotionId);
+impl_types!(ProductId, RuntimeId, TabId, PromotionId, CategoryId);
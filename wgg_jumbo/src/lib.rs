@@ -1,8 +1,8 @@
-use reqwest::{Client, Proxy, Response};
+use reqwest::{Client, Proxy, Response, StatusCode};
 use serde::Serialize;
 use std::collections::HashMap;
 
-use crate::models::{LoginRequest, UserResponse};
+use crate::models::{BasketItem, BasketResponse, BasketUpdateRequest, LoginRequest, UserResponse};
 use anyhow::anyhow;
 use std::time::Duration;
 
@@ -113,6 +113,30 @@ impl FullJumboApi {
         Ok(response.json().await?)
     }
 
+    /// Return the current contents of the user's basket.
+    pub async fn basket(&self) -> Result<BasketResponse> {
+        let response = Self::error_for_status("/basket", self.get("/basket", &Default::default()).await?)?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Set the quantity of the provided items in the user's basket.
+    ///
+    /// Note that the given quantities are *absolute*, an item with a quantity of `0` will be removed from the basket.
+    /// Items not mentioned in `items` are left untouched.
+    pub async fn update_basket(&self, items: &[BasketItem]) -> Result<BasketResponse> {
+        let response = self.put("/basket", &BasketUpdateRequest { items }).await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Remove all items from the user's basket.
+    pub async fn clear_basket(&self) -> Result<()> {
+        let _ = self.delete("/basket").await?;
+
+        Ok(())
+    }
+
     /// Return the current credentials used by the [FullJumboApi].
     ///
     /// Can be useful to save separately to avoid having to log in every restart.
@@ -144,6 +168,43 @@ impl FullJumboApi {
 
         Ok(response)
     }
+
+    async fn put<T: Serialize + ?Sized>(&self, url: &str, payload: &T) -> Result<Response> {
+        let response = self
+            .client
+            .put(self.config.get_full_url(url))
+            .header("x-jumbo-token", &self.credentials.auth_token)
+            .json(payload)
+            .send()
+            .await?;
+
+        Self::error_for_status(url, response)
+    }
+
+    async fn delete(&self, url: &str) -> Result<Response> {
+        let response = self
+            .client
+            .delete(self.config.get_full_url(url))
+            .header("x-jumbo-token", &self.credentials.auth_token)
+            .send()
+            .await?;
+
+        Self::error_for_status(url, response)
+    }
+
+    fn error_for_status(url: &str, response: Response) -> Result<Response> {
+        match response.status() {
+            StatusCode::OK | StatusCode::NO_CONTENT => Ok(response),
+            StatusCode::NOT_FOUND => Err(ApiError::NotFound(url.to_string())),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err(ApiError::LoginFailed(format!("Token was rejected for `{url}`")))
+            }
+            _ => {
+                tracing::warn!(status = %response.status(), ?response, "Jumbo API Error occurred");
+                Err(anyhow!("Error occurred: {}", response.status()).into())
+            }
+        }
+    }
 }
 
 impl BaseApi for FullJumboApi {
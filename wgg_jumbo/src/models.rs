@@ -1,4 +1,4 @@
-use crate::ids::{ProductId, PromotionId, RuntimeId, TabId};
+use crate::ids::{CategoryId, ProductId, PromotionId, RuntimeId, TabId};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -105,6 +105,31 @@ pub struct PromotionImage {
     pub relative_path: String,
 }
 
+// ** Categories **
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryList {
+    pub categories: CategoriesPage,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoriesPage {
+    pub data: Vec<Category>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Category {
+    pub id: CategoryId,
+    pub title: String,
+    /// The amount of direct sub-categories, `0` for categories which only contain products.
+    #[serde(default)]
+    pub sub_categories_count: u32,
+    pub image_url: Option<String>,
+}
+
 // ** Partial Products **
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -184,6 +209,16 @@ pub enum Unit {
     Other,
 }
 
+impl Unit {
+    /// The unit as expected by the basket API, which is some instance of [Unit]... but SCREAMING_CASE.
+    pub fn basket_unit(&self) -> &'static str {
+        match self {
+            Unit::Kg => "KILOGRAMS",
+            Unit::Liter | Unit::Piece | Unit::Pieces | Unit::Other => "PIECES",
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Prices {
@@ -269,6 +304,8 @@ pub struct Product {
     pub details_text: Option<String>,
     pub product_type: ProductType,
     pub quantity: Option<String>,
+    /// The EAN barcode of the product, not listed for every product (such as loose fruit).
+    pub ean: Option<String>,
     pub image_info: ImageInfo,
     #[serde(flatten)]
     pub category: CategoryInfo,
@@ -535,16 +572,21 @@ pub struct PromotionCompletionRequest {
 #[serde(rename_all = "camelCase")]
 pub struct BasketItem {
     pub(crate) sku: ProductId,
-    pub(crate) unit: &'static str,
+    pub(crate) unit: String,
     pub(crate) quantity: u32,
 }
 
 impl BasketItem {
+    /// Create a new item which is sold per piece.
     pub fn new(sku: ProductId, quantity: u32) -> Self {
-        // `unit` needs to be some instance of [Unit]... but SCREAMING_CASE.
+        Self::with_unit(sku, Unit::Pieces.basket_unit(), quantity)
+    }
+
+    /// Create a new item with the given `unit`, see [Unit::basket_unit].
+    pub fn with_unit(sku: ProductId, unit: impl Into<String>, quantity: u32) -> Self {
         Self {
             sku,
-            unit: "PIECES",
+            unit: unit.into(),
             quantity,
         }
     }
@@ -576,3 +618,37 @@ pub struct StringPrice {
     pub amount: String,
     pub currency: String,
 }
+
+// ** Basket **
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BasketResponse {
+    pub basket: Basket,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Basket {
+    pub id: String,
+    #[serde(default)]
+    pub items: Vec<BasketLine>,
+    #[serde(default)]
+    pub total_product_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BasketLine {
+    pub sku: ProductId,
+    pub quantity: u32,
+    /// Usually `PIECES`, but can be `KILOGRAMS` for weighted products.
+    pub unit: String,
+}
+
+/// Replaces the quantity of the given items in the basket, a `quantity` of `0` removes the item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BasketUpdateRequest<'a> {
+    pub items: &'a [BasketItem],
+}
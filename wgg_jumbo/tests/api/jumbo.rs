@@ -1,5 +1,5 @@
-use crate::common::base_jumbo_api;
-use crate::conditional_test;
+use crate::common::{base_jumbo_api, full_jumbo_api};
+use crate::{auth_conditional_test, conditional_test};
 use wgg_jumbo::BaseApi;
 
 #[tokio::test]
@@ -47,3 +47,12 @@ pub async fn test_search() {
 
     assert!(result.products.total > 0)
 }
+
+#[tokio::test]
+pub async fn test_basket() {
+    let api = auth_conditional_test!(full_jumbo_api);
+
+    let result = api.basket().await.unwrap();
+
+    assert!(!result.basket.id.is_empty())
+}
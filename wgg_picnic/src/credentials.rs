@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context};
+use chrono::{DateTime, Duration, Utc};
 use futures::future::BoxFuture;
 use futures::FutureExt;
 use md5::Digest;
@@ -12,6 +13,9 @@ use tokio::sync::RwLock;
 use crate::models::{LoginRequest, LoginResponse};
 use crate::{get_reqwest_client, ApiError, Config};
 
+/// How long a second factor code remains valid after it was sent.
+pub const SECOND_FACTOR_VALIDITY_MINUTES: i64 = 10;
+
 #[derive(Deserialize, Clone, Serialize, Debug)]
 pub struct Credentials {
     pub auth_token: String,
@@ -30,11 +34,50 @@ pub struct LoginCredentials {
     pub password: String,
 }
 
+/// The state of the login of a [CredentialsManager].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginStatus {
+    /// Credentials are available.
+    LoggedIn,
+    /// No credentials are available yet, they'll be acquired on the next request.
+    LoggedOut,
+    /// A login is waiting for the second factor (SMS) code, see [CredentialsManager::submit_second_factor].
+    ///
+    /// Requests fail with [ApiError::SecondFactorPending] until the code has been submitted.
+    AwaitingSecondFactor {
+        requested_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    },
+    /// The second factor code was not submitted in time, the login has to be restarted through
+    /// [CredentialsManager::restart_login].
+    ///
+    /// Requests fail with [ApiError::SecondFactorExpired] until then.
+    SecondFactorExpired { requested_at: DateTime<Utc> },
+}
+
+/// A login which has sent a second factor code, and is waiting for that code to be submitted.
+struct PendingSecondFactor {
+    headers: HeaderMap,
+    user_id: String,
+    requested_at: DateTime<Utc>,
+}
+
+impl PendingSecondFactor {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.requested_at + Duration::minutes(SECOND_FACTOR_VALIDITY_MINUTES)
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at() <= Utc::now()
+    }
+}
+
 pub struct CredentialsManager {
     config: Arc<Config>,
     login_credentials: LoginCredentials,
     cache: Box<RwLock<dyn CredentialsCache>>,
     refresh_lock: tokio::sync::Mutex<()>,
+    pending_second_factor: tokio::sync::Mutex<Option<PendingSecondFactor>>,
 }
 
 impl CredentialsManager {
@@ -44,6 +87,7 @@ impl CredentialsManager {
             login_credentials: login,
             cache: Box::new(RwLock::new(credentials_cache)),
             refresh_lock: tokio::sync::Mutex::new(()),
+            pending_second_factor: tokio::sync::Mutex::new(None),
         }
     }
 
@@ -53,6 +97,13 @@ impl CredentialsManager {
         match credentials {
             Some(creds) => Ok(creds),
             _ => {
+                // Logging in again would just send another code.
+                match self.pending_second_factor.lock().await.as_ref() {
+                    Some(pending) if pending.is_expired() => return Err(ApiError::SecondFactorExpired),
+                    Some(_) => return Err(ApiError::SecondFactorPending),
+                    None => {}
+                }
+
                 // Need to refresh the credentials.
                 // Probably better done with a separate task, but this ensures only a single task actually does the refresh.
                 match self.refresh_lock.try_lock() {
@@ -73,6 +124,87 @@ impl CredentialsManager {
         }
     }
 
+    /// The current state of the login.
+    pub async fn login_status(&self) -> crate::Result<LoginStatus> {
+        if let Some(pending) = self.pending_second_factor.lock().await.as_ref() {
+            return Ok(if pending.is_expired() {
+                LoginStatus::SecondFactorExpired {
+                    requested_at: pending.requested_at,
+                }
+            } else {
+                LoginStatus::AwaitingSecondFactor {
+                    requested_at: pending.requested_at,
+                    expires_at: pending.expires_at(),
+                }
+            });
+        }
+
+        let credentials = self.cache.read().await.request_credentials().await?;
+
+        Ok(if credentials.is_some() {
+            LoginStatus::LoggedIn
+        } else {
+            LoginStatus::LoggedOut
+        })
+    }
+
+    /// Finish a login which is waiting for a second factor code, see [LoginStatus::AwaitingSecondFactor].
+    ///
+    /// Should the code be rejected the login keeps waiting, allowing the code to be submitted again. Once the code
+    /// expired the login has to be restarted with [Self::restart_login] instead.
+    #[tracing::instrument(skip(self, code))]
+    pub async fn submit_second_factor(&self, code: &str) -> crate::Result<Arc<Credentials>> {
+        let _lock = self.refresh_lock.lock().await;
+        let pending = {
+            let mut pending = self.pending_second_factor.lock().await;
+            match pending.take() {
+                Some(expired) if expired.is_expired() => {
+                    *pending = Some(expired);
+                    return Err(ApiError::SecondFactorExpired);
+                }
+                Some(pending) => pending,
+                None => {
+                    return Err(ApiError::LoginFailed(
+                        "No login is waiting for a second factor code".to_string(),
+                    ));
+                }
+            }
+        };
+
+        let client = get_reqwest_client(&self.config.user_agent)?;
+
+        match self.verify_second_factor(&client, pending.headers.clone(), code).await {
+            Ok(auth_token) => {
+                let credentials = Arc::new(Credentials::new(auth_token, pending.user_id));
+                self.cache
+                    .write()
+                    .await
+                    .persist_credentials(credentials.clone())
+                    .await?;
+
+                Ok(credentials)
+            }
+            Err(e) => {
+                *self.pending_second_factor.lock().await = Some(pending);
+                Err(e)
+            }
+        }
+    }
+
+    /// Discard any pending login and log in again, sending a new second factor code if one is required.
+    ///
+    /// Returns the state of the new login.
+    #[tracing::instrument(skip(self))]
+    pub async fn restart_login(&self) -> crate::Result<LoginStatus> {
+        let _lock = self.refresh_lock.lock().await;
+        self.pending_second_factor.lock().await.take();
+
+        match self.force_refresh().await {
+            Ok(_) | Err(ApiError::SecondFactorPending) => self.login_status().await,
+            Err(e) => Err(e),
+        }
+    }
+
     /// Handle a response, and update the JWT.
     pub async fn handle_response(&self, response: &reqwest::Response) -> crate::Result<()> {
         match response.status() {
@@ -180,29 +312,23 @@ impl CredentialsManager {
                 return Err(ApiError::LoginFailed("Failed to send a 2fa request".to_string()));
             }
 
-            let fa_code = self.cache.read().await.request_2fa_code().await?;
-            tracing::debug!(fa_code, "Sending 2FA code");
-
-            let response = client
-                .post(self.config.get_full_url("/user/2fa/verify"))
-                .headers(headers)
-                .json(&serde_json::json!({
-                    "otp": fa_code
-                }))
-                .send()
-                .await?;
-
-            if !response.status().is_success() {
-                return Err(ApiError::LoginFailed("Failed to verify a 2fa code".to_string()));
-            }
+            let fa_code = match self.cache.read().await.request_2fa_code().await {
+                Ok(code) => code,
+                Err(ApiError::NoSecondFactorCode) => {
+                    // The code will be provided later through `submit_second_factor`.
+                    tracing::info!("Picnic login is waiting for a second factor code");
+                    *self.pending_second_factor.lock().await = Some(PendingSecondFactor {
+                        headers,
+                        user_id: login_response.user_id,
+                        requested_at: Utc::now(),
+                    });
+
+                    return Err(ApiError::SecondFactorPending);
+                }
+                Err(e) => return Err(e),
+            };
 
-            auth_token = response
-                .headers()
-                .get("x-picnic-auth")
-                .ok_or_else(|| anyhow!("No picnic auth token available in response: {:#?}", response))?
-                .to_str()
-                .context("Failed to convert to str")?
-                .to_string();
+            auth_token = self.verify_second_factor(&client, headers, &fa_code).await?;
         }
 
         Ok(Credentials {
@@ -210,6 +336,37 @@ impl CredentialsManager {
             user_id: login_response.user_id,
         })
     }
+
+    /// Verify the second factor `code`, returning the new auth token.
+    async fn verify_second_factor(
+        &self,
+        client: &reqwest::Client,
+        headers: HeaderMap,
+        code: &str,
+    ) -> crate::Result<String> {
+        tracing::debug!("Sending 2FA code");
+
+        let response = client
+            .post(self.config.get_full_url("/user/2fa/verify"))
+            .headers(headers)
+            .json(&serde_json::json!({
+                "otp": code
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::LoginFailed("Failed to verify a 2fa code".to_string()));
+        }
+
+        Ok(response
+            .headers()
+            .get("x-picnic-auth")
+            .ok_or_else(|| anyhow!("No picnic auth token available in response: {:#?}", response))?
+            .to_str()
+            .context("Failed to convert to str")?
+            .to_string())
+    }
 }
 
 pub trait CredentialsCache: Send + Sync + 'static {
@@ -240,6 +397,9 @@ pub trait CredentialsCache: Send + Sync + 'static {
     }
 
     /// Request the user to provide a 2fa code
+    ///
+    /// Return [ApiError::NoSecondFactorCode] if the code can't be provided right away, the login will then wait until
+    /// the code is submitted through [CredentialsManager::submit_second_factor].
     fn request_2fa_code(&self) -> BoxFuture<crate::Result<String>>;
 }
 
@@ -347,3 +507,166 @@ pub mod cache {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+
+    use chrono::{Duration, Utc};
+
+    use super::cache::MemoryCache;
+    use super::{CredentialsManager, LoginCredentials, LoginStatus, SECOND_FACTOR_VALIDITY_MINUTES};
+    use crate::{ApiError, Config};
+
+    const SECOND_FACTOR_CODE: &str = "123456";
+
+    /// Start a minimal Picnic API which always requires a second factor, and only accepts [SECOND_FACTOR_CODE].
+    fn mock_picnic_api() -> Config {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = Config {
+            url: format!("http://{}/api", listener.local_addr().unwrap())
+                .parse()
+                .unwrap(),
+            ..Default::default()
+        };
+
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    match header.split_once(':') {
+                        Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                            content_length = value.trim().parse().unwrap()
+                        }
+                        Some(_) => {}
+                        None => break,
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let body = String::from_utf8(body).unwrap();
+
+                let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+                let (status, auth_token, response) = match path {
+                    "/api/user/login" => (
+                        "200 OK",
+                        Some("login-token"),
+                        concat!(
+                            r#"{"user_id":"user","second_factor_authentication_required":true,"#,
+                            r#""show_second_factor_authentication_intro":false}"#
+                        ),
+                    ),
+                    "/api/user/2fa/generate" => ("200 OK", None, "{}"),
+                    "/api/user/2fa/verify" if body.contains(SECOND_FACTOR_CODE) => {
+                        ("200 OK", Some("verified-token"), "{}")
+                    }
+                    _ => ("400 Bad Request", None, "{}"),
+                };
+                let auth_header = auth_token
+                    .map(|token| format!("x-picnic-auth: {token}\r\n"))
+                    .unwrap_or_default();
+
+                let _ = write!(
+                    stream,
+                    concat!(
+                        "HTTP/1.1 {status}\r\n{auth_header}content-type: application/json\r\n",
+                        "content-length: {length}\r\nconnection: close\r\n\r\n{response}"
+                    ),
+                    status = status,
+                    auth_header = auth_header,
+                    length = response.len(),
+                    response = response,
+                );
+            }
+        });
+
+        config
+    }
+
+    fn credentials_manager() -> CredentialsManager {
+        CredentialsManager::new(
+            MemoryCache::new(None),
+            Arc::new(mock_picnic_api()),
+            LoginCredentials::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_second_factor_login() {
+        let manager = credentials_manager();
+        assert_eq!(manager.login_status().await.unwrap(), LoginStatus::LoggedOut);
+
+        // The first request starts the login, further requests shouldn't send another code.
+        assert!(matches!(
+            manager.credentials().await,
+            Err(ApiError::SecondFactorPending)
+        ));
+        assert!(matches!(
+            manager.credentials().await,
+            Err(ApiError::SecondFactorPending)
+        ));
+        assert!(matches!(
+            manager.login_status().await.unwrap(),
+            LoginStatus::AwaitingSecondFactor { .. }
+        ));
+
+        // A rejected code keeps the login waiting.
+        assert!(manager.submit_second_factor("000000").await.is_err());
+        assert!(matches!(
+            manager.login_status().await.unwrap(),
+            LoginStatus::AwaitingSecondFactor { .. }
+        ));
+
+        let credentials = manager.submit_second_factor(SECOND_FACTOR_CODE).await.unwrap();
+        assert_eq!(credentials.auth_token, "verified-token");
+        assert_eq!(credentials.user_id, "user");
+        assert_eq!(manager.login_status().await.unwrap(), LoginStatus::LoggedIn);
+        assert_eq!(manager.credentials().await.unwrap().auth_token, "verified-token");
+    }
+
+    #[tokio::test]
+    async fn test_second_factor_expiry() {
+        let manager = credentials_manager();
+        assert!(matches!(
+            manager.credentials().await,
+            Err(ApiError::SecondFactorPending)
+        ));
+
+        let requested_at = Utc::now() - Duration::minutes(SECOND_FACTOR_VALIDITY_MINUTES);
+        manager
+            .pending_second_factor
+            .lock()
+            .await
+            .as_mut()
+            .unwrap()
+            .requested_at = requested_at;
+
+        assert_eq!(
+            manager.login_status().await.unwrap(),
+            LoginStatus::SecondFactorExpired { requested_at }
+        );
+        assert!(matches!(
+            manager.credentials().await,
+            Err(ApiError::SecondFactorExpired)
+        ));
+        assert!(matches!(
+            manager.submit_second_factor(SECOND_FACTOR_CODE).await,
+            Err(ApiError::SecondFactorExpired)
+        ));
+
+        // Restarting sends a new code, which can then be submitted.
+        let status = manager.restart_login().await.unwrap();
+        assert!(matches!(status, LoginStatus::AwaitingSecondFactor { requested_at: new, .. } if new > requested_at));
+
+        manager.submit_second_factor(SECOND_FACTOR_CODE).await.unwrap();
+        assert_eq!(manager.login_status().await.unwrap(), LoginStatus::LoggedIn);
+    }
+}
@@ -12,6 +12,10 @@ pub enum ApiError {
     AuthError,
     #[error("The current credentials cache has no way of returning a second factor code")]
     NoSecondFactorCode,
+    #[error("The login is waiting for a second factor code to be submitted")]
+    SecondFactorPending,
+    #[error("The second factor code expired, the login has to be restarted")]
+    SecondFactorExpired,
     #[error(transparent)]
     Other(#[from] anyhow::Error),
     #[error(transparent)]
@@ -10,7 +10,7 @@ use std::sync::Arc;
 use reqwest::header::HeaderMap;
 use std::time::Duration;
 
-use crate::credentials::{CredentialsCache, CredentialsManager};
+use crate::credentials::{CredentialsCache, CredentialsManager, LoginStatus};
 pub use crate::{config::Config, credentials::Credentials, credentials::LoginCredentials, error::ApiError};
 
 mod config;
@@ -54,6 +54,22 @@ impl PicnicApi {
         }
     }
 
+    /// The current state of the login, see [LoginStatus].
+    pub async fn login_status(&self) -> Result<LoginStatus> {
+        self.cred_manager.login_status().await
+    }
+
+    /// Finish a login which is waiting for the second factor code sent by SMS.
+    pub async fn submit_second_factor(&self, code: &str) -> Result<()> {
+        self.cred_manager.submit_second_factor(code).await?;
+        Ok(())
+    }
+
+    /// Discard any pending login and log in again, which sends a new second factor code if one is required.
+    pub async fn restart_login(&self) -> Result<LoginStatus> {
+        self.cred_manager.restart_login().await
+    }
+
     /// Query all user details of the current user.
     pub async fn user_details(&self) -> Result<UserInfo> {
         let response = self.get("/user", &[]).await?;
@@ -294,6 +310,17 @@ impl PicnicApi {
         Ok(response.json().await?)
     }
 
+    /// Returns the contents of the top-level list with the given id, including all its articles.
+    ///
+    /// If `sublist_id` is specified then only the contents of that sub-list of `list_id` are returned.
+    pub async fn list(&self, list_id: &ListId, sublist_id: Option<&ListId>) -> Result<Vec<Category>> {
+        let url = format!("/lists/{list_id}");
+        let query = sublist_id.map(|sublist_id| ("sublist", sublist_id));
+        let response = self.get(&url, query.as_slice()).await?;
+
+        Ok(response.json().await?)
+    }
+
     /// Returns all lists and sub-lists.
     /// Note that this returns (almost) the exact same as the catalogue provided in [PicnicApi::categories].
     ///
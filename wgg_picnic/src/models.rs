@@ -137,6 +137,8 @@ pub struct SingleArticle {
     #[serde(default)]
     pub unit_quantity: String,
     pub unit_quantity_sub: Option<String>,
+    /// The barcode of the article, not present in every listing.
+    pub gtin: Option<String>,
 }
 
 // ** Decorator **
@@ -309,6 +311,8 @@ pub struct ProductArticle {
     pub max_order_quantity: i32,
     /// Contains the quantity of product, aka `625 grams` or `4-6 pers | 30 mins`
     pub unit_quantity: String,
+    /// The barcode of the article, missing for products without one, such as meal kits.
+    pub gtin: Option<String>,
     pub category_link: Option<String>,
     pub allergies: Allergies,
     /// Contains unstructured info tid-bits like `Na bezorging minimaal 3 dagen vers` or `Binnen 30 minuten op tafel`
@@ -727,10 +731,12 @@ pub struct SelectedSlot {
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DepositBreakdown {
+    /// The kind of deposit, such as `BOTTLE` or `CRATE`.
     #[serde(rename = "type")]
     pub type_field: String,
-    value: i64,
-    count: i64,
+    /// The deposit per item, in cents.
+    pub value: i64,
+    pub count: i64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -813,6 +819,9 @@ pub struct DeliverySlot {
     pub reserved: bool,
     pub minimum_order_value: i64,
     pub unavailability_reason: Option<String>,
+    /// Only available in markets where Picnic charges for delivery.
+    #[serde(default)]
+    pub delivery_fee: Option<i64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
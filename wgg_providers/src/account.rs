@@ -0,0 +1,124 @@
+use crate::error::{ProviderError, Result};
+use crate::models::{
+    ProductIdRef, Provider, WggCartItem, WggCheckoutStatus, WggDeliverySlot, WggOrder, WggOrderSummary, WggRemoteTally,
+};
+use crate::providers::{PicnicBridge, ProviderCart, ProviderDelivery};
+use crate::{DynProvider, PicnicLoginStatus};
+use std::sync::Arc;
+
+/// The remote cart of a single provider account.
+///
+/// Unlike the cart operations on [WggProvider](crate::WggProvider), which all act on the one account the providers were
+/// built with, an [AccountCart] acts on the account it was created for.
+/// See [WggProvider::picnic_account_cart](crate::WggProvider::picnic_account_cart).
+///
+/// The cart holds on to the session of the account, it should therefore be kept around between operations.
+#[derive(Clone)]
+pub struct AccountCart {
+    provider: Provider,
+    bridge: Arc<DynProvider>,
+}
+
+impl AccountCart {
+    pub(crate) fn new<T: ProviderCart + Send + Sync>(cart: T) -> Self {
+        Self {
+            provider: cart.provider(),
+            bridge: Arc::new(cart),
+        }
+    }
+
+    /// The provider this account belongs to.
+    pub fn provider(&self) -> Provider {
+        self.provider
+    }
+
+    /// Add the given item(s) and the quantity thereof to the cart of this account.
+    pub async fn add_to_cart(&self, items: &[(&ProductIdRef, u32)]) -> Result<()> {
+        self.cart()?.add_to_cart(items).await
+    }
+
+    /// Remove the given item(s) and the quantity thereof from the cart of this account.
+    pub async fn remove_from_cart(&self, items: &[(&ProductIdRef, u32)]) -> Result<()> {
+        self.cart()?.remove_from_cart(items).await
+    }
+
+    /// Clear the remote cart of this account.
+    pub async fn clear_cart(&self) -> Result<()> {
+        self.cart()?.clear_cart().await
+    }
+
+    /// Retrieve the contents of the remote cart of this account.
+    pub async fn cart_contents(&self) -> Result<Vec<WggCartItem>> {
+        self.cart()?.cart_contents().await
+    }
+
+    /// Retrieve the tally of the remote cart of this account, including the progress of the given sales.
+    pub async fn cart_tally(&self, sale_ids: &[&str]) -> Result<WggRemoteTally> {
+        self.cart()?.cart_tally(sale_ids).await
+    }
+
+    /// Retrieve all delivery slots for the cart of this account.
+    pub async fn delivery_slots(&self) -> Result<Vec<WggDeliverySlot>> {
+        self.delivery()?.delivery_slots().await
+    }
+
+    /// Select the delivery slot with the given id for the cart of this account.
+    pub async fn select_delivery_slot(&self, slot_id: &str) -> Result<()> {
+        self.delivery()?.select_delivery_slot(slot_id).await
+    }
+
+    /// Retrieve the status of the checkout of the cart of this account.
+    pub async fn checkout_status(&self) -> Result<WggCheckoutStatus> {
+        self.delivery()?.checkout_status().await
+    }
+
+    /// Retrieve all orders of this account which have been delivered, most recent first.
+    pub async fn delivered_orders(&self) -> Result<Vec<WggOrderSummary>> {
+        self.delivery()?.delivered_orders().await
+    }
+
+    /// Retrieve the full contents of the delivered order with the given id.
+    pub async fn delivered_order(&self, order_id: &str) -> Result<WggOrder> {
+        self.delivery()?.delivered_order(order_id).await
+    }
+
+    /// The state of the login of this Picnic account.
+    ///
+    /// Whilst the login is awaiting its second factor code all cart operations fail, see
+    /// [Self::picnic_submit_second_factor].
+    pub async fn picnic_login_status(&self) -> Result<PicnicLoginStatus> {
+        self.picnic()?.login_status().await
+    }
+
+    /// Submit the second factor (SMS) code of the login of this Picnic account.
+    pub async fn picnic_submit_second_factor(&self, code: &str) -> Result<()> {
+        self.picnic()?.submit_second_factor(code).await
+    }
+
+    /// Discard any pending login of this Picnic account and log in again, sending a new second factor code if one is
+    /// required.
+    pub async fn picnic_restart_login(&self) -> Result<PicnicLoginStatus> {
+        self.picnic()?.restart_login().await
+    }
+
+    fn picnic(&self) -> Result<&PicnicBridge> {
+        (*self.bridge)
+            .as_any()
+            .downcast_ref::<PicnicBridge>()
+            .ok_or_else(|| ProviderError::OperationUnsupported("The account is not a Picnic account".to_string()))
+    }
+
+    fn cart(&self) -> Result<&(dyn ProviderCart + Send + Sync)> {
+        self.bridge.as_cart_provider().ok_or_else(|| {
+            ProviderError::OperationUnsupported("Cart operations are not supported on the given provider".to_string())
+        })
+    }
+
+    fn delivery(&self) -> Result<&(dyn ProviderDelivery + Send + Sync)> {
+        self.bridge.as_delivery_provider().ok_or_else(|| {
+            ProviderError::OperationUnsupported(
+                "Delivery operations are not supported on the given provider".to_string(),
+            )
+        })
+    }
+}
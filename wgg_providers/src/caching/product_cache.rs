@@ -1,5 +1,5 @@
 use crate::models::WggSaleGroupComplete;
-use crate::models::{Provider, WggProduct, WggSearchProduct};
+use crate::models::{gtin_key, GtinRef, Provider, WggProduct, WggSearchProduct};
 use chrono::{DateTime, Utc};
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -15,6 +15,10 @@ pub struct WggProviderCache {
     full_products: WggCacheMap<WggProduct>,
     search_products: WggCacheMap<WggSearchProduct>,
     promotions: WggCacheMap<WggSaleGroupComplete, String>,
+    /// Maps the [gtin_key] of a barcode to the id of the product it belongs to.
+    ///
+    /// Not persisted, it is rebuilt from the product caches instead.
+    barcodes: HashMap<Provider, moka::sync::Cache<String, ProductId>>,
     ttl: chrono::Duration,
 }
 
@@ -37,6 +41,7 @@ impl WggProviderCache {
             full_products: cache.full_products,
             search_products: cache.search_products,
             promotions: cache.promotions,
+            barcodes: HashMap::new(),
             ttl: chrono::Duration::from_std(cache_lifetime).unwrap(),
         };
 
@@ -51,6 +56,24 @@ impl WggProviderCache {
                     .time_to_live(cache_lifetime)
                     .build()
             });
+            result.barcodes.insert(
+                provider,
+                moka::sync::CacheBuilder::new(max_products.get() as u64)
+                    .time_to_live(cache_lifetime)
+                    .build(),
+            );
+        }
+
+        for (provider, cache) in result.search_products.iter() {
+            for (_, entry) in cache.iter() {
+                result.index_barcode(*provider, entry.entry.gtin.as_deref(), &entry.entry.id);
+            }
+        }
+
+        for (provider, cache) in result.full_products.iter() {
+            for (_, entry) in cache.iter() {
+                result.index_barcode(*provider, entry.entry.gtin.as_deref(), &entry.entry.id);
+            }
         }
 
         result
@@ -96,8 +119,20 @@ impl WggProviderCache {
         self.get_or_invalidate(product_id, full_cache)
     }
 
+    /// Try to find the product with the given barcode.
+    ///
+    /// Only products which were cached with their barcode can be found, the full product is preferred.
+    pub fn get_search_product_by_gtin(&self, provider: Provider, gtin: &GtinRef) -> Option<WggSearchProduct> {
+        let product_id = self.barcodes.get(&provider)?.get(gtin_key(gtin))?;
+
+        self.get_product(provider, &product_id)
+            .map(|item| item.into())
+            .or_else(|| self.get_search_product(provider, &product_id))
+    }
+
     pub fn insert_search_product(&self, provider: Provider, product: WggSearchProduct) -> Option<()> {
         let search_cache = self.search_products.get(&provider)?;
+        self.index_barcode(provider, product.gtin.as_deref(), &product.id);
         let to_insert = CacheEntry {
             entry: product,
             inserted_at: Utc::now(),
@@ -109,6 +144,7 @@ impl WggProviderCache {
 
     pub fn insert_product(&self, provider: Provider, product: WggProduct, product_id: &str) -> Option<()> {
         let full_cache = self.full_products.get(&provider)?;
+        self.index_barcode(provider, product.gtin.as_deref(), &product.id);
         let to_insert = CacheEntry {
             entry: product,
             inserted_at: Utc::now(),
@@ -127,6 +163,12 @@ impl WggProviderCache {
         Some(())
     }
 
+    fn index_barcode(&self, provider: Provider, gtin: Option<&GtinRef>, product_id: &str) {
+        if let (Some(gtin), Some(barcodes)) = (gtin, self.barcodes.get(&provider)) {
+            barcodes.insert(gtin_key(gtin).to_string(), product_id.to_string());
+        }
+    }
+
     /// Retrieves the associated item with the given key from the given cache.
     ///
     /// If the TTL defined by our application has been exceeded the item will be invalidated and `None` will be returned.
@@ -1,4 +1,4 @@
-use crate::error::SubProviderError::{JumboMiscError, PicnicMiscError};
+use crate::error::SubProviderError::{AhMiscError, JumboMiscError, PicnicMiscError};
 use crate::models::Provider;
 use thiserror::Error;
 
@@ -14,6 +14,8 @@ pub enum ProviderError {
     ProviderUninitialised(Provider),
     #[error("Provider: {0:?} - Failure: {1:?}")]
     SubProviderError(Provider, SubProviderError),
+    #[error("Invalid sale label rule: {0}")]
+    InvalidSaleRule(String),
     #[error("Operation `{0}` is not supported on this provider")]
     OperationUnsupported(String),
     #[error(transparent)]
@@ -24,12 +26,17 @@ pub enum ProviderError {
 
 impl From<wgg_picnic::ApiError> for ProviderError {
     fn from(value: wgg_picnic::ApiError) -> Self {
-        Self::SubProviderError(Provider::Picnic, value.into())
+        Self::SubProviderError(Provider::PICNIC, value.into())
     }
 }
 impl From<wgg_jumbo::ApiError> for ProviderError {
     fn from(value: wgg_jumbo::ApiError) -> Self {
-        Self::SubProviderError(Provider::Jumbo, value.into())
+        Self::SubProviderError(Provider::JUMBO, value.into())
+    }
+}
+impl From<wgg_ah::ApiError> for ProviderError {
+    fn from(value: wgg_ah::ApiError) -> Self {
+        Self::SubProviderError(Provider::ALBERT_HEIJN, value.into())
     }
 }
 
@@ -43,6 +50,8 @@ pub enum SubProviderError {
     PicnicMiscError(wgg_picnic::ApiError),
     #[error(transparent)]
     JumboMiscError(wgg_jumbo::ApiError),
+    #[error(transparent)]
+    AhMiscError(wgg_ah::ApiError),
 }
 
 impl From<wgg_picnic::ApiError> for SubProviderError {
@@ -64,3 +73,13 @@ impl From<wgg_jumbo::ApiError> for SubProviderError {
         }
     }
 }
+
+impl From<wgg_ah::ApiError> for SubProviderError {
+    fn from(value: wgg_ah::ApiError) -> Self {
+        match value {
+            wgg_ah::ApiError::LoginFailed(val) => SubProviderError::LoginFailed(val),
+            wgg_ah::ApiError::NotFound(url_suffix) => SubProviderError::NotFound(url_suffix),
+            _ => AhMiscError(value),
+        }
+    }
+}
@@ -2,26 +2,41 @@ use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 
+pub use account::AccountCart;
 pub use caching::SerdeCache;
 pub use error::ProviderError;
+pub use matching::ProductMatch;
 pub use providers::PICNIC_RECOMMENDED_RPS;
+pub use registry::{is_registered, provider_metadata, register_provider, registered_providers, ProviderRegistration};
 pub use sale_resolver::SaleInfo;
+pub use sale_rules::{SaleLabelRule, SaleLabelRules, SaleRuleKind};
 pub use wgg_picnic;
-pub use wgg_provider::{ProvidersIter, WggProvider, WggProviderBuilder};
+pub use wgg_picnic::credentials::LoginStatus as PicnicLoginStatus;
+pub use wgg_provider::{PriceObserver, ProvidersIter, WggProvider, WggProviderBuilder};
 
 use crate::models::Provider;
-pub use crate::providers::PicnicCredentials;
+pub use crate::providers::{JumboCredentials, PicnicCredentials};
 use crate::providers::ProviderInfo;
 
+mod account;
 mod caching;
 mod error;
+mod matching;
 pub mod models;
 pub mod pagination;
 mod providers;
+mod registry;
 mod sale_resolver;
+mod sale_rules;
 mod scheduled_jobs;
 mod wgg_provider;
 
+/// Everything required to implement a provider bridge outside of this crate, see [ProviderRegistration].
+pub mod bridge {
+    pub use crate::error::Result;
+    pub use crate::providers::{ProviderCart, ProviderInfo, ProviderToAny, StaticProviderInfo};
+}
+
 pub(crate) type ProviderMap<T> = HashMap<Provider, T>;
 pub(crate) type DynProvider = dyn ProviderInfo + Send + Sync;
 
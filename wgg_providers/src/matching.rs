@@ -0,0 +1,252 @@
+//! Find equivalent products across providers.
+//!
+//! Products with the same barcode are always a perfect match, other products are scored on their normalised name,
+//! their brand, and their [UnitQuantity].
+//! The brand of a product is derived from the first word of its name, the store brands of all providers are considered
+//! to be the same brand.
+//!
+//! Search results of different providers are interleaved by their [relevance] to the query.
+use crate::models::{gtin_key, Unit, UnitQuantity, WggSearchProduct};
+use std::collections::BTreeSet;
+
+/// The minimum score a product needs to be considered an equivalent.
+pub(crate) const MIN_MATCH_SCORE: f64 = 0.5;
+/// The maximum amount of equivalents returned per provider.
+pub(crate) const MAX_MATCHES_PER_PROVIDER: usize = 3;
+
+/// Name prefixes of the store brands, these are treated as one and the same brand.
+const STORE_BRANDS: &[&str] = &["ah", "jumbo", "picnic"];
+
+const NAME_WEIGHT: f64 = 0.6;
+const BRAND_WEIGHT: f64 = 0.2;
+const QUANTITY_WEIGHT: f64 = 0.2;
+
+const QUERY_WEIGHT: f64 = 0.5;
+const RANK_WEIGHT: f64 = 0.5;
+/// The rank at which a provider's own ordering contributes half of [RANK_WEIGHT].
+const RANK_HALF_LIFE: f64 = 10.0;
+
+/// A product which is likely equivalent to another product at a different provider.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProductMatch {
+    pub product: WggSearchProduct,
+    /// How likely the product is an equivalent, between `0.0` and `1.0`.
+    pub score: f64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct NormalisedName {
+    /// The brand of the product, `None` for store brands.
+    brand: Option<String>,
+    /// All other words in the name.
+    words: BTreeSet<String>,
+}
+
+impl NormalisedName {
+    fn new(name: &str) -> Self {
+        let mut words = words(name);
+
+        let brand = words.next().filter(|first| !STORE_BRANDS.contains(&first.as_str()));
+
+        Self {
+            brand,
+            words: words.collect(),
+        }
+    }
+}
+
+/// Split the given text into lowercase words, ignoring any punctuation.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Create a search query which should find equivalents of the product with the given name at other providers.
+pub(crate) fn search_query(name: &str) -> String {
+    let normalised = NormalisedName::new(name);
+
+    normalised
+        .brand
+        .into_iter()
+        .chain(normalised.words)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Score all `candidates` against `product`.
+///
+/// Returns the best [MAX_MATCHES_PER_PROVIDER] candidates with a score of at least [MIN_MATCH_SCORE].
+pub(crate) fn best_matches(
+    product: &WggSearchProduct,
+    candidates: impl IntoIterator<Item = WggSearchProduct>,
+) -> Vec<ProductMatch> {
+    let mut matches: Vec<_> = candidates
+        .into_iter()
+        .map(|candidate| ProductMatch {
+            score: score(product, &candidate),
+            product: candidate,
+        })
+        .filter(|candidate| candidate.score >= MIN_MATCH_SCORE)
+        .collect();
+
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(MAX_MATCHES_PER_PROVIDER);
+
+    matches
+}
+
+/// Score how likely `candidate` is an equivalent of `product`, between `0.0` and `1.0`.
+pub(crate) fn score(product: &WggSearchProduct, candidate: &WggSearchProduct) -> f64 {
+    let same_gtin = product
+        .gtin
+        .as_deref()
+        .zip(candidate.gtin.as_deref())
+        .is_some_and(|(gtin, candidate_gtin)| gtin_key(gtin) == gtin_key(candidate_gtin));
+
+    if same_gtin {
+        return 1.0;
+    }
+
+    let name = NormalisedName::new(&product.name);
+    let candidate_name = NormalisedName::new(&candidate.name);
+
+    let name_score = jaccard(&name.words, &candidate_name.words);
+    let brand_score = match (&name.brand, &candidate_name.brand) {
+        (None, None) => 1.0,
+        (Some(brand), Some(candidate_brand)) if brand == candidate_brand => 1.0,
+        (Some(_), Some(_)) => 0.0,
+        // A store brand product can still be a fine replacement for a branded product.
+        _ => 0.5,
+    };
+    let quantity_score = quantity_similarity(&product.unit_quantity, &candidate.unit_quantity);
+
+    NAME_WEIGHT * name_score + BRAND_WEIGHT * brand_score + QUANTITY_WEIGHT * quantity_score
+}
+
+/// Score how relevant `product` is as a search result for `query`, between `0.0` and `1.0`.
+///
+/// `rank` is the position of the product in its provider's own search results. Every provider orders its results by
+/// its own notion of relevance, which is combined with the share of the query's words found in the product name.
+pub(crate) fn relevance(query: &str, product: &WggSearchProduct, rank: usize) -> f64 {
+    let query: BTreeSet<String> = words(query).collect();
+    let name: BTreeSet<String> = words(&product.name).collect();
+
+    let query_score = if query.is_empty() {
+        0.0
+    } else {
+        query.intersection(&name).count() as f64 / query.len() as f64
+    };
+    let rank_score = RANK_HALF_LIFE / (RANK_HALF_LIFE + rank as f64);
+
+    QUERY_WEIGHT * query_score + RANK_WEIGHT * rank_score
+}
+
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let union = a.union(b).count();
+
+    if union == 0 {
+        0.0
+    } else {
+        a.intersection(b).count() as f64 / union as f64
+    }
+}
+
+/// The ratio between the smaller and larger quantity, `0.0` if the quantities aren't of the same kind of unit.
+fn quantity_similarity(a: &UnitQuantity, b: &UnitQuantity) -> f64 {
+    let (Some((unit, amount)), Some((other_unit, other_amount))) = (base_quantity(a), base_quantity(b)) else {
+        return 0.0;
+    };
+
+    if unit != other_unit || amount <= 0.0 || other_amount <= 0.0 {
+        0.0
+    } else {
+        amount.min(other_amount) / amount.max(other_amount)
+    }
+}
+
+/// Normalise the given quantity to kilograms, liters, or pieces.
+fn base_quantity(quantity: &UnitQuantity) -> Option<(Unit, f64)> {
+    let result = match quantity.unit {
+        Unit::Piece => (Unit::Piece, quantity.amount),
+        Unit::Liter => (Unit::Liter, quantity.amount),
+        Unit::MilliLiter => (Unit::Liter, quantity.amount / 1000.0),
+        Unit::KiloGram => (Unit::KiloGram, quantity.amount),
+        Unit::Gram => (Unit::KiloGram, quantity.amount / 1000.0),
+    };
+
+    result.1.is_finite().then_some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{relevance, score, search_query, NormalisedName};
+    use crate::models::{PriceInfo, Provider, Unit, UnitQuantity, WggSearchProduct};
+    use std::collections::BTreeSet;
+
+    fn product(name: &str, unit: Unit, amount: f64) -> WggSearchProduct {
+        WggSearchProduct {
+            id: name.to_string(),
+            name: name.to_string(),
+            price_info: PriceInfo {
+                display_price: 100,
+                original_price: 100,
+                unit_price: None,
+                deposit: None,
+            },
+            unit_quantity: UnitQuantity { unit, amount },
+            gtin: None,
+            unavailable_details: None,
+            image_url: None,
+            decorators: Vec::new(),
+            sale_information: None,
+            provider: Provider::JUMBO,
+        }
+    }
+
+    #[test]
+    fn test_normalised_name() {
+        let name = NormalisedName::new("AH Halfvolle melk");
+        assert_eq!(name.brand, None);
+        assert_eq!(name.words, BTreeSet::from(["halfvolle", "melk"].map(String::from)));
+
+        assert_eq!(
+            NormalisedName::new("Campina Halfvolle Melk").brand,
+            Some("campina".into())
+        );
+        assert_eq!(search_query("Jumbo Halfvolle Melk"), "halfvolle melk");
+        assert_eq!(search_query("Coca-Cola Zero"), "coca cola zero");
+    }
+
+    #[test]
+    fn test_score() {
+        let milk = product("AH Halfvolle melk", Unit::Liter, 1.0);
+
+        let store_brand = score(&milk, &product("Jumbo Halfvolle Melk", Unit::MilliLiter, 1000.0));
+        let branded = score(&milk, &product("Campina Halfvolle melk", Unit::Liter, 1.5));
+        let other = score(&milk, &product("Jumbo Volle Yoghurt", Unit::Gram, 500.0));
+
+        assert!((store_brand - 1.0).abs() < f64::EPSILON);
+        assert!(branded < store_brand && branded >= super::MIN_MATCH_SCORE);
+        assert!(other < super::MIN_MATCH_SCORE);
+
+        let mut barcoded = product("Jumbo Volle Yoghurt", Unit::Gram, 500.0);
+        barcoded.gtin = Some("08710400280307".to_string());
+        let milk = WggSearchProduct {
+            gtin: Some("8710400280307".to_string()),
+            ..milk
+        };
+        assert!((score(&milk, &barcoded) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_relevance() {
+        let milk = product("AH Halfvolle melk", Unit::Liter, 1.0);
+        let yoghurt = product("Jumbo Volle Yoghurt", Unit::Gram, 500.0);
+
+        assert!((relevance("halfvolle melk", &milk, 0) - 1.0).abs() < f64::EPSILON);
+        assert!(relevance("Halfvolle Melk", &milk, 10) > relevance("halfvolle melk", &yoghurt, 0));
+        assert!(relevance("melk", &milk, 0) > relevance("melk", &milk, 1));
+        assert!(relevance("", &yoghurt, 0) <= super::RANK_WEIGHT);
+    }
+}
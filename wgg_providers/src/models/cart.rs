@@ -0,0 +1,36 @@
+use crate::models::{CentPrice, ProductId, SublistId, WggOrderLine};
+use serde::{Deserialize, Serialize};
+
+/// A product in the remote cart of a provider.
+#[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WggCartItem {
+    pub product_id: ProductId,
+    pub quantity: u32,
+}
+
+/// The tally of the remote cart as calculated by the provider itself.
+///
+/// Every field is only as complete as the provider reports it, and can therefore be used as the ground truth to compare
+/// our own tally with.
+#[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub struct WggRemoteTally {
+    /// The lines of the remote cart with the prices the provider charges, empty if the provider doesn't list prices.
+    pub lines: Vec<WggOrderLine>,
+    /// The price of all products after discounts, excluding deposits and fees.
+    pub total_price: Option<CentPrice>,
+    pub deposit: Option<CentPrice>,
+    /// The progress of promotions in the remote cart.
+    pub promotions: Vec<WggRemotePromotion>,
+}
+
+/// The progress of a single promotion in the remote cart.
+#[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub struct WggRemotePromotion {
+    pub sale_id: SublistId,
+    /// Whether the provider applied the promotion to the cart at least once.
+    pub applied: bool,
+    /// The amount of additional items required for the promotion to apply (again).
+    pub missing_quantity: Option<u32>,
+    /// The total discount given by this promotion.
+    pub savings: Option<CentPrice>,
+}
@@ -0,0 +1,42 @@
+use crate::models::{Provider, ProviderInfo};
+use serde::{Deserialize, Serialize};
+
+/// A category of a provider's catalogue, such as `Zuivel` or `Groente & Fruit`.
+///
+/// Use [WggCategory::id] to retrieve the products in this category, including those of its sub-categories.
+#[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq)]
+#[graphql(complex)]
+pub struct WggCategory {
+    /// This service's ID for the current category.
+    /// Not transferable between [Provider]s
+    pub id: String,
+    pub name: String,
+    /// Direct URL to the category's image.
+    pub image_url: Option<String>,
+    /// All sub-categories, in the order the provider lists them.
+    ///
+    /// Can be empty if the provider doesn't list any, or only lists them when browsing the category itself.
+    pub children: Vec<WggCategory>,
+    /// The grocery store this category is provided from.
+    #[graphql(skip)]
+    pub provider: Provider,
+}
+
+#[async_graphql::ComplexObject]
+impl WggCategory {
+    /// Grocery store information associated with this category
+    async fn provider_info(&self) -> ProviderInfo {
+        self.provider.as_provider_info()
+    }
+}
+
+impl WggCategory {
+    /// Find the category with the given `id` among this category and all its descendants.
+    pub fn find(&self, id: &str) -> Option<&WggCategory> {
+        if self.id == id {
+            Some(self)
+        } else {
+            self.children.iter().find_map(|child| child.find(id))
+        }
+    }
+}
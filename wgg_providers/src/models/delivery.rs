@@ -0,0 +1,75 @@
+use crate::models::CentPrice;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A window in which a provider can deliver an order.
+#[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WggDeliverySlot {
+    pub id: String,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    /// The moment after which an order can no longer be placed, or changed, for this slot.
+    pub cut_off_time: DateTime<Utc>,
+    /// Whether this slot can currently be selected.
+    pub available: bool,
+    /// Whether this slot is selected for the current cart.
+    pub selected: bool,
+    /// Explains why the slot is unavailable, if the provider gave a reason.
+    pub unavailability_reason: Option<String>,
+    /// The minimum value (after discounts, excluding deposits) the cart needs to have for this slot.
+    pub minimum_order_value: CentPrice,
+    /// The fee charged for a delivery in this slot.
+    pub delivery_fee: CentPrice,
+}
+
+/// The status of the checkout of the current remote cart.
+#[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub struct WggCheckoutStatus {
+    /// The id of the order which is being checked out, if the provider assigned one yet.
+    pub order_id: Option<String>,
+    pub state: CheckoutState,
+    /// The id of the slot selected for the order, see [WggDeliverySlot::id].
+    pub selected_slot_id: Option<String>,
+    /// The total price of the order, including any fees.
+    pub total_price: CentPrice,
+}
+
+#[derive(Serialize, Deserialize, async_graphql::Enum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckoutState {
+    /// No checkout has been started for the current cart.
+    NotStarted,
+    Ongoing,
+    Finished,
+    Failed,
+    Abandoned,
+    Unknown,
+}
+
+/// A summary of an order which has been delivered, see [WggOrder] for its contents.
+#[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub struct WggOrderSummary {
+    pub id: String,
+    pub delivered_at: DateTime<Utc>,
+}
+
+/// An order which has been delivered, with the prices which were actually paid.
+#[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub struct WggOrder {
+    pub id: String,
+    pub delivered_at: DateTime<Utc>,
+    pub lines: Vec<WggOrderLine>,
+    /// The deposit (statiegeld) charged on top of the products.
+    pub deposit: CentPrice,
+    pub delivery_fee: CentPrice,
+    pub service_fee: CentPrice,
+}
+
+#[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub struct WggOrderLine {
+    pub product_id: String,
+    pub quantity: u32,
+    /// The full price of all `quantity` items, ignoring any sales.
+    pub original_price: CentPrice,
+    /// The price paid for all `quantity` items, after discounts.
+    pub display_price: CentPrice,
+}
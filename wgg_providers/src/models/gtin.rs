@@ -0,0 +1,35 @@
+/// A GTIN barcode (EAN-8, EAN-13, UPC-A, or GTIN-14), as printed on the product.
+pub type Gtin = String;
+pub type GtinRef = str;
+
+/// Parse the given barcode, returning `None` if it isn't a GTIN.
+///
+/// Surrounding whitespace is ignored, but the barcode's own leading zeroes are kept.
+pub fn parse_gtin(barcode: &str) -> Option<Gtin> {
+    let barcode = barcode.trim();
+    let valid = (8..=14).contains(&barcode.len()) && barcode.bytes().all(|c| c.is_ascii_digit());
+
+    valid.then(|| barcode.to_string())
+}
+
+/// The key under which a [Gtin] should be compared.
+///
+/// The same product can be listed as an EAN-13 at one provider and as a zero-padded GTIN-14 at another.
+pub(crate) fn gtin_key(gtin: &GtinRef) -> &GtinRef {
+    gtin.trim_start_matches('0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gtin_key, parse_gtin};
+
+    #[test]
+    fn test_parse_gtin() {
+        assert_eq!(parse_gtin(" 8710400280307 "), Some("8710400280307".to_string()));
+        assert_eq!(parse_gtin("08710400280307"), Some("08710400280307".to_string()));
+        assert_eq!(parse_gtin("1234567"), None);
+        assert_eq!(parse_gtin("87104002803A7"), None);
+
+        assert_eq!(gtin_key("08710400280307"), gtin_key("8710400280307"));
+    }
+}
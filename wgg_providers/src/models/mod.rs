@@ -1,11 +1,23 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+mod cart;
+mod category;
+mod delivery;
+mod gtin;
+mod outcome;
+mod price_history;
 mod product;
 mod providers;
 mod sale;
 mod search_product;
 
+pub use cart::*;
+pub use category::*;
+pub use delivery::*;
+pub use gtin::*;
+pub use outcome::*;
+pub use price_history::*;
 pub use product::*;
 pub use providers::*;
 pub use sale::*;
@@ -118,6 +130,11 @@ pub struct PriceInfo {
     /// The full price of an article, ignoring any sales
     pub original_price: CentPrice,
     pub unit_price: Option<UnitPrice>,
+    /// The deposit (statiegeld) charged on top of the price, if any.
+    ///
+    /// Not every provider exposes this in their search results, so this can be `None` for products with a deposit.
+    #[serde(default)]
+    pub deposit: Option<CentPrice>,
 }
 
 #[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq, PartialOrd)]
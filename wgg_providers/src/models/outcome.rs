@@ -0,0 +1,100 @@
+use crate::error::ProviderError;
+use crate::models::{Provider, ProviderInfo};
+use serde::{Deserialize, Serialize};
+
+/// Results gathered from multiple providers at the same time, together with the outcome of every provider.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MultiProviderResult<T> {
+    pub items: Vec<T>,
+    /// The outcome of every queried provider.
+    pub outcomes: Vec<ProviderOutcome>,
+}
+
+#[derive(Serialize, Deserialize, async_graphql::Enum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OutcomeStatus {
+    /// The provider responded as expected.
+    Ok,
+    /// The provider responded, but its results are likely incomplete.
+    Degraded,
+    /// The provider failed to respond, none of its results are included.
+    Failed,
+}
+
+/// The outcome of a single provider in a query across multiple providers.
+#[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, Eq)]
+#[graphql(complex)]
+pub struct ProviderOutcome {
+    #[graphql(skip)]
+    pub provider: Provider,
+    pub status: OutcomeStatus,
+    /// A summary of what went wrong, `None` if the provider responded as expected.
+    pub message: Option<String>,
+}
+
+#[async_graphql::ComplexObject]
+impl ProviderOutcome {
+    /// Grocery store information associated with this outcome
+    async fn provider_info(&self) -> ProviderInfo {
+        self.provider.as_provider_info()
+    }
+}
+
+impl ProviderOutcome {
+    pub fn ok(provider: Provider) -> Self {
+        Self {
+            provider,
+            status: OutcomeStatus::Ok,
+            message: None,
+        }
+    }
+
+    pub fn degraded(provider: Provider, message: impl Into<String>) -> Self {
+        Self {
+            provider,
+            status: OutcomeStatus::Degraded,
+            message: Some(message.into()),
+        }
+    }
+
+    pub fn failed(provider: Provider, error: &ProviderError) -> Self {
+        let message = match error {
+            // The provider is already known, and the inner error is more readable on its own.
+            ProviderError::SubProviderError(_, e) => e.to_string(),
+            e => e.to_string(),
+        };
+
+        Self {
+            provider,
+            status: OutcomeStatus::Failed,
+            message: Some(message),
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.status == OutcomeStatus::Ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutcomeStatus, ProviderOutcome};
+    use crate::error::{ProviderError, SubProviderError};
+    use crate::models::Provider;
+
+    #[test]
+    fn test_failed_outcome() {
+        let error = ProviderError::SubProviderError(
+            Provider::PICNIC,
+            SubProviderError::LoginFailed("an expired session".to_string()),
+        );
+        let outcome = ProviderOutcome::failed(Provider::PICNIC, &error);
+
+        assert_eq!(outcome.status, OutcomeStatus::Failed);
+        assert_eq!(
+            outcome.message.as_deref(),
+            Some("Could not log in due to an expired session")
+        );
+        assert!(!outcome.is_ok());
+        assert!(ProviderOutcome::ok(Provider::PICNIC).is_ok());
+    }
+}
@@ -0,0 +1,37 @@
+use crate::models::{PriceInfo, ProductId, Provider, WggProduct, WggSearchProduct};
+use chrono::{DateTime, Utc};
+
+/// A single observation of the price of a product, as returned by a provider at a given time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceObservation {
+    pub provider: Provider,
+    pub product_id: ProductId,
+    pub price_info: PriceInfo,
+    /// The label of the sale active at the time of observation, if any.
+    pub sale_label: Option<String>,
+    pub observed_at: DateTime<Utc>,
+}
+
+impl From<&WggSearchProduct> for PriceObservation {
+    fn from(product: &WggSearchProduct) -> Self {
+        Self {
+            provider: product.provider,
+            product_id: product.id.clone(),
+            price_info: product.price_info.clone(),
+            sale_label: product.sale_information.as_ref().map(|sale| sale.label.clone()),
+            observed_at: Utc::now(),
+        }
+    }
+}
+
+impl From<&WggProduct> for PriceObservation {
+    fn from(product: &WggProduct) -> Self {
+        Self {
+            provider: product.provider,
+            product_id: product.id.clone(),
+            price_info: product.price_info.clone(),
+            sale_label: product.sale_information.as_ref().map(|sale| sale.label.clone()),
+            observed_at: Utc::now(),
+        }
+    }
+}
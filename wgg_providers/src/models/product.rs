@@ -1,5 +1,5 @@
 use std::borrow::Cow;
-use crate::models::{AllergyTags, IngredientInfo, ItemInfo, NutritionalInfo, PriceInfo, Provider, ProviderInfo, SaleInformation, TextType, UnavailableItem, UnitQuantity, WggDecorator};
+use crate::models::{AllergyTags, Gtin, IngredientInfo, ItemInfo, NutritionalInfo, PriceInfo, Provider, ProviderInfo, SaleInformation, TextType, UnavailableItem, UnitQuantity, WggDecorator};
 use serde::{Deserialize, Serialize};
 
 // ** Full Product **
@@ -17,6 +17,8 @@ pub struct WggProduct {
     pub price_info: PriceInfo,
     /// The amount of weight/liters/pieces this product represents.
     pub unit_quantity: UnitQuantity,
+    /// The barcode of this product, if the provider lists it.
+    pub gtin: Option<Gtin>,
     /// If this product is currently unavailable this will contain details explaining why.
     ///
     /// If this is `None` then the object is available
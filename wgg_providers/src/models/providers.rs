@@ -1,68 +1,237 @@
-use crate::providers::StaticProviderInfo;
-use crate::providers::{JumboBridge, PicnicBridge};
+use crate::models::CentPrice;
 use serde::{Deserialize, Serialize};
+use serde::{Deserializer, Serializer};
 use std::borrow::Cow;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 pub fn provider_info(provider: Provider) -> ProviderInfo {
-    ProviderInfo {
-        provider,
-        logo_url: provider.get_metadata().logo_url,
-    }
+    provider.as_provider_info()
 }
 
-#[derive(Serialize, Deserialize, async_graphql::Enum, Hash, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub enum Provider {
-    Picnic,
-    Jumbo,
-}
+/// Identifies a grocery store, such as `PICNIC`.
+///
+/// The built-in providers are available as associated constants, other providers can be supplied through a
+/// [ProviderRegistration](crate::ProviderRegistration).
+#[derive(Hash, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Provider(&'static str);
 
 impl Provider {
-    /// Get all relevant metadata for this provider
+    pub const PICNIC: Provider = Provider("PICNIC");
+    pub const JUMBO: Provider = Provider("JUMBO");
+    pub const ALBERT_HEIJN: Provider = Provider("ALBERT_HEIJN");
+
+    /// Create a provider with the given id.
+    ///
+    /// Ids should be `SCREAMING_SNAKE_CASE`, and unique across all providers.
+    pub const fn from_static(id: &'static str) -> Self {
+        Self(id)
+    }
+
+    /// The unique id of this provider, e.g `PICNIC`.
+    pub fn id(&self) -> &'static str {
+        self.0
+    }
+
+    /// Get all relevant metadata for this provider.
+    ///
+    /// Should the provider not be registered then placeholder metadata is returned.
     pub fn get_metadata(&self) -> ProviderMetadata {
-        match self {
-            Provider::Picnic => PicnicBridge::metadata(),
-            Provider::Jumbo => JumboBridge::metadata(),
-        }
+        crate::registry::provider_metadata(*self).unwrap_or_else(|| ProviderMetadata::unregistered(*self))
     }
 
-    /// Retrieve a ProviderInfo object which contains the logo url for the current provider.
+    /// Retrieve a ProviderInfo object which contains the name and logo url for the current provider.
     pub fn as_provider_info(&self) -> ProviderInfo {
+        let metadata = self.get_metadata();
+
         ProviderInfo {
             provider: *self,
-            logo_url: self.get_metadata().logo_url,
+            name: metadata.display_name,
+            logo_url: metadata.logo_url,
         }
     }
 }
 
+impl Debug for Provider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Display for Provider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
 impl FromStr for Provider {
     type Err = anyhow::Error;
 
+    /// Parse the id of a *registered* provider.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "PICNIC" => Ok(Provider::Picnic),
-            "JUMBO" => Ok(Provider::Jumbo),
-            _ => anyhow::bail!("Failed to parse provider {}", s),
-        }
+        crate::registry::find_registered(s).ok_or_else(|| anyhow::anyhow!("Failed to parse provider {}", s))
+    }
+}
+
+impl Serialize for Provider {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Provider {
+    /// Like [FromStr] only *registered* providers are accepted. Any [ProviderRegistration](crate::ProviderRegistration)
+    /// therefore has to be built into a [WggProvider](crate::WggProvider) before persisted data containing its id is
+    /// loaded.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = Cow::<'de, str>::deserialize(deserializer)?;
+        // Before providers were registered they were serialized by their (enum) variant name.
+        let provider = match id.as_ref() {
+            "Picnic" => Provider::PICNIC,
+            "Jumbo" => Provider::JUMBO,
+            "AlbertHeijn" => Provider::ALBERT_HEIJN,
+            id => crate::registry::find_registered(id)
+                .ok_or_else(|| serde::de::Error::custom(format!("Unknown provider {id}")))?,
+        };
+
+        Ok(provider)
+    }
+}
+
+/// The id of a registered provider, such as `PICNIC`.
+///
+/// For backwards compatibility the id can also be passed as an enum value.
+#[async_graphql::Scalar(name = "Provider")]
+impl async_graphql::ScalarType for Provider {
+    fn parse(value: async_graphql::Value) -> async_graphql::InputValueResult<Self> {
+        let id = match &value {
+            async_graphql::Value::String(id) => id.as_str(),
+            async_graphql::Value::Enum(id) => id.as_str(),
+            _ => return Err(async_graphql::InputValueError::expected_type(value)),
+        };
+
+        id.parse().map_err(async_graphql::InputValueError::custom)
+    }
+
+    fn to_value(&self) -> async_graphql::Value {
+        async_graphql::Value::String(self.0.to_string())
     }
 }
 
 #[derive(Serialize, Deserialize, async_graphql::SimpleObject, Hash, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[graphql(complex)]
 pub struct ProviderInfo {
     /// The grocery store which provided this item.
     pub provider: Provider,
+    /// The human readable name of the grocery store.
+    pub name: Cow<'static, str>,
     /// The SVG logo of the grocery store
     pub logo_url: Cow<'static, str>,
 }
 
+#[async_graphql::ComplexObject]
+impl ProviderInfo {
+    /// All static metadata of the grocery store, such as its fees.
+    async fn metadata(&self) -> ProviderMetadata {
+        self.provider.get_metadata()
+    }
+}
+
 #[derive(Serialize, Deserialize, async_graphql::SimpleObject, Hash, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ProviderMetadata {
+    /// The human readable name of the grocery store.
+    pub display_name: Cow<'static, str>,
     /// The SVG logo of the grocery store
     pub logo_url: Cow<'static, str>,
     /// The strategy for multi-product sale resolution.
     pub sale_strategy: SaleResolutionStrategy,
     /// Whether the provider supports managing cart contents to whatever backend it uses.
     pub supports_cart: bool,
+    /// Whether the provider supports selecting delivery slots and following the checkout of the remote cart.
+    pub supports_delivery: bool,
+    /// Whether the provider supports browsing its catalogue by category.
+    pub supports_categories: bool,
+    /// The minimum value (after discounts, excluding deposits) a basket needs to have before an order can be placed.
+    pub minimum_order_value: CentPrice,
+    /// The delivery fee charged for an order, depending on its value.
+    pub delivery_fee: FeeSchedule,
+    /// Any additional service (e.g, picking or packaging) fees charged for an order, depending on its value.
+    pub service_fee: FeeSchedule,
+    /// Whether the provider charges deposits (statiegeld) on top of the product price.
+    ///
+    /// Deposits are returned upon returning the bottles/crates, and are therefore tallied separately.
+    pub charges_deposit: bool,
+}
+
+impl ProviderMetadata {
+    /// Placeholder metadata for a provider which isn't (yet) registered.
+    pub fn unregistered(provider: Provider) -> Self {
+        Self {
+            display_name: provider.id().into(),
+            logo_url: Cow::Borrowed(""),
+            sale_strategy: SaleResolutionStrategy::Opportunistic,
+            supports_cart: false,
+            supports_delivery: false,
+            supports_categories: false,
+            minimum_order_value: 0,
+            delivery_fee: FeeSchedule::free(),
+            service_fee: FeeSchedule::free(),
+            charges_deposit: false,
+        }
+    }
+}
+
+/// A tiered fee, where the fee is determined by the value of the order.
+#[derive(
+    Serialize, Deserialize, async_graphql::SimpleObject, Hash, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub struct FeeSchedule {
+    /// All fee tiers, sorted by [FeeTier::from_order_value] in ascending order.
+    ///
+    /// If the order value is below the first tier no fee is charged.
+    pub tiers: Vec<FeeTier>,
+}
+
+#[derive(
+    Serialize, Deserialize, async_graphql::SimpleObject, Hash, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub struct FeeTier {
+    /// The (inclusive) order value from which this tier applies.
+    pub from_order_value: CentPrice,
+    pub fee: CentPrice,
+}
+
+impl FeeSchedule {
+    /// A schedule which never charges a fee.
+    pub fn free() -> Self {
+        Self::default()
+    }
+
+    /// A schedule which always charges the same `fee`, regardless of order value.
+    pub fn flat(fee: CentPrice) -> Self {
+        Self::tiered([(0, fee)])
+    }
+
+    /// Create a schedule out of `(from_order_value, fee)` pairs.
+    pub fn tiered(tiers: impl IntoIterator<Item = (CentPrice, CentPrice)>) -> Self {
+        let mut tiers: Vec<_> = tiers
+            .into_iter()
+            .map(|(from_order_value, fee)| FeeTier { from_order_value, fee })
+            .collect();
+        tiers.sort_by_key(|tier| tier.from_order_value);
+
+        Self { tiers }
+    }
+
+    /// Calculate the fee for an order with the given value (after discounts).
+    pub fn fee_for(&self, order_value: CentPrice) -> CentPrice {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.from_order_value <= order_value)
+            .map(|tier| tier.fee)
+            .unwrap_or_default()
+    }
 }
 
 /// When resolving multi-product (think `1 + 1 free`, `2nd half off`, etc) the strategy determines how products will
@@ -77,3 +246,24 @@ pub enum SaleResolutionStrategy {
     /// When resolving a multi-product sale the provider will ensure the smallest savings for the customer.
     Pessimistic,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FeeSchedule;
+
+    #[test]
+    fn test_fee_for() {
+        assert_eq!(FeeSchedule::free().fee_for(0), 0);
+        assert_eq!(FeeSchedule::flat(495).fee_for(0), 495);
+        assert_eq!(FeeSchedule::flat(495).fee_for(100_000), 495);
+
+        // Tiers are sorted, and the lower bound of every tier is inclusive.
+        let schedule = FeeSchedule::tiered([(7500, 395), (2000, 695), (15000, 0)]);
+        assert_eq!(schedule.fee_for(1999), 0);
+        assert_eq!(schedule.fee_for(2000), 695);
+        assert_eq!(schedule.fee_for(7499), 695);
+        assert_eq!(schedule.fee_for(7500), 395);
+        assert_eq!(schedule.fee_for(14999), 395);
+        assert_eq!(schedule.fee_for(15000), 0);
+    }
+}
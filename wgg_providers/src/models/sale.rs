@@ -129,7 +129,7 @@ pub struct SaleInformation {
 }
 
 pub mod sale_types {
-    use crate::models::CentPrice;
+    use crate::models::{CentPrice, Unit, UnitQuantity};
     use serde::{Deserialize, Serialize};
     use std::num::NonZeroU16;
 
@@ -141,6 +141,28 @@ pub mod sale_types {
         NumForPrice(NumForPrice),
         NumEuroOff(NumEuroOff),
         NumEuroPrice(NumEuroPrice),
+        NumForPercentOff(NumForPercentOff),
+        StackingDiscount(StackingDiscount),
+        PricePerUnit(PricePerUnit),
+        MinimumQuantity(MinimumQuantity),
+    }
+
+    impl SaleType {
+        /// The name of the kind of this sale, ignoring its parameters. For example `NumPlusNumFree`.
+        pub fn kind(&self) -> &'static str {
+            match self {
+                SaleType::NumPlusNumFree(_) => "NumPlusNumFree",
+                SaleType::NumPercentOff(_) => "NumPercentOff",
+                SaleType::NumthPercentOff(_) => "NumthPercentOff",
+                SaleType::NumForPrice(_) => "NumForPrice",
+                SaleType::NumEuroOff(_) => "NumEuroOff",
+                SaleType::NumEuroPrice(_) => "NumEuroPrice",
+                SaleType::NumForPercentOff(_) => "NumForPercentOff",
+                SaleType::StackingDiscount(_) => "StackingDiscount",
+                SaleType::PricePerUnit(_) => "PricePerUnit",
+                SaleType::MinimumQuantity(_) => "MinimumQuantity",
+            }
+        }
     }
 
     /// Follows from the following kinds of sales:
@@ -210,4 +232,95 @@ pub mod sale_types {
     pub struct NumEuroPrice {
         pub price: CentPrice,
     }
+
+    /// Follows from the following kinds of sales:
+    /// * `2 voor 25% korting` - 2 required, 25% off all of them
+    #[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, PartialOrd)]
+    pub struct NumForPercentOff {
+        pub required: NonZeroU16,
+        /// The percent reduction. Guaranteed to be `> 0 && <= 100`.
+        percent_off: NonZeroU16,
+    }
+
+    impl NumForPercentOff {
+        pub fn new(required: NonZeroU16, percent_off: impl TryInto<NonZeroU16>) -> Option<Self> {
+            let percent_off = percent_off.try_into().ok()?;
+            if percent_off.get() > 100 {
+                None
+            } else {
+                Self { required, percent_off }.into()
+            }
+        }
+
+        /// Get the percent off.
+        ///
+        /// Guaranteed to be `> 0 && <= 100`.
+        pub fn get_percent_off(&self) -> NonZeroU16 {
+            self.percent_off
+        }
+    }
+
+    /// Follows from the following kinds of sales:
+    /// * `stapelkorting` - No known tiers
+    /// * `stapelkorting 2 stuks 10% 3 stuks 20%` - 10% off everything from 2 items, 20% off everything from 3 items
+    #[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, PartialOrd)]
+    pub struct StackingDiscount {
+        /// All discount tiers, sorted by [StackingTier::required] in ascending order.
+        ///
+        /// Can be empty if the label didn't mention the tiers.
+        pub tiers: Vec<StackingTier>,
+    }
+
+    impl StackingDiscount {
+        /// Get the tier which applies to the given total quantity, if any.
+        pub fn tier_for(&self, quantity: u32) -> Option<&StackingTier> {
+            self.tiers
+                .iter()
+                .rev()
+                .find(|tier| tier.required.get() as u32 <= quantity)
+        }
+    }
+
+    #[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, PartialOrd)]
+    pub struct StackingTier {
+        pub required: NonZeroU16,
+        /// The percent reduction. Guaranteed to be `> 0 && <= 100`.
+        pub percent_off: NonZeroU16,
+    }
+
+    /// Follows from the following kinds of sales:
+    /// * `per kilo €9,99` - 999 centprice per kilogram
+    #[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, PartialOrd)]
+    pub struct PricePerUnit {
+        pub unit: Unit,
+        pub price: CentPrice,
+    }
+
+    impl PricePerUnit {
+        /// Calculate the sale price of a product with the given `quantity`.
+        ///
+        /// Returns `None` if the unit of the product doesn't match the unit of the sale.
+        pub fn price_for(&self, quantity: &UnitQuantity) -> Option<CentPrice> {
+            let (sale_amount, sale_unit) = normalise(1.0, self.unit);
+            let (amount, unit) = normalise(quantity.amount, quantity.unit);
+
+            (unit == sale_unit).then(|| (self.price as f64 * amount / sale_amount).round() as CentPrice)
+        }
+    }
+
+    /// Convert the given amount to kilograms or liters where possible.
+    fn normalise(amount: f64, unit: Unit) -> (f64, Unit) {
+        match unit {
+            Unit::Gram => (amount / 1000., Unit::KiloGram),
+            Unit::MilliLiter => (amount / 1000., Unit::Liter),
+            _ => (amount, unit),
+        }
+    }
+
+    /// Follows from the following kinds of sales:
+    /// * `vanaf 2 stuks` - The sale price only applies when at least 2 are bought
+    #[derive(Serialize, Deserialize, async_graphql::SimpleObject, Clone, Debug, PartialEq, PartialOrd)]
+    pub struct MinimumQuantity {
+        pub required: NonZeroU16,
+    }
 }
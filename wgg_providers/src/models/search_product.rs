@@ -1,5 +1,5 @@
 use crate::models::{
-    PriceInfo, Provider, ProviderInfo, SaleInformation, UnavailableItem, UnitQuantity, WggDecorator, WggProduct,
+    Gtin, PriceInfo, Provider, ProviderInfo, SaleInformation, UnavailableItem, UnitQuantity, WggDecorator, WggProduct,
 };
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -16,6 +16,10 @@ pub struct WggSearchProduct {
     pub price_info: PriceInfo,
     /// The amount of weight/liters/pieces this product represents.
     pub unit_quantity: UnitQuantity,
+    /// The barcode of this product, if the provider lists it.
+    ///
+    /// Not every provider includes barcodes in its search results, see the full product instead.
+    pub gtin: Option<Gtin>,
     /// If this product is currently unavailable this will contain details explaining why.
     ///
     /// If this is `None` then the object is available
@@ -52,6 +56,7 @@ impl From<WggProduct> for WggSearchProduct {
             name: product.name,
             price_info: product.price_info,
             unit_quantity: product.unit_quantity,
+            gtin: product.gtin,
             unavailable_details: product.unavailable_details,
             image_url: product.image_urls.into_iter().next(),
             decorators: product.decorators,
@@ -1,4 +1,8 @@
+use crate::models::{Provider, ProviderOutcome};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub struct OffsetPagination<T> {
@@ -6,3 +10,127 @@ pub struct OffsetPagination<T> {
     pub total_items: usize,
     pub offset: u32,
 }
+
+/// A page of results gathered from multiple providers at the same time.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct MultiProviderPagination<T> {
+    pub items: Vec<T>,
+    /// The sum of all providers' total items.
+    pub total_items: usize,
+    /// The cursor for the next page, `None` if all providers are exhausted.
+    pub next: Option<MultiProviderCursor>,
+    /// The outcome of every provider queried for this page.
+    pub outcomes: Vec<ProviderOutcome>,
+}
+
+/// The position within the results of every provider, used to paginate over multiple providers at the same time.
+///
+/// Providers without an offset start from the beginning.
+/// The cursor is encoded as a comma separated list of `PROVIDER:OFFSET` entries, where exhausted providers are marked
+/// with a trailing `!`, e.g. `JUMBO:24,PICNIC:31!`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct MultiProviderCursor {
+    offsets: BTreeMap<Provider, u32>,
+    exhausted: BTreeSet<Provider>,
+}
+
+impl MultiProviderCursor {
+    /// The offset from which the results of the given provider should continue.
+    pub fn offset(&self, provider: Provider) -> u32 {
+        self.offsets.get(&provider).copied().unwrap_or_default()
+    }
+
+    /// Whether all results of the given provider have been returned.
+    pub fn is_exhausted(&self, provider: Provider) -> bool {
+        self.exhausted.contains(&provider)
+    }
+
+    /// Continue the results of `provider` from `offset`.
+    ///
+    /// The provider is marked as exhausted if `offset` is past its `total_items`.
+    pub fn advance(&mut self, provider: Provider, offset: u32, total_items: usize) {
+        self.offsets.insert(provider, offset);
+
+        if offset as usize >= total_items {
+            self.exhausted.insert(provider);
+        }
+    }
+
+    /// The amount of items returned so far for all exhausted providers.
+    pub(crate) fn exhausted_items(&self) -> usize {
+        self.exhausted
+            .iter()
+            .map(|provider| self.offset(*provider) as usize)
+            .sum()
+    }
+}
+
+impl Display for MultiProviderCursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, (provider, offset)) in self.offsets.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+
+            write!(f, "{provider}:{offset}")?;
+
+            if self.is_exhausted(*provider) {
+                f.write_str("!")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for MultiProviderCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut result = Self::default();
+
+        for entry in s.split(',').filter(|entry| !entry.is_empty()) {
+            let (entry, exhausted) = match entry.strip_suffix('!') {
+                Some(entry) => (entry, true),
+                None => (entry, false),
+            };
+            let (provider, offset) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Invalid cursor entry {}", entry))?;
+            let provider: Provider = provider.parse()?;
+
+            result.offsets.insert(provider, offset.parse()?);
+
+            if exhausted {
+                result.exhausted.insert(provider);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiProviderCursor;
+    use crate::models::Provider;
+
+    #[test]
+    fn test_multi_provider_cursor() {
+        let mut cursor = MultiProviderCursor::default();
+        cursor.advance(Provider::JUMBO, 24, 100);
+        cursor.advance(Provider::PICNIC, 31, 31);
+
+        assert_eq!(cursor.offset(Provider::ALBERT_HEIJN), 0);
+        assert!(cursor.is_exhausted(Provider::PICNIC));
+        assert!(!cursor.is_exhausted(Provider::JUMBO));
+        assert_eq!(cursor.exhausted_items(), 31);
+
+        let encoded = cursor.to_string();
+        assert_eq!(encoded, "JUMBO:24,PICNIC:31!");
+        assert_eq!(encoded.parse::<MultiProviderCursor>().unwrap(), cursor);
+
+        assert!("JUMBO".parse::<MultiProviderCursor>().is_err());
+        assert!("JUMBO:abc".parse::<MultiProviderCursor>().is_err());
+    }
+}
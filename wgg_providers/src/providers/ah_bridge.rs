@@ -0,0 +1,586 @@
+use crate::error::Result;
+use crate::models::{
+    AllergyTags, AllergyType, CentPrice, Description, FeeSchedule, IngredientInfo, ItemInfo, ItemType, NutritionalInfo,
+    NutritionalItem, PriceInfo, Provider, ProviderMetadata, SaleInformation, SaleResolutionStrategy, SaleValidity,
+    SubNutritionalItem, TextType, UnavailableItem, UnavailableReason, Unit, UnitPrice, UnitQuantity, WggAutocomplete,
+    WggProduct, WggSaleCategory, WggSaleGroupComplete, WggSaleGroupLimited, WggSaleItem, WggSearchProduct,
+};
+use crate::pagination::OffsetPagination;
+use crate::providers::common_bridge::{
+    derive_unit_price, get_guessed_sale_validity, parse_int_fract_price, parse_unit_component,
+};
+use crate::providers::{common_bridge, ProviderInfo, StaticProviderInfo};
+use crate::sale_rules::CompiledSaleRules;
+use crate::{lazy_re, ProviderError};
+use chrono::{NaiveDate, Utc};
+use regex::Regex;
+use std::sync::Arc;
+use wgg_ah::models::{OrderAvailabilityStatus, PromotionType};
+use wgg_ah::{BaseAhApi, BaseApi};
+
+/// The amount of items requested per search page.
+const AH_PAGE_SIZE: u32 = 30;
+
+pub(crate) struct AhBridge {
+    pub api: BaseAhApi,
+    sale_rules: Arc<CompiledSaleRules>,
+}
+
+impl AhBridge {
+    pub fn new(api: BaseAhApi, sale_rules: Arc<CompiledSaleRules>) -> Self {
+        AhBridge { api, sale_rules }
+    }
+
+    /// Return the date which should be used for querying the current bonus period.
+    async fn current_bonus_date(&self) -> Result<NaiveDate> {
+        let today = Utc::now().date_naive();
+        let metadata = self.api.bonus_metadata().await?;
+
+        let period = metadata
+            .periods
+            .iter()
+            .find(|period| period.bonus_start_date <= today && today <= period.bonus_end_date)
+            .or_else(|| metadata.periods.first())
+            .ok_or(ProviderError::NothingFound)?;
+
+        Ok(today.clamp(period.bonus_start_date, period.bonus_end_date))
+    }
+}
+
+impl StaticProviderInfo for AhBridge {
+    fn provider() -> Provider {
+        Provider::ALBERT_HEIJN
+    }
+
+    fn metadata() -> ProviderMetadata {
+        ProviderMetadata {
+            display_name: "Albert Heijn".into(),
+            logo_url: "https://upload.wikimedia.org/wikipedia/commons/e/eb/Albert_Heijn_Logo.svg".into(),
+            sale_strategy: SaleResolutionStrategy::Pessimistic,
+            supports_cart: false,
+            supports_delivery: false,
+            supports_categories: false,
+            minimum_order_value: 5000,
+            // AH's delivery fee depends on the chosen time slot, this is the fee of an average slot.
+            delivery_fee: FeeSchedule::flat(695),
+            service_fee: FeeSchedule::free(),
+            charges_deposit: true,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProviderInfo for AhBridge {
+    fn provider(&self) -> Provider {
+        <Self as StaticProviderInfo>::provider()
+    }
+
+    fn metadata(&self) -> ProviderMetadata {
+        <Self as StaticProviderInfo>::metadata()
+    }
+
+    #[tracing::instrument(name = "ah_autocomplete", level="debug", skip_all, fields(query = query))]
+    async fn autocomplete(&self, query: &str) -> Result<Vec<WggAutocomplete>> {
+        let response = self.api.autocomplete(query, Some(10)).await?;
+
+        Ok(response
+            .suggestions
+            .into_iter()
+            .map(|sug| WggAutocomplete { name: sug.value })
+            .collect())
+    }
+
+    #[tracing::instrument(name = "ah_search", level = "debug", skip(self))]
+    async fn search(&self, query: &str, offset: Option<u32>) -> Result<OffsetPagination<WggSearchProduct>> {
+        // AH uses page based pagination, so we'll have to translate our offset.
+        // Any offset within the page is handled by skipping the items before it.
+        let offset = offset.unwrap_or_default();
+        let page = offset / AH_PAGE_SIZE;
+        let search_results = self.api.search(query, Some(page), Some(AH_PAGE_SIZE)).await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("AH Search: {:#?}", search_results);
+
+        Ok(OffsetPagination {
+            items: search_results
+                .products
+                .into_iter()
+                .skip((offset % AH_PAGE_SIZE) as usize)
+                .map(|product| parse_ah_item_to_search_item(product, &self.sale_rules))
+                .collect(),
+            total_items: search_results.page.total_elements as usize,
+            offset,
+        })
+    }
+
+    async fn product(&self, product_id: &str) -> Result<WggProduct> {
+        let result = self.api.product(&product_id.parse()?).await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("AH Product: {:#?}", result);
+
+        Ok(parse_ah_product_to_crate_product(result, &self.sale_rules))
+    }
+
+    async fn promotions(&self) -> Result<Vec<WggSaleCategory>> {
+        let date = self.current_bonus_date().await?;
+        let result = self.api.bonus_section(date, PromotionType::National).await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("AH Promotions: {:#?}", result);
+
+        // AH doesn't have a separate category endpoint, instead every group/product carries its own category.
+        // We preserve the order in which AH returns the categories.
+        let mut categories: Vec<(String, Vec<WggSaleItem>)> = Vec::new();
+
+        for group_or_product in result.bonus_group_or_products {
+            let (category, item) = match (group_or_product.bonus_group, group_or_product.product) {
+                (Some(group), _) => {
+                    let category = group.category.clone();
+                    let Some(group) = parse_ah_bonus_group(group, &self.sale_rules) else {
+                        continue;
+                    };
+                    (category, WggSaleItem::Group(group))
+                }
+                (None, Some(product)) => {
+                    let category = product.main_category.clone();
+                    let product = parse_ah_item_to_search_item(product, &self.sale_rules);
+                    (category, WggSaleItem::Product(product))
+                }
+                (None, None) => continue,
+            };
+
+            let category = category.unwrap_or_else(|| "Overig".to_string());
+
+            match categories.iter_mut().find(|(name, _)| *name == category) {
+                Some((_, items)) => items.push(item),
+                None => categories.push((category, vec![item])),
+            }
+        }
+
+        Ok(categories
+            .into_iter()
+            .map(|(name, items)| WggSaleCategory {
+                id: None,
+                name,
+                items,
+                image_urls: Vec::new(),
+                complete: true,
+                provider: Provider::ALBERT_HEIJN,
+            })
+            .collect())
+    }
+
+    async fn promotions_sublist(&self, sublist_id: &str) -> Result<WggSaleGroupComplete> {
+        let date = self.current_bonus_date().await?;
+        let group = self.api.bonus_group(&sublist_id.parse()?, date).await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("AH Bonus Group: {:#?}", group);
+
+        let products = group.products.clone();
+        let promo = parse_ah_bonus_group(group, &self.sale_rules)
+            .ok_or_else(|| anyhow::anyhow!("Provided bonus group ({sublist_id}) has no sale information"))?;
+
+        let items = products
+            .into_iter()
+            .map(|product| parse_ah_item_to_search_item(product, &self.sale_rules))
+            .map(|mut item| {
+                // Products within a bonus group don't always carry the group's sale info themselves.
+                if item.sale_information.is_none() {
+                    item.sale_information = Some(promo.sale_info.clone());
+                }
+
+                item
+            })
+            .collect();
+
+        Ok(WggSaleGroupComplete {
+            id: promo.id,
+            name: promo.name,
+            image_urls: promo.image_urls,
+            items,
+            sale_info: promo.sale_info,
+            sale_description: promo.sale_description,
+            provider: promo.provider,
+        })
+    }
+}
+
+fn parse_ah_bonus_group(group: wgg_ah::models::BonusGroup, rules: &CompiledSaleRules) -> Option<WggSaleGroupLimited> {
+    let sale_info = parse_bonus_to_sale_info(
+        Some(group.id.to_string()),
+        group.discount_description.as_deref(),
+        group
+            .discount_labels
+            .iter()
+            .map(|label| label.default_description.as_str()),
+        SaleValidity {
+            valid_from: date_to_validity(group.bonus_start_date, false),
+            valid_until: date_to_validity(group.bonus_end_date, true),
+        },
+        rules,
+    )?;
+
+    Some(WggSaleGroupLimited {
+        id: group.id.into(),
+        name: group.segment_description,
+        image_urls: group.images.into_iter().map(|img| img.url).collect(),
+        // The products embedded in the bonus section are only a preview of the group, leaving them out ensures the full
+        // group is retrieved when resolving sales.
+        items: Vec::new(),
+        sale_info,
+        sale_description: None,
+        provider: Provider::ALBERT_HEIJN,
+    })
+}
+
+fn parse_ah_product_to_crate_product(
+    response: wgg_ah::models::ProductDetailResponse,
+    rules: &CompiledSaleRules,
+) -> WggProduct {
+    let search_item = parse_ah_item_to_search_item(response.product_card.clone(), rules);
+    let trade_item = response.trade_item.unwrap_or_default();
+
+    let mut result = WggProduct {
+        id: search_item.id,
+        name: search_item.name,
+        description: Description {
+            text: trade_item
+                .description
+                .map(|desc| desc.descriptions.join("\n"))
+                .unwrap_or_default(),
+            text_type: TextType::PlainText,
+        },
+        price_info: search_item.price_info,
+        unit_quantity: search_item.unit_quantity,
+        gtin: search_item.gtin,
+        unavailable_details: search_item.unavailable_details,
+        image_urls: select_image_urls(&response.product_card.images),
+        // Will be parsed
+        ingredients: vec![],
+        // Will be parsed
+        nutritional: None,
+        // Will be parsed
+        allergy_info: vec![],
+        // Will be parsed
+        additional_items: vec![],
+        decorators: search_item.decorators,
+        sale_information: search_item.sale_information,
+        provider: Provider::ALBERT_HEIJN,
+    };
+
+    // Ingredients
+    if let Some(ingredients) = trade_item.ingredients {
+        if let Some(statement) = ingredients.statement {
+            result.ingredients = statement
+                .split(',')
+                .map(|item| item.trim().trim_end_matches('.'))
+                .filter(|item| !item.is_empty())
+                .map(|name| IngredientInfo { name: name.to_string() })
+                .collect();
+        }
+
+        if let Some(allergens) = ingredients.allergens {
+            let contains = allergens.contains.into_iter().map(|name| AllergyTags {
+                name,
+                contains: AllergyType::Contains,
+            });
+            let may_contain = allergens.may_contain.into_iter().map(|name| AllergyTags {
+                name,
+                contains: AllergyType::MayContain,
+            });
+
+            result.allergy_info = contains.chain(may_contain).collect();
+        }
+    }
+
+    // Nutritional
+    if let Some(primary_nutrition) = trade_item.nutritions.into_iter().next() {
+        result.nutritional = NutritionalInfo {
+            info_unit: primary_nutrition
+                .basis_quantity
+                .unwrap_or_else(|| "per 100g".to_string()),
+            items: primary_nutrition
+                .nutrients
+                .into_iter()
+                .map(|nutrient| NutritionalItem {
+                    name: nutrient.name,
+                    value: nutrient.value,
+                    sub_values: nutrient
+                        .sub_nutrients
+                        .into_iter()
+                        .map(|sub| SubNutritionalItem {
+                            name: sub.name,
+                            value: sub.value,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+        .into()
+    }
+
+    // Additional items
+    if let Some(usage) = trade_item.usage {
+        let usage_items = [
+            (ItemType::PreparationAdvice, usage.preparation_instructions),
+            (ItemType::StorageAdvice, usage.storage_instructions),
+            (ItemType::SafetyWarning, usage.safety_warning),
+        ];
+
+        for (item_type, text) in usage_items {
+            if let Some(text) = text {
+                result.additional_items.push(ItemInfo {
+                    item_type,
+                    text,
+                    text_type: TextType::PlainText,
+                })
+            }
+        }
+    }
+
+    if let Some(origin) = trade_item.origin.and_then(|origin| origin.country_of_origin) {
+        result.additional_items.push(ItemInfo {
+            item_type: ItemType::CountryOfOrigin,
+            text: origin,
+            text_type: TextType::PlainText,
+        })
+    }
+
+    result
+}
+
+/// Parse a partial AH [wgg_ah::models::PartialProduct] to our normalised [WggSearchProduct]
+fn parse_ah_item_to_search_item(
+    article: wgg_ah::models::PartialProduct,
+    rules: &CompiledSaleRules,
+) -> WggSearchProduct {
+    let original_price = article
+        .price_before_bonus
+        .or(article.current_price)
+        .map(parse_ah_price)
+        .unwrap_or_default();
+    let display_price = article.current_price.map(parse_ah_price).unwrap_or(original_price);
+
+    let mut result = WggSearchProduct {
+        id: article.webshop_id.into(),
+        name: article.title,
+        price_info: PriceInfo {
+            display_price,
+            original_price,
+            unit_price: None,
+            // AH doesn't expose deposits in their API.
+            deposit: None,
+        },
+        unit_quantity: article
+            .sales_unit_size
+            .as_deref()
+            .and_then(parse_sales_unit_size)
+            .unwrap_or_default(),
+        // AH doesn't expose barcodes in their API.
+        gtin: None,
+        unavailable_details: None,
+        image_url: select_image_urls(&article.images).into_iter().next(),
+        decorators: Vec::new(),
+        sale_information: None,
+        provider: Provider::ALBERT_HEIJN,
+    };
+
+    // Unit Pricing
+    result.price_info.unit_price = article
+        .unit_price_description
+        .as_deref()
+        .and_then(parse_unit_price_description)
+        .or_else(|| derive_unit_price(&result.unit_quantity, result.price_info.display_price));
+
+    // Promotions
+    if article.is_bonus {
+        let sale_validity = match (article.bonus_start_date, article.bonus_end_date) {
+            (Some(start), Some(end)) => SaleValidity {
+                valid_from: date_to_validity(start, false),
+                valid_until: date_to_validity(end, true),
+            },
+            _ => get_guessed_sale_validity(Utc::now()),
+        };
+
+        result.sale_information = parse_bonus_to_sale_info(
+            article.bonus_segment_id.map(String::from),
+            article.bonus_mechanism.as_deref(),
+            article
+                .discount_labels
+                .iter()
+                .map(|label| label.default_description.as_str()),
+            sale_validity,
+            rules,
+        );
+    }
+
+    // Availability
+    match article.order_availability_status {
+        Some(OrderAvailabilityStatus::InAssortment) | None => {}
+        Some(status) => {
+            result.unavailable_details = UnavailableItem {
+                reason: match status {
+                    OrderAvailabilityStatus::OutOfStock => UnavailableReason::TemporarilyUnavailable,
+                    OrderAvailabilityStatus::NotAvailable => UnavailableReason::OutOfAssortment,
+                    _ => UnavailableReason::Unknown,
+                },
+                explanation_short: None,
+                explanation_long: None,
+                replacements: Vec::new(),
+            }
+            .into();
+        }
+    }
+
+    result
+}
+
+/// Parse the AH bonus information into a [SaleInformation].
+///
+/// # Arguments
+/// * `mechanism` - The primary sale label, such as `2e halve prijs`.
+/// * `labels` - Any additional labels, which will be used for parsing the [crate::models::sale_types::SaleType] if
+///   the `mechanism` couldn't be parsed.
+fn parse_bonus_to_sale_info<'a>(
+    promotion_id: Option<String>,
+    mechanism: Option<&'a str>,
+    labels: impl Iterator<Item = &'a str>,
+    sale_validity: SaleValidity,
+    rules: &CompiledSaleRules,
+) -> Option<SaleInformation> {
+    let labels: Vec<&str> = mechanism.into_iter().chain(labels).collect();
+    let label = *labels.first()?;
+    let sale_type = labels.iter().find_map(|label| rules.parse(label));
+
+    Some(SaleInformation {
+        id: promotion_id,
+        label: label.to_string(),
+        additional_label: labels.iter().filter(|l| **l != label).map(|l| l.to_string()).collect(),
+        sale_validity,
+        sale_type,
+    })
+}
+
+/// Convert an AH euro price (`2.49`) to a [CentPrice] (`249`).
+fn parse_ah_price(price: f64) -> CentPrice {
+    (price * 100.).round() as CentPrice
+}
+
+/// Convert an AH bonus date to a [chrono::DateTime].
+///
+/// AH bonus dates are inclusive, so the `end_of_day` should be set for the final day of a bonus.
+fn date_to_validity(date: NaiveDate, end_of_day: bool) -> chrono::DateTime<Utc> {
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+
+    time.unwrap_or_default().and_utc()
+}
+
+/// Select the image urls, sorted from largest to smallest.
+fn select_image_urls(images: &[wgg_ah::models::ProductImage]) -> Vec<String> {
+    let mut images = images.iter().collect::<Vec<_>>();
+    images.sort_by_key(|img| std::cmp::Reverse(img.width));
+
+    images.into_iter().map(|img| img.url.clone()).collect()
+}
+
+/// Parse the AH `salesUnitSize`, which mostly follows the usual quantity format, but is occasionally approximate.
+///
+/// Format expected: `500 g`, `ca. 1 kg`, or `per stuk`
+fn parse_sales_unit_size(unit_size: &str) -> Option<UnitQuantity> {
+    let unit_size = unit_size.trim_start_matches("ca.").trim();
+
+    if unit_size == "per stuk" {
+        Some(UnitQuantity {
+            unit: Unit::Piece,
+            amount: 1.,
+        })
+    } else {
+        common_bridge::parse_quantity(unit_size)
+    }
+}
+
+/// Parse the AH `unitPriceDescription` element.
+///
+/// Format expected: `prijs per kg €4.38`
+fn parse_unit_price_description(description: &str) -> Option<UnitPrice> {
+    lazy_re!(UNIT_PRICE_RX, r"per (\w+) €\s*(\d+)(?:[.,](\d+))?");
+
+    let capture = UNIT_PRICE_RX.captures(description)?;
+    let unit = parse_unit_component(capture.get(1)?.as_str())?;
+    let price = parse_int_fract_price(
+        capture.get(2)?.as_str().parse().ok()?,
+        capture.get(3).and_then(|frac| frac.as_str().parse().ok()).unwrap_or(0),
+    );
+
+    Some(UnitPrice { unit, price })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{Unit, UnitPrice, UnitQuantity};
+    use crate::providers::ah_bridge::{parse_ah_price, parse_sales_unit_size, parse_unit_price_description};
+
+    #[test]
+    pub fn test_parse_unit_price_description() {
+        let examples = vec!["prijs per kg €4.38", "prijs per liter €1,09", "prijs per stuk €0.50"];
+        let expected = vec![
+            UnitPrice {
+                unit: Unit::KiloGram,
+                price: 438,
+            },
+            UnitPrice {
+                unit: Unit::Liter,
+                price: 109,
+            },
+            UnitPrice {
+                unit: Unit::Piece,
+                price: 50,
+            },
+        ];
+
+        assert_eq!(
+            examples
+                .into_iter()
+                .flat_map(parse_unit_price_description)
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    pub fn test_parse_sales_unit_size() {
+        let examples = vec!["500 g", "ca. 1 kg", "per stuk"];
+        let expected = vec![
+            UnitQuantity {
+                unit: Unit::Gram,
+                amount: 500.,
+            },
+            UnitQuantity {
+                unit: Unit::KiloGram,
+                amount: 1.,
+            },
+            UnitQuantity {
+                unit: Unit::Piece,
+                amount: 1.,
+            },
+        ];
+
+        assert_eq!(
+            examples.into_iter().flat_map(parse_sales_unit_size).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    pub fn test_parse_ah_price() {
+        assert_eq!(parse_ah_price(2.49), 249);
+        assert_eq!(parse_ah_price(0.1), 10);
+        assert_eq!(parse_ah_price(12.), 1200);
+    }
+}
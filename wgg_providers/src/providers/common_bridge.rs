@@ -3,9 +3,7 @@ use std::num::NonZeroU16;
 use chrono::{DateTime, Datelike, Utc, Weekday};
 use regex::{Regex, RegexBuilder, RegexSet, RegexSetBuilder};
 
-use crate::models::sale_types::{
-    NumEuroOff, NumEuroPrice, NumForPrice, NumPercentOff, NumPlusNumFree, NumthPercentOff, SaleType,
-};
+use crate::models::sale_types::{StackingDiscount, StackingTier};
 use crate::models::{CentPrice, SaleValidity, Unit, UnitPrice, UnitQuantity};
 
 #[macro_export]
@@ -110,106 +108,24 @@ pub(crate) fn derive_unit_price(unit_quantity: &UnitQuantity, display_price: Cen
     .into()
 }
 
-/// Try to parse the given sale label into a proper [SaleType]
-pub(crate) fn parse_sale_label(sale_label: &str) -> Option<SaleType> {
-    //language=regexp
-    lazy_re_set!(
-        SALE_RX,
-        // Basic `1 + 2 gratis`
-        r"(\d+) \s* \+ \s* (\d+) \s* gratis",
-        // `2e gratis`
-        r"(\d+) \s* e \s* gratis",
-        // `50% korting`
-        r"(\d+) \s* % \s* korting",
-        // `2e halve prijs`
-        r"(\d+) \s* e \s* halve \s* prijs",
-        // `3 voor €4,50`
-        r"(\d+) \s* voor \s* €? \s* (\d+)(?:[,.](\d+))?",
-        // `1 euro korting` | `1.50 euro korting`
-        r"(\d+)(?:[,.](\d+))? \s* euro \s* korting",
-        // `€1.00 korting`
-        r"€? \s* (\d+)(?:[,.](\d+))? \s* korting",
-        // `NU €4.00`
-        r"NU \s* €(\d+)(?:[,.](\d+))?"
-    );
-
-    let match_idx = SALE_RX.0.matches(sale_label).into_iter().next()?;
-    let capture = SALE_RX.1[match_idx].captures(sale_label)?;
-
-    match match_idx {
-        0 => {
-            // Basic `1 + 2 gratis`
-            let (required, free) = (capture.get(1)?, capture.get(2)?);
-            let result = NumPlusNumFree {
-                required: required.as_str().parse().ok()?,
-                free: free.as_str().parse().ok()?,
-            };
-
-            Some(SaleType::NumPlusNumFree(result))
-        }
-        1 => {
-            //`2e gratis`
-            let required: u16 = capture.get(1)?.as_str().parse().ok()?;
-            let result = NumPlusNumFree {
-                required: required.checked_sub(1)?.try_into().ok()?,
-                free: NonZeroU16::new(1)?,
-            };
-
-            Some(SaleType::NumPlusNumFree(result))
-        }
-        2 => {
-            // `50% korting`
-            let percent_off: NonZeroU16 = capture.get(1)?.as_str().parse().ok()?;
-            let result = NumPercentOff::new(percent_off)?;
-
-            Some(SaleType::NumPercentOff(result))
-        }
-        3 => {
-            // `2e halve prijs`
-            let required: NonZeroU16 = capture.get(1)?.as_str().parse().ok()?;
-            let result = NumthPercentOff {
-                required,
-                last_percent_off: NonZeroU16::new(50)?,
-            };
+/// Parse the individual tiers of a `stapelkorting` label, like `stapelkorting 2 stuks 10% 3 stuks 20%`.
+///
+/// Labels which don't mention their tiers result in an empty [StackingDiscount].
+pub(crate) fn parse_stacking_tiers(sale_label: &str) -> StackingDiscount {
+    lazy_re!(TIER_RX, r"(?i)(\d+)\s*(?:stuks?)?\s*[:=]?\s*(\d+)\s*%");
 
-            Some(SaleType::NumthPercentOff(result))
-        }
-        4 => {
-            // `3 voor €4,50`
+    let mut tiers: Vec<StackingTier> = TIER_RX
+        .captures_iter(sale_label)
+        .flat_map(|capture| {
             let required: NonZeroU16 = capture.get(1)?.as_str().parse().ok()?;
-            let (integer_part, fractional_part) = (capture.get(2)?, capture.get(3));
-            let price = parse_int_fract_price(
-                integer_part.as_str().parse().ok()?,
-                fractional_part.and_then(|frac| frac.as_str().parse().ok()).unwrap_or(0),
-            );
-            let result = NumForPrice { required, price };
+            let percent_off: NonZeroU16 = capture.get(2)?.as_str().parse().ok()?;
 
-            Some(SaleType::NumForPrice(result))
-        }
-        5 | 6 => {
-            // `1 euro korting` / `€1.00 korting`
-            let (integer_part, fractional_part) = (capture.get(1)?, capture.get(2));
-            let price = parse_int_fract_price(
-                integer_part.as_str().parse().ok()?,
-                fractional_part.and_then(|frac| frac.as_str().parse().ok()).unwrap_or(0),
-            );
-            let result = NumEuroOff { price_off: price };
-
-            Some(SaleType::NumEuroOff(result))
-        }
-        7 => {
-            // `NU €4.00`
-            let (integer_part, fractional_part) = (capture.get(1)?, capture.get(2));
-            let price = parse_int_fract_price(
-                integer_part.as_str().parse().ok()?,
-                fractional_part.and_then(|frac| frac.as_str().parse().ok()).unwrap_or(0),
-            );
-            let result = NumEuroPrice { price };
+            (percent_off.get() <= 100).then_some(StackingTier { required, percent_off })
+        })
+        .collect();
+    tiers.sort_by_key(|tier| tier.required);
 
-            Some(SaleType::NumEuroPrice(result))
-        }
-        _ => panic!("Wrong index match for sale matching, forgot an update to the match expression?"),
-    }
+    StackingDiscount { tiers }
 }
 
 pub(crate) fn parse_int_fract_price(integer_part: CentPrice, fractional_part: CentPrice) -> CentPrice {
@@ -264,7 +180,8 @@ pub(crate) fn create_regex_set(regexes: impl IntoIterator<Item = impl AsRef<str>
 #[cfg(test)]
 mod tests {
     use crate::models::{Unit, UnitPrice, UnitQuantity};
-    use crate::providers::common_bridge::{derive_unit_price, parse_quantity, parse_sale_label};
+    use crate::providers::common_bridge::{derive_unit_price, parse_quantity};
+    use crate::sale_rules::CompiledSaleRules;
 
     #[test]
     pub fn test_sale_parser() {
@@ -287,6 +204,13 @@ mod tests {
             "15 euro korting",
             "1.50 euro korting",
             "€ 1,00 korting",
+            "2 voor 25% korting",
+            "3e gratis bij 3",
+            "2 + 1 gratis op het hele assortiment",
+            "Stapelkorting",
+            "stapelkorting 2 stuks 10% 3 stuks 20%",
+            "per kilo €9,99",
+            "vanaf 2 stuks",
         ];
         let expected = [
             SaleType::NumPlusNumFree(NumPlusNumFree {
@@ -325,11 +249,42 @@ mod tests {
             SaleType::NumEuroOff(NumEuroOff { price_off: 1500 }),
             SaleType::NumEuroOff(NumEuroOff { price_off: 150 }),
             SaleType::NumEuroOff(NumEuroOff { price_off: 100 }),
+            SaleType::NumForPercentOff(NumForPercentOff::new(nz!(2), 25).unwrap()),
+            SaleType::NumPlusNumFree(NumPlusNumFree {
+                required: nz!(2),
+                free: nz!(1),
+            }),
+            SaleType::NumPlusNumFree(NumPlusNumFree {
+                required: nz!(2),
+                free: nz!(1),
+            }),
+            SaleType::StackingDiscount(StackingDiscount { tiers: vec![] }),
+            SaleType::StackingDiscount(StackingDiscount {
+                tiers: vec![
+                    StackingTier {
+                        required: nz!(2),
+                        percent_off: nz!(10),
+                    },
+                    StackingTier {
+                        required: nz!(3),
+                        percent_off: nz!(20),
+                    },
+                ],
+            }),
+            SaleType::PricePerUnit(PricePerUnit {
+                unit: Unit::KiloGram,
+                price: 999,
+            }),
+            SaleType::MinimumQuantity(MinimumQuantity { required: nz!(2) }),
         ];
 
+        let rules = CompiledSaleRules::default();
+
         for (to_parse, expected) in test_cases.iter().zip(expected.iter()) {
             assert_eq!(
-                parse_sale_label(to_parse).unwrap_or_else(|| panic!("Failed to parse example: {to_parse}")),
+                rules
+                    .parse(to_parse)
+                    .unwrap_or_else(|| panic!("Failed to parse example: {to_parse}")),
                 *expected
             );
         }
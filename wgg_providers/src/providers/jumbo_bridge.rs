@@ -1,42 +1,216 @@
 use crate::error::Result;
 use crate::models::sale_types::SaleType;
 use crate::models::{
-    AllergyTags, AllergyType, Description, FreshLabel, IngredientInfo, ItemInfo, ItemType, NumberOfServings,
-    NutritionalInfo, NutritionalItem, PriceInfo, ProductIdT, Provider, ProviderMetadata, SaleInformation,
-    SaleResolutionStrategy, SaleValidity, SubNutritionalItem, TextType, UnavailableItem, UnavailableReason, UnitPrice,
-    WggAutocomplete, WggDecorator, WggProduct, WggSaleCategory, WggSaleGroupComplete, WggSaleGroupLimited, WggSaleItem,
-    WggSearchProduct,
+    AllergyTags, AllergyType, Description, FeeSchedule, FreshLabel, IngredientInfo, ItemInfo, ItemType,
+    NumberOfServings, NutritionalInfo, NutritionalItem, PriceInfo, ProductIdRef, ProductIdT, Provider,
+    ProviderMetadata, SaleInformation, SaleResolutionStrategy, SaleValidity, SubNutritionalItem, TextType,
+    UnavailableItem, UnavailableReason, UnitPrice, WggAutocomplete, WggCartItem, WggCategory, WggDecorator, WggProduct,
+    WggRemotePromotion, WggRemoteTally, WggSaleCategory, WggSaleGroupComplete, WggSaleGroupLimited, WggSaleItem,
+    WggSearchProduct, parse_gtin,
 };
 use crate::pagination::OffsetPagination;
-use crate::providers::common_bridge::{derive_unit_price, parse_sale_label, parse_unit_component};
-use crate::providers::{ProviderInfo, StaticProviderInfo, common_bridge};
+use crate::providers::common_bridge::{derive_unit_price, parse_unit_component};
+use crate::providers::{ProviderCart, ProviderInfo, StaticProviderInfo, common_bridge};
+use crate::sale_rules::CompiledSaleRules;
 use crate::{ProviderError, lazy_re};
 use anyhow::Context;
 use cached::proc_macro::once;
 use regex::Regex;
-use wgg_jumbo::models::{AvailabilityType, PromotionGroupContent};
-use wgg_jumbo::{BaseApi, BaseJumboApi};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+use std::future::Future;
+use std::sync::Arc;
+use wgg_jumbo::models::{AvailabilityType, BasketItem, PromotionCompletionRequest, PromotionGroupContent, Unit};
+use wgg_jumbo::{BaseApi, BaseJumboApi, FullJumboApi};
+
+/// The amount of products requested per page when browsing a category.
+const JUMBO_CATEGORY_PAGE_SIZE: u32 = 50;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct JumboCredentials {
+    email: String,
+    password: SecretString,
+}
+
+impl JumboCredentials {
+    /// Create a new set of credentials.
+    pub fn new(email: impl Into<String>, password: SecretString) -> Self {
+        Self {
+            email: email.into(),
+            password,
+        }
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn password(&self) -> &secrecy::SecretString {
+        &self.password
+    }
+}
 
 pub(crate) struct JumboBridge {
     pub api: BaseJumboApi,
+    /// Only available if the bridge was created with [JumboCredentials], required for cart operations.
+    session: Option<JumboSession>,
+    sale_rules: Arc<CompiledSaleRules>,
+}
+
+/// A lazily logged in Jumbo session, which logs in again once its token is rejected.
+struct JumboSession {
+    config: wgg_jumbo::Config,
+    credentials: JumboCredentials,
+    /// The currently logged in API, `None` until the first cart operation.
+    ///
+    /// The lock is held during a log in to ensure only *one* future tries to log in when the token is expired.
+    api: tokio::sync::Mutex<Option<Arc<FullJumboApi>>>,
+}
+
+impl JumboSession {
+    /// Retrieve the logged in API, logging in if there is no session yet or if the current session is `expired`.
+    async fn logged_in(&self, expired: Option<&Arc<FullJumboApi>>) -> Result<Arc<FullJumboApi>> {
+        let mut api = self.api.lock().await;
+
+        match api.as_ref() {
+            // Another future could've already logged in again while we were waiting for the lock.
+            Some(current) if !expired.is_some_and(|expired| Arc::ptr_eq(expired, current)) => Ok(current.clone()),
+            _ => {
+                tracing::debug!("Logging in to Jumbo");
+                let new_api = Arc::new(
+                    FullJumboApi::from_login(
+                        self.credentials.email(),
+                        self.credentials.password().expose_secret(),
+                        self.config.clone(),
+                    )
+                    .await?,
+                );
+
+                Ok(api.insert(new_api).clone())
+            }
+        }
+    }
 }
 
 impl JumboBridge {
-    pub fn new(api: BaseJumboApi) -> Self {
-        JumboBridge { api }
+    pub fn new(api: BaseJumboApi, sale_rules: Arc<CompiledSaleRules>) -> Self {
+        JumboBridge {
+            api,
+            session: None,
+            sale_rules,
+        }
+    }
+
+    /// Create a new [JumboBridge] which also supports cart operations by logging in with the given `credentials`.
+    ///
+    /// The log in is deferred until the first cart operation.
+    pub fn new_authenticated(
+        config: wgg_jumbo::Config,
+        credentials: JumboCredentials,
+        sale_rules: Arc<CompiledSaleRules>,
+    ) -> Self {
+        JumboBridge {
+            api: BaseJumboApi::new(config.clone()),
+            session: Some(JumboSession {
+                config,
+                credentials,
+                api: Default::default(),
+            }),
+            sale_rules,
+        }
+    }
+
+    /// Perform the given `api_request` with the logged in cart API.
+    ///
+    /// Should the token be rejected we'll log in again, and retry the request once.
+    async fn cart_request<F, Fut, O>(&self, api_request: F) -> Result<O>
+    where
+        F: Fn(Arc<FullJumboApi>) -> Fut,
+        Fut: Future<Output = wgg_jumbo::Result<O>>,
+    {
+        let session = self.session.as_ref().ok_or_else(|| {
+            ProviderError::OperationUnsupported("Cart operations require Jumbo credentials".to_string())
+        })?;
+        let api = session.logged_in(None).await?;
+
+        match api_request(api.clone()).await {
+            Err(wgg_jumbo::ApiError::LoginFailed(reason)) => {
+                tracing::debug!(%reason, "Jumbo token was rejected, logging in again");
+                let api = session.logged_in(Some(&api)).await?;
+
+                Ok(api_request(api).await?)
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Apply the given `quantity_change` to all `items` currently in the basket.
+    ///
+    /// The Jumbo basket API only accepts absolute quantities, so we first need to retrieve the current basket.
+    async fn modify_basket(
+        &self,
+        items: &[(&ProductIdRef, u32)],
+        quantity_change: impl Fn(u32, u32) -> u32 + Send + Sync,
+    ) -> Result<()> {
+        let current = self.cart_request(|api| async move { api.basket().await }).await?.basket;
+
+        let new_items = futures::future::try_join_all(items.iter().map(|(id, quantity)| {
+            let current_line = current.items.iter().find(|line| line.sku == *id);
+            let quantity_change = &quantity_change;
+
+            async move {
+                let item = match current_line {
+                    Some(line) => BasketItem::with_unit(
+                        line.sku.clone(),
+                        line.unit.clone(),
+                        quantity_change(line.quantity, *quantity),
+                    ),
+                    None => {
+                        // Weighted products need to be added by their own unit, which requires the product details.
+                        let product = self.api.product(&id.parse()?).await?.product.data;
+                        let unit = product
+                            .quantity_options
+                            .first()
+                            .map(|option| option.unit.basket_unit())
+                            .unwrap_or(Unit::Pieces.basket_unit());
+
+                        BasketItem::with_unit(product.id, unit, quantity_change(0, *quantity))
+                    }
+                };
+
+                Ok::<_, ProviderError>(item)
+            }
+        }))
+        .await?;
+
+        let new_items = &new_items;
+        let _ = self
+            .cart_request(|api| async move { api.update_basket(new_items).await })
+            .await?;
+
+        Ok(())
     }
 }
 
 impl StaticProviderInfo for JumboBridge {
     fn provider() -> Provider {
-        Provider::Jumbo
+        Provider::JUMBO
     }
 
     fn metadata() -> ProviderMetadata {
         ProviderMetadata {
+            display_name: "Jumbo".into(),
             logo_url: "https://upload.wikimedia.org/wikipedia/commons/8/8d/Jumbo_Logo.svg".into(),
             sale_strategy: SaleResolutionStrategy::Pessimistic,
+            // Only supported when the bridge has credentials, see the `ProviderInfo` implementation.
             supports_cart: false,
+            supports_delivery: false,
+            supports_categories: true,
+            minimum_order_value: 4000,
+            // Jumbo's delivery fee depends on the chosen time slot, this is the fee of an average slot.
+            delivery_fee: FeeSchedule::tiered([(0, 695), (7500, 395)]),
+            service_fee: FeeSchedule::free(),
+            charges_deposit: true,
         }
     }
 }
@@ -48,7 +222,14 @@ impl ProviderInfo for JumboBridge {
     }
 
     fn metadata(&self) -> ProviderMetadata {
-        <Self as StaticProviderInfo>::metadata()
+        ProviderMetadata {
+            supports_cart: self.session.is_some(),
+            ..<Self as StaticProviderInfo>::metadata()
+        }
+    }
+
+    fn as_cart_provider(&self) -> Option<&(dyn ProviderCart + Send + Sync)> {
+        self.session.as_ref().map(|_| self as &(dyn ProviderCart + Send + Sync))
     }
 
     #[tracing::instrument(name = "jumbo_autocomplete", level="debug", skip_all, fields(query = query))]
@@ -83,7 +264,7 @@ impl ProviderInfo for JumboBridge {
                 .products
                 .data
                 .into_iter()
-                .map(parse_jumbo_item_to_search_item)
+                .map(|article| parse_jumbo_item_to_search_item(article, &self.sale_rules))
                 .collect(),
             total_items: search_results.products.total as usize,
             offset: search_results.products.offset,
@@ -96,7 +277,10 @@ impl ProviderInfo for JumboBridge {
         #[cfg(feature = "trace-original-api")]
         tracing::trace!("Jumbo Product: {:#?}", result);
 
-        Ok(parse_jumbo_product_to_crate_product(result.product.data))
+        Ok(parse_jumbo_product_to_crate_product(
+            result.product.data,
+            &self.sale_rules,
+        ))
     }
 
     async fn promotions(&self) -> Result<Vec<WggSaleCategory>> {
@@ -133,11 +317,11 @@ impl ProviderInfo for JumboBridge {
             .map(|item| WggSaleCategory {
                 id: None,
                 name: item.title,
-                provider: Provider::Jumbo,
+                provider: Provider::JUMBO,
                 items: item
                     .promotions
                     .into_iter()
-                    .flat_map(parse_jumbo_promotion)
+                    .flat_map(|promotion| parse_jumbo_promotion(promotion, &self.sale_rules))
                     .map(WggSaleItem::Group)
                     .collect(),
                 image_urls: Vec::new(),
@@ -156,11 +340,141 @@ impl ProviderInfo for JumboBridge {
         #[cfg(feature = "trace-original-api")]
         tracing::trace!("Jumbo Promotion Products: {:#?}", product_list);
 
-        parse_jumbo_promotion_complete(promotion, product_list)
+        parse_jumbo_promotion_complete(promotion, product_list, &self.sale_rules)
+    }
+
+    #[tracing::instrument(name = "jumbo_categories", level = "debug", skip(self))]
+    async fn categories(&self) -> Result<Vec<WggCategory>> {
+        let top_level = self.api.categories(None).await?.categories.data;
+
+        // Jumbo only lists the direct sub-categories of a category, we limit ourselves to the first two levels.
+        let children = top_level.iter().map(|category| async move {
+            if category.sub_categories_count == 0 {
+                Ok(Vec::new())
+            } else {
+                self.api
+                    .categories(Some(&category.id))
+                    .await
+                    .map(|list| list.categories.data)
+            }
+        });
+        let children = futures::future::try_join_all(children).await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("Jumbo Categories: {:#?} - {:#?}", top_level, children);
+
+        Ok(top_level
+            .into_iter()
+            .zip(children)
+            .map(|(category, children)| WggCategory {
+                children: children.into_iter().map(parse_jumbo_category).collect(),
+                ..parse_jumbo_category(category)
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(name = "jumbo_category_products", level = "debug", skip(self))]
+    async fn category_products(
+        &self,
+        category_id: &str,
+        offset: Option<u32>,
+    ) -> Result<OffsetPagination<WggSearchProduct>> {
+        let offset = offset.unwrap_or_default();
+        let products = self
+            .api
+            .products_category(&category_id.parse()?, JUMBO_CATEGORY_PAGE_SIZE, offset)
+            .await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("Jumbo Category Products: {:#?}", products);
+
+        Ok(OffsetPagination {
+            items: products
+                .products
+                .data
+                .into_iter()
+                .map(|article| parse_jumbo_item_to_search_item(article, &self.sale_rules))
+                .collect(),
+            total_items: products.products.total as usize,
+            offset: products.products.offset,
+        })
     }
 }
 
-fn parse_jumbo_promotion(promotion: wgg_jumbo::models::Promotion) -> Option<WggSaleGroupLimited> {
+#[async_trait::async_trait]
+impl ProviderCart for JumboBridge {
+    #[tracing::instrument(name = "jumbo_add_to_cart", level = "trace", skip(self, items))]
+    async fn add_to_cart(&self, items: &[(&ProductIdRef, u32)]) -> Result<()> {
+        self.modify_basket(items, |current, quantity| current + quantity).await
+    }
+
+    #[tracing::instrument(name = "jumbo_remove_from_cart", level = "trace", skip(self, items))]
+    async fn remove_from_cart(&self, items: &[(&ProductIdRef, u32)]) -> Result<()> {
+        self.modify_basket(items, |current, quantity| current.saturating_sub(quantity))
+            .await
+    }
+
+    #[tracing::instrument(name = "jumbo_clear_cart", level = "trace", skip(self))]
+    async fn clear_cart(&self) -> Result<()> {
+        self.cart_request(|api| async move { api.clear_basket().await }).await
+    }
+
+    #[tracing::instrument(name = "jumbo_cart_contents", level = "trace", skip(self))]
+    async fn cart_contents(&self) -> Result<Vec<WggCartItem>> {
+        let basket = self.cart_request(|api| async move { api.basket().await }).await?.basket;
+
+        Ok(basket
+            .items
+            .into_iter()
+            .map(|line| WggCartItem {
+                product_id: line.sku.into(),
+                quantity: line.quantity,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(name = "jumbo_cart_tally", level = "trace", skip(self))]
+    async fn cart_tally(&self, sale_ids: &[&str]) -> Result<WggRemoteTally> {
+        let basket = self.cart_request(|api| async move { api.basket().await }).await?.basket;
+        let request = PromotionCompletionRequest {
+            basket_items: basket
+                .items
+                .into_iter()
+                .map(|line| BasketItem::with_unit(line.sku, line.unit, line.quantity))
+                .collect(),
+        };
+
+        // Jumbo doesn't list any prices for the basket, only the progress of individual promotions.
+        let futures = sale_ids.iter().map(|sale_id| {
+            let request = &request;
+            async move {
+                let promotion_id = &sale_id.parse()?;
+                let completion = self
+                    .cart_request(|api| async move { api.promotion_completion(promotion_id, request).await })
+                    .await?;
+
+                Ok::<_, ProviderError>(WggRemotePromotion {
+                    sale_id: sale_id.to_string(),
+                    applied: completion.times_promotion_fulfilled > 0,
+                    missing_quantity: Some(completion.remaining_quantity_to_fulfil_promotion),
+                    savings: None,
+                })
+            }
+        });
+
+        Ok(WggRemoteTally {
+            lines: Vec::new(),
+            total_price: None,
+            deposit: None,
+            promotions: futures::future::try_join_all(futures).await?,
+        })
+    }
+}
+
+fn parse_jumbo_promotion(
+    promotion: wgg_jumbo::models::Promotion,
+    rules: &CompiledSaleRules,
+) -> Option<WggSaleGroupLimited> {
     Some(WggSaleGroupLimited {
         id: promotion.id.clone().into(),
         name: promotion.title,
@@ -179,23 +493,26 @@ fn parse_jumbo_promotion(promotion: wgg_jumbo::models::Promotion) -> Option<WggS
                 valid_from: promotion.start_date,
                 valid_until: promotion.end_date,
             },
+            rules,
         )?,
         sale_description: promotion.subtitle,
-        provider: Provider::Jumbo,
+        provider: Provider::JUMBO,
     })
 }
 
 fn parse_jumbo_promotion_complete(
     promotion: wgg_jumbo::models::Promotion,
     product_list: wgg_jumbo::models::ProductList,
+    rules: &CompiledSaleRules,
 ) -> Result<WggSaleGroupComplete> {
-    let promo = parse_jumbo_promotion(promotion).context("Provided list is not a true sale (may be a faux-sale!)")?;
+    let promo =
+        parse_jumbo_promotion(promotion, rules).context("Provided list is not a true sale (may be a faux-sale!)")?;
 
     let items = product_list
         .products
         .data
         .into_iter()
-        .map(parse_jumbo_item_to_search_item)
+        .map(|article| parse_jumbo_item_to_search_item(article, rules))
         .map(|mut item| {
             // As it turns out Jumbo doesn't embed the sale info into the individual sale items,
             // we have to do this ourselves!.
@@ -216,7 +533,10 @@ fn parse_jumbo_promotion_complete(
     })
 }
 
-fn parse_jumbo_product_to_crate_product(mut product: wgg_jumbo::models::Product) -> WggProduct {
+fn parse_jumbo_product_to_crate_product(
+    mut product: wgg_jumbo::models::Product,
+    rules: &CompiledSaleRules,
+) -> WggProduct {
     let mut result = WggProduct {
         id: product.id.into(),
         name: product.title,
@@ -241,12 +561,15 @@ fn parse_jumbo_product_to_crate_product(mut product: wgg_jumbo::models::Product)
             original_price: product.prices.price.amount,
             // Will be parsed
             unit_price: None,
+            // Jumbo doesn't expose deposits in their API.
+            deposit: None,
         },
         unit_quantity: product
             .quantity
             .as_deref()
             .and_then(common_bridge::parse_quantity)
             .unwrap_or_default(),
+        gtin: product.ean.as_deref().and_then(parse_gtin),
         // Will be parsed
         unavailable_details: None,
         image_urls: product.image_info.primary_view.into_iter().map(|i| i.url).collect(),
@@ -262,7 +585,7 @@ fn parse_jumbo_product_to_crate_product(mut product: wgg_jumbo::models::Product)
         decorators: vec![],
         // Will be parsed
         sale_information: None,
-        provider: Provider::Jumbo,
+        provider: Provider::JUMBO,
     };
 
     // Unit Pricing
@@ -293,6 +616,7 @@ fn parse_jumbo_product_to_crate_product(mut product: wgg_jumbo::models::Product)
                 valid_from: promotion.from_date,
                 valid_until: promotion.to_date,
             },
+            rules,
         );
     }
 
@@ -393,8 +717,21 @@ fn parse_jumbo_product_to_crate_product(mut product: wgg_jumbo::models::Product)
     result
 }
 
+fn parse_jumbo_category(category: wgg_jumbo::models::Category) -> WggCategory {
+    WggCategory {
+        id: category.id.into(),
+        name: category.title,
+        image_url: category.image_url,
+        children: Vec::new(),
+        provider: Provider::JUMBO,
+    }
+}
+
 /// Parse a full picnic [wgg_jumbo::models::SingleArticle] to our normalised [SearchItem]
-fn parse_jumbo_item_to_search_item(mut article: wgg_jumbo::models::PartialProduct) -> WggSearchProduct {
+fn parse_jumbo_item_to_search_item(
+    mut article: wgg_jumbo::models::PartialProduct,
+    rules: &CompiledSaleRules,
+) -> WggSearchProduct {
     let mut result = WggSearchProduct {
         id: article.id.into(),
         name: article.title,
@@ -403,6 +740,8 @@ fn parse_jumbo_item_to_search_item(mut article: wgg_jumbo::models::PartialProduc
             .as_deref()
             .and_then(common_bridge::parse_quantity)
             .unwrap_or_default(),
+        // Jumbo only lists barcodes on the full product.
+        gtin: None,
         image_url: article
             .image_info
             .take()
@@ -410,7 +749,7 @@ fn parse_jumbo_item_to_search_item(mut article: wgg_jumbo::models::PartialProduc
             .and_then(|it| it.map(|i| i.url).next()),
         decorators: Vec::new(),
         sale_information: None,
-        provider: Provider::Jumbo,
+        provider: Provider::JUMBO,
         price_info: PriceInfo {
             original_price: article.prices.price.amount,
             display_price: article
@@ -419,6 +758,7 @@ fn parse_jumbo_item_to_search_item(mut article: wgg_jumbo::models::PartialProduc
                 .map(|price| price.amount)
                 .unwrap_or(article.prices.price.amount),
             unit_price: None,
+            deposit: None,
         },
         unavailable_details: None,
     };
@@ -451,6 +791,7 @@ fn parse_jumbo_item_to_search_item(mut article: wgg_jumbo::models::PartialProduc
                 valid_from: promotion.from_date,
                 valid_until: promotion.to_date,
             },
+            rules,
         );
     }
 
@@ -487,11 +828,12 @@ fn parse_promotion_to_sale_info(
     promotion_id: Option<String>,
     tags: &[impl AsRef<str>],
     sale_validity: SaleValidity,
+    rules: &CompiledSaleRules,
 ) -> Option<SaleInformation> {
     // Get the first valid label
     let parsed_label = tags
         .iter()
-        .flat_map(|tag| Some((tag, parse_sale_label(tag.as_ref())?)))
+        .flat_map(|tag| Some((tag, rules.parse(tag.as_ref())?)))
         .next();
     let to_sale = |sale_type: Option<SaleType>, label: &str| SaleInformation {
         id: promotion_id,
@@ -1,12 +1,18 @@
-use crate::error::Result;
-use crate::models::{ProductIdRef, Provider, ProviderMetadata, WggAutocomplete, WggSearchProduct};
-use crate::models::{WggProduct, WggSaleCategory, WggSaleGroupComplete};
+use crate::error::{ProviderError, Result};
+use crate::models::{ProductIdRef, Provider, ProviderMetadata, ProviderOutcome, WggAutocomplete, WggSearchProduct};
+use crate::models::{
+    WggCartItem, WggCategory, WggCheckoutStatus, WggDeliverySlot, WggOrder, WggOrderSummary, WggProduct,
+    WggRemoteTally, WggSaleCategory, WggSaleGroupComplete,
+};
 use crate::pagination::OffsetPagination;
 
+mod ah_bridge;
 pub mod common_bridge;
 mod jumbo_bridge;
 mod picnic_bridge;
 
+pub(crate) use ah_bridge::*;
+pub use jumbo_bridge::JumboCredentials;
 pub(crate) use jumbo_bridge::*;
 pub use picnic_bridge::*;
 
@@ -33,6 +39,21 @@ pub trait ProviderInfo: StaticProviderInfo {
         None
     }
 
+    /// If this provider supports delivery slots then it will be converted to [ProviderDelivery].
+    fn as_delivery_provider(&self) -> Option<&(dyn ProviderDelivery + Send + Sync)> {
+        None
+    }
+
+    /// The current health of this provider.
+    ///
+    /// For example [OutcomeStatus::Degraded](crate::models::OutcomeStatus::Degraded) if its requests are known to fail
+    /// until some action is taken.
+    ///
+    /// Should not perform any network requests.
+    async fn status(&self) -> ProviderOutcome {
+        ProviderOutcome::ok(self.provider())
+    }
+
     /// Perform an autocomplete match for the provided query.
     ///
     /// Some APIs will perform a network call, whilst others will do in-process filtering to provide a list of terms.
@@ -52,6 +73,25 @@ pub trait ProviderInfo: StaticProviderInfo {
 
     /// Retrieve a specific promotion
     async fn promotions_sublist(&self, sublist_id: &str) -> Result<WggSaleGroupComplete>;
+
+    /// Retrieve the category tree of the provider's catalogue.
+    ///
+    /// See also [ProviderMetadata::supports_categories]
+    async fn categories(&self) -> Result<Vec<WggCategory>> {
+        Err(ProviderError::OperationUnsupported("categories".to_string()))
+    }
+
+    /// Retrieve the products in the category with the given `category_id`, including those of its sub-categories.
+    ///
+    /// # Arguments
+    /// * `offset` - Can be `None` for APIs which don't support pagination, but should be used for others which do.
+    async fn category_products(
+        &self,
+        _category_id: &str,
+        _offset: Option<u32>,
+    ) -> Result<OffsetPagination<WggSearchProduct>> {
+        Err(ProviderError::OperationUnsupported("category_products".to_string()))
+    }
 }
 
 /// A trait implemented by a provider if it supports Cart manipulations
@@ -67,6 +107,38 @@ pub trait ProviderCart: ProviderInfo {
 
     /// Clear the current remote cart.
     async fn clear_cart(&self) -> Result<()>;
+
+    /// Retrieve the contents of the current remote cart.
+    ///
+    /// The same product can be listed more than once, for example when a provider lists sale items separately.
+    async fn cart_contents(&self) -> Result<Vec<WggCartItem>>;
+
+    /// Retrieve the tally of the current remote cart as calculated by the provider.
+    ///
+    /// `sale_ids` are the promotions of which the progress should be retrieved, a provider which reports the progress
+    /// of the promotions in its cart by itself may ignore them.
+    async fn cart_tally(&self, sale_ids: &[&str]) -> Result<WggRemoteTally>;
+}
+
+/// A trait implemented by a provider if it supports selecting a delivery slot for, and checking out, the remote cart.
+///
+/// See also [ProviderMetadata::supports_delivery]
+#[async_trait::async_trait]
+pub trait ProviderDelivery: ProviderCart {
+    /// Retrieve all delivery slots for the current remote cart, including unavailable ones.
+    async fn delivery_slots(&self) -> Result<Vec<WggDeliverySlot>>;
+
+    /// Select the delivery slot with the given id for the current remote cart.
+    async fn select_delivery_slot(&self, slot_id: &str) -> Result<()>;
+
+    /// Retrieve the status of the checkout of the current remote cart.
+    async fn checkout_status(&self) -> Result<WggCheckoutStatus>;
+
+    /// Retrieve all orders which have been delivered, most recent first.
+    async fn delivered_orders(&self) -> Result<Vec<WggOrderSummary>>;
+
+    /// Retrieve the full contents of the delivered order with the given id, see [Self::delivered_orders].
+    async fn delivered_order(&self, order_id: &str) -> Result<WggOrder>;
 }
 
 pub trait ProviderToAny: 'static {
@@ -1,6 +1,7 @@
 use std::marker::PhantomData;
 use std::num::NonZeroU32;
 use std::ops::Deref;
+use std::sync::Arc;
 use std::time::Duration;
 
 use chrono::{Datelike, NaiveDate, Utc};
@@ -13,29 +14,35 @@ use regex::Regex;
 
 use wgg_picnic::PicnicApi;
 use wgg_picnic::models::{
-    Body, Decorator, ImageSize, PageBody, PageChildren, PagePml, PagesRoot, PmlComponent, UnavailableReason,
+    Body, Catalog, Category, CheckoutStatus, Decorator, Delivery, DeliverySlot, DeliveryStatus, ImageSize, Order,
+    OrderArticle, OrderLine, PageBody, PageChildren, PagePml, PagesRoot, PmlComponent, SingleArticle, SubCategory,
+    UnavailableReason,
 };
 
-use crate::error::Result;
+use crate::error::{Result, SubProviderError};
 use crate::models::{
-    AllergyTags, AllergyType, CentPrice, Description, FreshLabel, IngredientInfo, ItemInfo, ItemType, NutritionalInfo,
-    NutritionalItem, PrepTime, PriceInfo, ProductIdRef, Provider, ProviderMetadata, SaleInformation,
-    SaleResolutionStrategy, SaleValidity, SubNutritionalItem, TextType, UnavailableItem, UnitPrice, WggAutocomplete,
-    WggDecorator, WggProduct, WggSaleCategory, WggSaleGroupComplete, WggSaleGroupLimited, WggSaleItem,
-    WggSearchProduct,
+    AllergyTags, AllergyType, CentPrice, CheckoutState, Description, FeeSchedule, FreshLabel, IngredientInfo, ItemInfo,
+    ItemType, NutritionalInfo, NutritionalItem, PrepTime, PriceInfo, ProductIdRef, Provider, ProviderMetadata,
+    ProviderOutcome, SaleInformation, SaleResolutionStrategy, SaleValidity, SubNutritionalItem, TextType,
+    UnavailableItem, UnitPrice, WggAutocomplete, WggCartItem, WggCategory, WggCheckoutStatus, WggDecorator,
+    WggDeliverySlot, WggOrder, WggOrderLine, WggOrderSummary, WggProduct, WggRemotePromotion, WggRemoteTally,
+    WggSaleCategory, WggSaleGroupComplete, WggSaleGroupLimited, WggSaleItem, WggSearchProduct, parse_gtin,
 };
 use crate::pagination::OffsetPagination;
-use crate::providers::common_bridge::{parse_quantity, parse_sale_label};
-use crate::providers::{ProviderCart, ProviderInfo, StaticProviderInfo, common_bridge};
+use crate::providers::common_bridge::parse_quantity;
+use crate::providers::{ProviderCart, ProviderDelivery, ProviderInfo, StaticProviderInfo, common_bridge};
+use crate::sale_rules::CompiledSaleRules;
 use crate::{ProviderError, lazy_re, lazy_re_set};
 
 pub use authentication::PicnicCredentials;
-use wgg_picnic::credentials::CredentialsCache;
+use wgg_picnic::credentials::{CredentialsCache, LoginStatus};
 
 mod authentication;
 
 pub const PICNIC_RECOMMENDED_RPS: Option<NonZeroU32> = NonZeroU32::new(5);
 const JITTER: Duration = Duration::from_millis(500);
+/// The amount of products returned per page when browsing a category.
+const PICNIC_CATEGORY_PAGE_SIZE: usize = 50;
 
 /// A separate bridge struct to allow for easier caching.
 pub(crate) struct PicnicBridge {
@@ -44,20 +51,29 @@ pub(crate) struct PicnicBridge {
     credentials: PicnicCredentials,
     /// Lock used to ensure only *one* future tries to refresh the auth-token when it is expired.
     refresh_lock: tokio::sync::Mutex<()>,
+    sale_rules: Arc<CompiledSaleRules>,
 }
 
 impl StaticProviderInfo for PicnicBridge {
     fn provider() -> Provider {
-        Provider::Picnic
+        Provider::PICNIC
     }
 
     fn metadata() -> ProviderMetadata {
         ProviderMetadata {
+            display_name: "Picnic".into(),
             logo_url: "https://upload.wikimedia.org/wikipedia/commons/0/01/Picnic_logo.svg".into(),
             // Technically Picnic sale resolution depends on the order of addition to the cart, but the user can manually
             // make this opportunistic in that case.
             sale_strategy: SaleResolutionStrategy::Opportunistic,
             supports_cart: true,
+            supports_delivery: true,
+            supports_categories: true,
+            minimum_order_value: 3500,
+            // Picnic doesn't charge for delivery, instead relying on the minimum order value.
+            delivery_fee: FeeSchedule::free(),
+            service_fee: FeeSchedule::free(),
+            charges_deposit: true,
         }
     }
 }
@@ -67,14 +83,20 @@ impl PicnicBridge {
         login: PicnicCredentials,
         cache: T,
         limit_rps: Option<NonZeroU32>,
+        sale_rules: Arc<CompiledSaleRules>,
     ) -> Result<Self> {
         let config = Default::default();
         let api = PicnicApi::new(cache, config, login.to_login());
 
-        Ok(Self::from_api(api, login, limit_rps))
+        Ok(Self::from_api(api, login, limit_rps, sale_rules))
     }
 
-    pub(crate) fn from_api(api: PicnicApi, credentials: PicnicCredentials, limit_rps: Option<NonZeroU32>) -> Self {
+    pub(crate) fn from_api(
+        api: PicnicApi,
+        credentials: PicnicCredentials,
+        limit_rps: Option<NonZeroU32>,
+        sale_rules: Arc<CompiledSaleRules>,
+    ) -> Self {
         let limiter =
             governor::RateLimiter::direct(Quota::per_second(limit_rps.unwrap_or(PICNIC_RECOMMENDED_RPS.unwrap())));
 
@@ -83,6 +105,7 @@ impl PicnicBridge {
             limiter,
             credentials,
             refresh_lock: Default::default(),
+            sale_rules,
         }
     }
 
@@ -102,6 +125,21 @@ impl PicnicBridge {
         let read_lock = self.api.read().await;
         Ok(api_request(&TemporaryApi::new(&read_lock)).await?)
     }
+
+    /// The current state of the Picnic login.
+    pub(crate) async fn login_status(&self) -> Result<LoginStatus> {
+        Ok(self.api.read().await.login_status().await?)
+    }
+
+    /// Finish a login which is waiting for the second factor code.
+    pub(crate) async fn submit_second_factor(&self, code: &str) -> Result<()> {
+        Ok(self.api.read().await.submit_second_factor(code).await?)
+    }
+
+    /// Discard any pending login and log in again, returning the state of the new login.
+    pub(crate) async fn restart_login(&self) -> Result<LoginStatus> {
+        Ok(self.api.read().await.restart_login().await?)
+    }
 }
 
 #[async_trait::async_trait]
@@ -118,6 +156,26 @@ impl ProviderInfo for PicnicBridge {
         Some(self as &(dyn ProviderCart + Send + Sync))
     }
 
+    fn as_delivery_provider(&self) -> Option<&(dyn ProviderDelivery + Send + Sync)> {
+        Some(self as &(dyn ProviderDelivery + Send + Sync))
+    }
+
+    async fn status(&self) -> ProviderOutcome {
+        let provider = <Self as StaticProviderInfo>::provider();
+
+        match self.login_status().await {
+            Ok(LoginStatus::AwaitingSecondFactor { .. }) => {
+                ProviderOutcome::degraded(provider, "The login is waiting for the second factor code sent by SMS")
+            }
+            Ok(LoginStatus::SecondFactorExpired { .. }) => ProviderOutcome::degraded(
+                provider,
+                "The second factor code expired, the login has to be restarted",
+            ),
+            Ok(LoginStatus::LoggedIn | LoginStatus::LoggedOut) => ProviderOutcome::ok(provider),
+            Err(e) => ProviderOutcome::failed(provider, &e),
+        }
+    }
+
     #[tracing::instrument(name = "picnic_autocomplete", level = "trace", skip(self))]
     async fn autocomplete(&self, query: &str) -> Result<Vec<WggAutocomplete>> {
         let result = self.picnic_request(|api| api.suggestions(query).boxed()).await?;
@@ -144,7 +202,7 @@ impl ProviderInfo for PicnicBridge {
             .flat_map(|res| {
                 res.items.into_iter().filter_map(|item| {
                     if let wgg_picnic::models::SearchItem::SingleArticle(article) = item {
-                        Some(parse_picnic_item_to_search_item(article))
+                        Some(parse_picnic_item_to_search_item(article, &self.sale_rules))
                     } else {
                         None
                     }
@@ -169,7 +227,7 @@ impl ProviderInfo for PicnicBridge {
         #[cfg(feature = "trace-original-api")]
         tracing::trace!("Picnic Product: {:#?}", result);
 
-        parse_picnic_full_product_to_product(result)
+        parse_picnic_full_product_to_product(result, &self.sale_rules)
     }
 
     #[tracing::instrument(name = "picnic_promotions", level = "trace", skip(self))]
@@ -179,7 +237,7 @@ impl ProviderInfo for PicnicBridge {
         #[cfg(feature = "trace-original-api")]
         tracing::trace!("Picnic Promotions: {:#?}", result);
 
-        Ok(parse_new_picnic_promotions(result))
+        Ok(parse_new_picnic_promotions(result, &self.sale_rules))
     }
 
     #[tracing::instrument(name = "picnic_promotions_sublist", level = "trace", skip(self))]
@@ -190,10 +248,57 @@ impl ProviderInfo for PicnicBridge {
         #[cfg(feature = "trace-original-api")]
         tracing::trace!("Picnic Promotions Sublist: {:#?}", result);
 
-        let result = parse_new_picnic_promotion(sublist_id, result).ok_or_else(|| ProviderError::NothingFound)?;
+        let result = parse_new_picnic_promotion(sublist_id, result, &self.sale_rules)
+            .ok_or_else(|| ProviderError::NothingFound)?;
 
         Ok(result)
     }
+
+    #[tracing::instrument(name = "picnic_categories", level = "trace", skip(self))]
+    async fn categories(&self) -> Result<Vec<WggCategory>> {
+        // Sub-categories are only guaranteed to be included from a depth of 2.
+        let result = self.picnic_request(|api| api.categories(2).boxed()).await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("Picnic Categories: {:#?}", result);
+
+        Ok(result
+            .catalog
+            .into_iter()
+            // Filters out the pseudo-categories, such as promotions and recipes.
+            .filter(|catalog| catalog.is_included_in_category_tree)
+            .map(picnic_catalog_to_category)
+            .filter_map(parse_picnic_category)
+            .collect())
+    }
+
+    #[tracing::instrument(name = "picnic_category_products", level = "trace", skip(self))]
+    async fn category_products(
+        &self,
+        category_id: &str,
+        offset: Option<u32>,
+    ) -> Result<OffsetPagination<WggSearchProduct>> {
+        // Sub-categories can only be retrieved through the top-level category (list) they're part of.
+        let tree = self.picnic_request(|api| api.categories(2).boxed()).await?;
+        let list_id = find_picnic_list_id(tree.catalog.into_iter().map(picnic_catalog_to_category), category_id)
+            .ok_or(ProviderError::NothingFound)?;
+        let sublist_id = (list_id != category_id).then_some(category_id);
+
+        let result = self
+            .picnic_request(|api| api.list(&list_id, sublist_id).boxed())
+            .await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("Picnic Category Products: {:#?}", result);
+
+        let category = find_picnic_category(result, category_id).ok_or(ProviderError::NothingFound)?;
+
+        Ok(parse_picnic_category_page(
+            category,
+            offset.unwrap_or_default(),
+            &self.sale_rules,
+        ))
+    }
 }
 
 #[async_trait::async_trait]
@@ -241,6 +346,127 @@ impl ProviderCart for PicnicBridge {
         self.picnic_request(|api| api.clear_shopping_cart().boxed()).await?;
         Ok(())
     }
+
+    #[tracing::instrument(name = "picnic_cart_contents", level = "trace", skip(self))]
+    async fn cart_contents(&self) -> Result<Vec<WggCartItem>> {
+        let cart = self.picnic_request(|api| api.shopping_cart().boxed()).await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("Picnic Cart: {:#?}", cart);
+
+        Ok(cart
+            .items
+            .into_iter()
+            .flat_map(|line| line.items)
+            .map(|article| WggCartItem {
+                quantity: picnic_article_quantity(&article),
+                product_id: article.id,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(name = "picnic_cart_tally", level = "trace", skip(self))]
+    async fn cart_tally(&self, _sale_ids: &[&str]) -> Result<WggRemoteTally> {
+        let cart = self.picnic_request(|api| api.shopping_cart().boxed()).await?;
+
+        // Picnic only reports the progress of the promotion which was last modified, but does list the price of every
+        // line, which is more precise anyway.
+        let promotions = cart
+            .promo_progress
+            .map(|progress| WggRemotePromotion {
+                sale_id: progress.promotion_id,
+                applied: progress.completed_savings.is_some_and(|savings| savings > 0),
+                missing_quantity: None,
+                savings: progress.completed_savings,
+            })
+            .into_iter()
+            .collect();
+
+        Ok(WggRemoteTally {
+            lines: cart.items.into_iter().flat_map(parse_picnic_order_line).collect(),
+            total_price: Some(cart.total_price.max(0) as CentPrice),
+            deposit: cart.total_deposit,
+            promotions,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ProviderDelivery for PicnicBridge {
+    #[tracing::instrument(name = "picnic_delivery_slots", level = "trace", skip(self))]
+    async fn delivery_slots(&self) -> Result<Vec<WggDeliverySlot>> {
+        let result = self.picnic_request(|api| api.delivery_slots().boxed()).await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("Picnic Delivery Slots: {:#?}", result);
+
+        let fallback_fee = if result.delivery_slots.iter().all(|slot| slot.delivery_fee.is_some()) {
+            0
+        } else {
+            // Not all slots list their fee, those are charged according to the fee schedule instead.
+            let cart = self.picnic_request(|api| api.shopping_cart().boxed()).await?;
+            <Self as StaticProviderInfo>::metadata()
+                .delivery_fee
+                .fee_for(cart.total_price.max(0) as CentPrice)
+        };
+
+        Ok(result
+            .delivery_slots
+            .into_iter()
+            .map(|slot| parse_picnic_delivery_slot(slot, fallback_fee))
+            .collect())
+    }
+
+    #[tracing::instrument(name = "picnic_select_delivery_slot", level = "trace", skip(self))]
+    async fn select_delivery_slot(&self, slot_id: &str) -> Result<()> {
+        self.picnic_request(|api| api.set_delivery_slot(slot_id).boxed())
+            .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "picnic_checkout_status", level = "trace", skip(self))]
+    async fn checkout_status(&self) -> Result<WggCheckoutStatus> {
+        let cart = self.picnic_request(|api| api.shopping_cart().boxed()).await?;
+
+        let status = if let Some(order_id) = &cart.id {
+            match self.picnic_request(|api| api.order_status(order_id).boxed()).await {
+                Ok(status) => Some(status.checkout_status),
+                // Picnic only knows the status of carts for which a checkout was started.
+                Err(ProviderError::SubProviderError(_, SubProviderError::NotFound(_))) => None,
+                Err(e) => return Err(e),
+            }
+        } else {
+            None
+        };
+
+        Ok(parse_picnic_checkout_status(cart, status))
+    }
+
+    #[tracing::instrument(name = "picnic_delivered_orders", level = "trace", skip(self))]
+    async fn delivered_orders(&self) -> Result<Vec<WggOrderSummary>> {
+        let deliveries = self
+            .picnic_request(|api| api.deliveries(&[DeliveryStatus::Completed]).boxed())
+            .await?;
+
+        Ok(deliveries
+            .into_iter()
+            .map(|delivery| WggOrderSummary {
+                id: delivery.delivery_id,
+                delivered_at: delivery.delivery_time.start,
+            })
+            .sorted_by(|a, b| b.delivered_at.cmp(&a.delivered_at))
+            .collect())
+    }
+
+    #[tracing::instrument(name = "picnic_delivered_order", level = "trace", skip(self))]
+    async fn delivered_order(&self, order_id: &str) -> Result<WggOrder> {
+        let delivery = self.picnic_request(|api| api.delivery(order_id).boxed()).await?;
+
+        #[cfg(feature = "trace-original-api")]
+        tracing::trace!("Picnic Delivery: {:#?}", delivery);
+
+        Ok(parse_picnic_delivery(delivery))
+    }
 }
 
 // This is here to hack around the lack of bounding for higher-ranked lifetimes
@@ -267,7 +493,115 @@ impl<'lower, 'upper> TemporaryApi<'lower, 'upper> {
     }
 }
 
-fn parse_new_picnic_promotions(promotions: PagesRoot) -> Vec<WggSaleCategory> {
+/// Parse the given slot, `fallback_fee` is used for the slot if Picnic didn't list its fee.
+fn parse_picnic_delivery_slot(slot: DeliverySlot, fallback_fee: CentPrice) -> WggDeliverySlot {
+    WggDeliverySlot {
+        id: slot.slot_id,
+        window_start: slot.window_start,
+        window_end: slot.window_end,
+        cut_off_time: slot.cut_off_time,
+        available: slot.is_available,
+        selected: slot.selected,
+        unavailability_reason: slot.unavailability_reason,
+        minimum_order_value: slot.minimum_order_value.max(0) as CentPrice,
+        delivery_fee: slot
+            .delivery_fee
+            .map(|fee| fee.max(0) as CentPrice)
+            .unwrap_or(fallback_fee),
+    }
+}
+
+/// Parse the checkout status of the given `cart`, `status` is `None` if no checkout was started for it.
+fn parse_picnic_checkout_status(cart: Order, status: Option<CheckoutStatus>) -> WggCheckoutStatus {
+    let state = match status {
+        Some(CheckoutStatus::Ongoing) => CheckoutState::Ongoing,
+        Some(CheckoutStatus::Finished) => CheckoutState::Finished,
+        Some(CheckoutStatus::Failed) => CheckoutState::Failed,
+        Some(CheckoutStatus::Abandoned) => CheckoutState::Abandoned,
+        Some(CheckoutStatus::Unknown | CheckoutStatus::Unsupported) => CheckoutState::Unknown,
+        None => CheckoutState::NotStarted,
+    };
+
+    WggCheckoutStatus {
+        order_id: cart.id,
+        state,
+        selected_slot_id: cart.selected_slot.map(|slot| slot.slot_id),
+        total_price: cart.checkout_total_price.max(0) as CentPrice,
+    }
+}
+
+fn parse_picnic_delivery(delivery: Delivery) -> WggOrder {
+    let deposit = delivery.orders.iter().filter_map(|order| order.total_deposit).sum();
+    let lines = delivery
+        .orders
+        .into_iter()
+        .flat_map(|order| order.items)
+        .flat_map(parse_picnic_order_line)
+        .collect();
+
+    WggOrder {
+        id: delivery.delivery_id,
+        delivered_at: delivery.delivery_time.start,
+        lines,
+        deposit,
+        // Picnic doesn't charge any fees, see the `ProviderMetadata` of Picnic.
+        delivery_fee: 0,
+        service_fee: 0,
+    }
+}
+
+/// Split the given order line into its articles.
+///
+/// The discount of a line is only known for the line as a whole, it is therefore spread over its articles in proportion
+/// to their price.
+fn parse_picnic_order_line(line: OrderLine) -> Vec<WggOrderLine> {
+    let articles = line
+        .items
+        .into_iter()
+        .map(|article| {
+            let quantity = picnic_article_quantity(&article);
+            (article.id, quantity, article.price.max(0) as CentPrice * quantity)
+        })
+        .collect_vec();
+    let mut remaining_price: CentPrice = articles.iter().map(|(_, _, price)| price).sum();
+    let mut remaining_discount = ((line.price - line.display_price).max(0) as CentPrice).min(remaining_price);
+
+    articles
+        .into_iter()
+        .map(|(product_id, quantity, original_price)| {
+            // A share of what remains, so rounding errors end up with the last article.
+            let article_discount = if remaining_price == 0 {
+                0
+            } else {
+                (remaining_discount as u64 * original_price as u64 / remaining_price as u64) as CentPrice
+            };
+            remaining_discount -= article_discount;
+            remaining_price -= original_price;
+
+            WggOrderLine {
+                product_id,
+                quantity,
+                original_price,
+                display_price: original_price - article_discount,
+            }
+        })
+        .collect()
+}
+
+/// The quantity of an article in a cart or order, which Picnic only lists as a decorator.
+fn picnic_article_quantity(article: &OrderArticle) -> u32 {
+    article
+        .decorators
+        .iter()
+        .find_map(|decorator| match decorator {
+            Decorator::Quantity { quantity } => Some(*quantity),
+            _ => None,
+        })
+        .unwrap_or(1)
+        .max(0) as u32
+}
+
+fn parse_new_picnic_promotions(promotions: PagesRoot, rules: &CompiledSaleRules) -> Vec<WggSaleCategory> {
     //language=regexp
     lazy_re_set!(
         BLOCK_IDS,
@@ -287,15 +621,15 @@ fn parse_new_picnic_promotions(promotions: PagesRoot) -> Vec<WggSaleCategory> {
         .flat_map(|promo_group| {
             let match_idx = BLOCK_IDS.0.matches(&promo_group.id).into_iter().next()?;
             match match_idx {
-                0 => parse_promo_vertical_tiles(promo_group),
-                1 => parse_promo_list_tiles(promo_group, &mut misc_list_id),
+                0 => parse_promo_vertical_tiles(promo_group, rules),
+                1 => parse_promo_list_tiles(promo_group, &mut misc_list_id, rules),
                 _ => unreachable!(),
             }
         })
         .collect()
 }
 
-fn parse_promo_vertical_tiles(promotion: PageBody) -> Option<WggSaleCategory> {
+fn parse_promo_vertical_tiles(promotion: PageBody, rules: &CompiledSaleRules) -> Option<WggSaleCategory> {
     //language=regexp
     lazy_re!(PROMO_GROUP_ID, r".*promo-group-(.*)");
 
@@ -317,7 +651,7 @@ fn parse_promo_vertical_tiles(promotion: PageBody) -> Option<WggSaleCategory> {
             .into_iter()
             .flat_map(PageChildren::to_article_tile)
             .map(|article| {
-                let parsed = parse_picnic_item_to_search_item(article.article);
+                let parsed = parse_picnic_item_to_search_item(article.article, rules);
 
                 // Update the `category_name`.
                 if category_name.is_none() {
@@ -336,7 +670,7 @@ fn parse_promo_vertical_tiles(promotion: PageBody) -> Option<WggSaleCategory> {
             items: articles,
             image_urls: vec![],
             complete: !more_button,
-            provider: Provider::Picnic,
+            provider: Provider::PICNIC,
         })
     } else {
         tracing::warn!(
@@ -347,7 +681,11 @@ fn parse_promo_vertical_tiles(promotion: PageBody) -> Option<WggSaleCategory> {
     }
 }
 
-fn parse_promo_list_tiles(promotion: PageBody, misc_list_id: &mut u32) -> Option<WggSaleCategory> {
+fn parse_promo_list_tiles(
+    promotion: PageBody,
+    misc_list_id: &mut u32,
+    rules: &CompiledSaleRules,
+) -> Option<WggSaleCategory> {
     // Check if it's a 'naked' list tile section, without header
     let child = promotion.children.into_iter().last()?.to_block()?;
     // Expect some form of `promo-groups-vertical-tiles`
@@ -356,7 +694,7 @@ fn parse_promo_list_tiles(promotion: PageBody, misc_list_id: &mut u32) -> Option
 
         let mut category_name = None;
         let sale_groups = pml_articles
-            .flat_map(|article| parse_list_article_item(&mut category_name, article))
+            .flat_map(|article| parse_list_article_item(&mut category_name, article, rules))
             .collect();
 
         Some(WggSaleCategory {
@@ -368,7 +706,7 @@ fn parse_promo_list_tiles(promotion: PageBody, misc_list_id: &mut u32) -> Option
             items: sale_groups,
             image_urls: vec![],
             complete: true,
-            provider: Provider::Picnic,
+            provider: Provider::PICNIC,
         })
     } else {
         tracing::warn!(
@@ -379,7 +717,11 @@ fn parse_promo_list_tiles(promotion: PageBody, misc_list_id: &mut u32) -> Option
     }
 }
 
-fn parse_list_article_item(category_name: &mut Option<String>, article: PagePml) -> Option<WggSaleItem> {
+fn parse_list_article_item(
+    category_name: &mut Option<String>,
+    article: PagePml,
+    rules: &CompiledSaleRules,
+) -> Option<WggSaleItem> {
     //language=regexp
     lazy_re!(PROMO_GROUP_ID, r".*promo_group_id=(.*)");
 
@@ -418,7 +760,7 @@ fn parse_list_article_item(category_name: &mut Option<String>, article: PagePml)
         return None;
     }
 
-    let parsed_sale = common_bridge::parse_sale_label(&sale_label);
+    let parsed_sale = rules.parse(&sale_label);
 
     let sale_information = {
         let valid = common_bridge::get_guessed_sale_validity(Utc::now());
@@ -448,13 +790,17 @@ fn parse_list_article_item(category_name: &mut Option<String>, article: PagePml)
         items: Vec::new(),
         sale_info: sale_information,
         sale_description: description,
-        provider: Provider::Picnic,
+        provider: Provider::PICNIC,
     };
 
     Some(WggSaleItem::Group(group))
 }
 
-fn parse_new_picnic_promotion(sublist_id: impl Into<String>, promotion: PagesRoot) -> Option<WggSaleGroupComplete> {
+fn parse_new_picnic_promotion(
+    sublist_id: impl Into<String>,
+    promotion: PagesRoot,
+    rules: &CompiledSaleRules,
+) -> Option<WggSaleGroupComplete> {
     let children = promotion.body.children;
     let content = children.into_iter().next()?.to_block()?;
     let vertical_tiles = content.children.into_iter().next()?.to_block()?;
@@ -464,7 +810,7 @@ fn parse_new_picnic_promotion(sublist_id: impl Into<String>, promotion: PagesRoo
             .children
             .into_iter()
             .flat_map(PageChildren::to_article_tile)
-            .map(|article| parse_picnic_item_to_search_item(article.article))
+            .map(|article| parse_picnic_item_to_search_item(article.article, rules))
             .collect();
 
         let sublist_id = sublist_id.into();
@@ -488,7 +834,7 @@ fn parse_new_picnic_promotion(sublist_id: impl Into<String>, promotion: PagesRoo
             items: articles,
             sale_info,
             sale_description: None,
-            provider: Provider::Picnic,
+            provider: Provider::PICNIC,
         };
 
         Some(result)
@@ -502,7 +848,10 @@ fn parse_new_picnic_promotion(sublist_id: impl Into<String>, promotion: PagesRoo
 }
 
 /// Parse a full picnic [wgg_picnic::models::ProductDetails] to our normalised [Product]
-fn parse_picnic_full_product_to_product(product: wgg_picnic::models::ProductArticle) -> Result<WggProduct> {
+fn parse_picnic_full_product_to_product(
+    product: wgg_picnic::models::ProductArticle,
+    rules: &CompiledSaleRules,
+) -> Result<WggProduct> {
     let mut result = WggProduct {
         id: product.id,
         name: product.name,
@@ -525,9 +874,11 @@ fn parse_picnic_full_product_to_product(product: wgg_picnic::models::ProductArti
             original_price: product.price_info.original_price.unwrap_or(product.price_info.price),
             // Will be parsed
             unit_price: None,
+            deposit: product.price_info.deposit.filter(|deposit| *deposit > 0),
         },
         // Will be parsed
         unit_quantity: Default::default(),
+        gtin: product.gtin.as_deref().and_then(parse_gtin),
         // Will be parsed
         unavailable_details: None,
         image_urls: product
@@ -547,7 +898,7 @@ fn parse_picnic_full_product_to_product(product: wgg_picnic::models::ProductArti
         decorators: Vec::new(),
         // Will be parsed
         sale_information: None,
-        provider: Provider::Picnic,
+        provider: Provider::PICNIC,
     };
 
     // Parse unit quantity
@@ -663,7 +1014,7 @@ fn parse_picnic_full_product_to_product(product: wgg_picnic::models::ProductArti
         if should_permit_sale_label(&promo.text) {
             result.sale_information = SaleInformation {
                 id: None,
-                sale_type: parse_sale_label(&promo.text),
+                sale_type: rules.parse(&promo.text),
                 label: promo.text,
                 additional_label: Vec::new(),
                 sale_validity: sale_validity.unwrap_or_else(|| common_bridge::get_guessed_sale_validity(Utc::now())),
@@ -679,6 +1030,7 @@ fn parse_picnic_full_product_to_product(product: wgg_picnic::models::ProductArti
             &mut result.decorators,
             &mut result.price_info.display_price,
             &mut result.unavailable_details,
+            rules,
         )
     }
 
@@ -735,21 +1087,130 @@ fn parse_picnic_full_product_to_product(product: wgg_picnic::models::ProductArti
     Ok(result)
 }
 
+/// Treat a top-level catalog entry as just another category, their contents only differ in name.
+fn picnic_catalog_to_category(catalog: Catalog) -> Category {
+    Category {
+        id: catalog.id,
+        name: catalog.name,
+        items: catalog.items.into_iter().map(SubCategory::Category).collect(),
+        level: catalog.level,
+        is_included_in_category_tree: catalog.is_included_in_category_tree,
+        hidden: catalog.hidden,
+        decorators: catalog.decorators,
+        links: catalog.links,
+        image_id: catalog.image_id,
+        header_image_id: catalog.header_image_id,
+    }
+}
+
+fn parse_picnic_category(category: Category) -> Option<WggCategory> {
+    if category.hidden {
+        return None;
+    }
+
+    Some(WggCategory {
+        id: category.id,
+        name: category.name,
+        image_url: category
+            .image_id
+            .map(|image_id| wgg_picnic::images::image_url(image_id, ImageSize::Small)),
+        children: category
+            .items
+            .into_iter()
+            .filter_map(|item| match item {
+                SubCategory::Category(sub_category) => parse_picnic_category(sub_category),
+                _ => None,
+            })
+            .collect(),
+        provider: Provider::PICNIC,
+    })
+}
+
+/// Find the category with the given `id` among the given categories and all their descendants.
+fn find_picnic_category(categories: impl IntoIterator<Item = Category>, id: &str) -> Option<Category> {
+    categories.into_iter().find_map(|category| {
+        if category.id == id {
+            Some(category)
+        } else {
+            let sub_categories: Vec<_> = category
+                .items
+                .into_iter()
+                .filter_map(|item| match item {
+                    SubCategory::Category(sub_category) => Some(sub_category),
+                    _ => None,
+                })
+                .collect();
+
+            find_picnic_category(sub_categories, id)
+        }
+    })
+}
+
+/// Find the id of the top-level category (list) which is, or contains, the category with the given `id`.
+fn find_picnic_list_id(lists: impl IntoIterator<Item = Category>, id: &str) -> Option<String> {
+    lists.into_iter().find_map(|list| {
+        let list_id = list.id.clone();
+
+        find_picnic_category([list], id).map(|_| list_id)
+    })
+}
+
+/// Parse the page of articles in the given category starting at `offset`, see [PICNIC_CATEGORY_PAGE_SIZE].
+fn parse_picnic_category_page(
+    category: Category,
+    offset: u32,
+    rules: &CompiledSaleRules,
+) -> OffsetPagination<WggSearchProduct> {
+    let mut articles = Vec::new();
+    collect_picnic_category_articles(category, &mut articles);
+
+    let total_items = articles.len();
+    let items = articles
+        .into_iter()
+        .skip(offset as usize)
+        .take(PICNIC_CATEGORY_PAGE_SIZE)
+        .map(|article| parse_picnic_item_to_search_item(article, rules))
+        .collect();
+
+    OffsetPagination {
+        items,
+        total_items,
+        offset,
+    }
+}
+
+/// Collect all articles in the given category, including those of its sub-categories.
+fn collect_picnic_category_articles(category: Category, articles: &mut Vec<SingleArticle>) {
+    for item in category.items {
+        match item {
+            SubCategory::Category(sub_category) => collect_picnic_category_articles(sub_category, articles),
+            SubCategory::SingleArticle(article) => articles.push(article),
+            SubCategory::Other => {}
+        }
+    }
+}
+
 /// Parse a full picnic [wgg_picnic::models::SingleArticle] to our normalised [SearchItem]
-fn parse_picnic_item_to_search_item(article: wgg_picnic::models::SingleArticle) -> WggSearchProduct {
+fn parse_picnic_item_to_search_item(
+    article: wgg_picnic::models::SingleArticle,
+    rules: &CompiledSaleRules,
+) -> WggSearchProduct {
     // Note that Picnic's 'display_price' is equivalent to our 'full_price'.
     let mut result = WggSearchProduct {
         id: article.id,
         name: article.name,
         unit_quantity: Default::default(),
+        gtin: article.gtin.as_deref().and_then(parse_gtin),
         image_url: Some(wgg_picnic::images::image_url(article.image_id, ImageSize::Medium)),
         decorators: Vec::new(),
         sale_information: None,
-        provider: Provider::Picnic,
+        provider: Provider::PICNIC,
         price_info: PriceInfo {
             display_price: article.display_price,
             original_price: article.display_price,
             unit_price: None,
+            // Search results don't contain deposit information.
+            deposit: None,
         },
         unavailable_details: None,
     };
@@ -760,7 +1221,7 @@ fn parse_picnic_item_to_search_item(article: wgg_picnic::models::SingleArticle)
         if should_permit_sale_label(&label) {
             result.sale_information = SaleInformation {
                 id: None,
-                sale_type: parse_sale_label(&label),
+                sale_type: rules.parse(&label),
                 label,
                 additional_label: Vec::new(),
                 sale_validity: sale_validity.unwrap_or_else(|| common_bridge::get_guessed_sale_validity(Utc::now())),
@@ -776,6 +1237,7 @@ fn parse_picnic_item_to_search_item(article: wgg_picnic::models::SingleArticle)
             &mut result.decorators,
             &mut result.price_info.display_price,
             &mut result.unavailable_details,
+            rules,
         )
     }
 
@@ -844,6 +1306,7 @@ pub fn parse_decorator(
     result: &mut Vec<WggDecorator>,
     set_display_price: &mut u32,
     set_unavailable: &mut Option<UnavailableItem>,
+    rules: &CompiledSaleRules,
 ) {
     match decorator {
         // If we already parsed it above, we don't want to do it again!
@@ -874,7 +1337,10 @@ pub fn parse_decorator(
                 },
                 explanation_short: explanation.short_explanation.into(),
                 explanation_long: explanation.long_explanation.into(),
-                replacements: replacements.into_iter().map(parse_picnic_item_to_search_item).collect(),
+                replacements: replacements
+                    .into_iter()
+                    .map(|article| parse_picnic_item_to_search_item(article, rules))
+                    .collect(),
             }
             .into();
         }
@@ -985,10 +1451,196 @@ fn should_permit_sale_label(label: impl AsRef<str>) -> bool {
 mod test {
     use std::vec;
 
-    use crate::models::{IngredientInfo, Unit, UnitPrice};
+    use wgg_picnic::models::{
+        Category, CheckoutStatus, Decorator, DeliverySlot, Order, OrderArticle, OrderLine, SelectedSlot, SingleArticle,
+        SubCategory,
+    };
+
+    use crate::models::{CheckoutState, IngredientInfo, Unit, UnitPrice, WggCheckoutStatus, WggOrderLine};
     use crate::providers::picnic_bridge::{
-        parse_days_fresh, parse_euro_price, parse_picnic_ingredient_blob, parse_prep_time, parse_unit_price,
+        PICNIC_CATEGORY_PAGE_SIZE, find_picnic_list_id, parse_days_fresh, parse_euro_price, parse_picnic_category_page,
+        parse_picnic_checkout_status, parse_picnic_delivery_slot, parse_picnic_ingredient_blob,
+        parse_picnic_order_line, parse_prep_time, parse_unit_price,
     };
+    use crate::sale_rules::CompiledSaleRules;
+
+    fn category(id: &str, items: Vec<SubCategory>) -> Category {
+        Category {
+            id: id.to_string(),
+            items,
+            ..Default::default()
+        }
+    }
+
+    fn articles(ids: impl IntoIterator<Item = usize>) -> Vec<SubCategory> {
+        ids.into_iter()
+            .map(|i| {
+                SubCategory::SingleArticle(SingleArticle {
+                    id: format!("s{i}"),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_find_list_id() {
+        let lists = vec![
+            category("dairy", vec![SubCategory::Category(category("milk", vec![]))]),
+            category("bread", vec![]),
+        ];
+
+        assert_eq!(find_picnic_list_id(lists.clone(), "dairy").as_deref(), Some("dairy"));
+        assert_eq!(find_picnic_list_id(lists.clone(), "milk").as_deref(), Some("dairy"));
+        assert_eq!(find_picnic_list_id(lists.clone(), "bread").as_deref(), Some("bread"));
+        assert_eq!(find_picnic_list_id(lists, "cheese"), None);
+    }
+
+    #[test]
+    pub fn test_parse_category_page() {
+        let rules = CompiledSaleRules::default();
+        // The articles of sub-categories are included, in the order they appear in.
+        let mut items = articles(0..40);
+        items.push(SubCategory::Category(category("milk", articles(40..70))));
+        items.push(SubCategory::Other);
+        let dairy = category("dairy", items);
+
+        let page = parse_picnic_category_page(dairy.clone(), 0, &rules);
+        assert_eq!(page.total_items, 70);
+        assert_eq!(page.items.len(), PICNIC_CATEGORY_PAGE_SIZE);
+        assert_eq!(page.items[0].id, "s0");
+        assert_eq!(page.items[45].id, "s45");
+
+        let page = parse_picnic_category_page(dairy.clone(), 50, &rules);
+        assert_eq!(page.offset, 50);
+        assert_eq!(page.items.len(), 20);
+        assert_eq!(page.items[0].id, "s50");
+
+        assert!(parse_picnic_category_page(dairy, 70, &rules).items.is_empty());
+    }
+
+    #[test]
+    pub fn test_parse_delivery_slot() {
+        let slot = DeliverySlot {
+            slot_id: "slot".to_string(),
+            is_available: true,
+            minimum_order_value: 3500,
+            ..Default::default()
+        };
+
+        // Slots which don't list their fee use the fallback.
+        let parsed = parse_picnic_delivery_slot(slot.clone(), 195);
+        assert_eq!(parsed.id, "slot");
+        assert!(parsed.available);
+        assert_eq!(parsed.minimum_order_value, 3500);
+        assert_eq!(parsed.delivery_fee, 195);
+
+        let slot = DeliverySlot {
+            delivery_fee: Some(295),
+            ..slot
+        };
+        assert_eq!(parse_picnic_delivery_slot(slot, 195).delivery_fee, 295);
+    }
+
+    #[test]
+    pub fn test_parse_checkout_status() {
+        let cart = Order {
+            id: Some("order".to_string()),
+            selected_slot: Some(SelectedSlot {
+                slot_id: "slot".to_string(),
+                state: "EXPLICIT".to_string(),
+            }),
+            total_price: 4000,
+            checkout_total_price: 4195,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_picnic_checkout_status(cart.clone(), Some(CheckoutStatus::Ongoing)),
+            WggCheckoutStatus {
+                order_id: Some("order".to_string()),
+                state: CheckoutState::Ongoing,
+                selected_slot_id: Some("slot".to_string()),
+                total_price: 4195,
+            }
+        );
+        assert_eq!(
+            parse_picnic_checkout_status(cart.clone(), Some(CheckoutStatus::Unsupported)).state,
+            CheckoutState::Unknown
+        );
+        assert_eq!(
+            parse_picnic_checkout_status(cart, None).state,
+            CheckoutState::NotStarted
+        );
+
+        // A cart without a selected slot, and a negative total (only discounts) shouldn't underflow.
+        let status = parse_picnic_checkout_status(
+            Order {
+                checkout_total_price: -100,
+                ..Default::default()
+            },
+            None,
+        );
+        assert_eq!(status.order_id, None);
+        assert_eq!(status.selected_slot_id, None);
+        assert_eq!(status.total_price, 0);
+    }
+
+    #[test]
+    pub fn test_parse_order_line() {
+        let line = OrderLine {
+            id: "line".to_string(),
+            items: vec![OrderArticle {
+                id: "s1001".to_string(),
+                price: 250,
+                decorators: vec![Decorator::Quantity { quantity: 2 }],
+                ..Default::default()
+            }],
+            display_price: 375,
+            price: 500,
+            decorators: vec![],
+        };
+
+        assert_eq!(
+            parse_picnic_order_line(line),
+            vec![WggOrderLine {
+                product_id: "s1001".to_string(),
+                quantity: 2,
+                original_price: 500,
+                display_price: 375,
+            }]
+        );
+
+        // The discount is spread over the articles by price, with the last article absorbing rounding errors.
+        let article = |id: &str, price| OrderArticle {
+            id: id.to_string(),
+            price,
+            decorators: vec![Decorator::Quantity { quantity: 1 }],
+            ..Default::default()
+        };
+        let line = OrderLine {
+            id: "line".to_string(),
+            items: vec![article("s1001", 300), article("s1002", 200), article("s1003", 100)],
+            display_price: 500,
+            price: 600,
+            decorators: vec![],
+        };
+        let display_prices = parse_picnic_order_line(line)
+            .into_iter()
+            .map(|line| line.display_price)
+            .collect::<Vec<_>>();
+        assert_eq!(display_prices, vec![250, 167, 83]);
+
+        // A discount larger than the line can't make the articles negative.
+        let line = OrderLine {
+            id: "line".to_string(),
+            items: vec![article("s1001", 100), article("s1002", 100)],
+            display_price: -50,
+            price: 200,
+            decorators: vec![],
+        };
+        assert!(parse_picnic_order_line(line).iter().all(|line| line.display_price == 0));
+    }
 
     #[test]
     pub fn test_parse_price() {
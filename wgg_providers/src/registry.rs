@@ -0,0 +1,196 @@
+//! The registry of all known providers.
+//!
+//! The built-in providers are always registered. Other crates can supply their own providers by passing a
+//! [ProviderRegistration] to [WggProviderBuilder::with_provider](crate::WggProviderBuilder::with_provider).
+//!
+//! Registered metadata never changes, registering a provider again with the same metadata does nothing, whereas
+//! registering it with different metadata is an error.
+use crate::error::{ProviderError, Result};
+use crate::models::{Provider, ProviderMetadata};
+use crate::providers::{AhBridge, JumboBridge, PicnicBridge, ProviderInfo, StaticProviderInfo};
+use crate::DynProvider;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+
+/// The static metadata of all known providers, initialised with the built-in providers.
+static REGISTRY: Lazy<Registry> = Lazy::new(Registry::with_built_in);
+
+type ProviderConstructor = Box<dyn FnOnce() -> BoxFuture<'static, Result<Arc<DynProvider>>> + Send>;
+
+/// A provider bridge supplied from outside of this crate, alongside the means to construct it.
+pub struct ProviderRegistration {
+    provider: Provider,
+    metadata: ProviderMetadata,
+    constructor: ProviderConstructor,
+}
+
+impl ProviderRegistration {
+    /// Register the bridge `P`, which will be created by `constructor` during
+    /// [WggProviderBuilder::build](crate::WggProviderBuilder::build).
+    ///
+    /// The id and metadata of the provider are taken from the [StaticProviderInfo] implementation of `P`.
+    pub fn new<P, F, Fut>(constructor: F) -> Self
+    where
+        P: ProviderInfo + Send + Sync,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<P>> + Send + 'static,
+    {
+        Self {
+            provider: <P as StaticProviderInfo>::provider(),
+            metadata: <P as StaticProviderInfo>::metadata(),
+            constructor: Box::new(move || {
+                async move {
+                    let bridge: Arc<DynProvider> = Arc::new(constructor().await?);
+                    Ok(bridge)
+                }
+                .boxed()
+            }),
+        }
+    }
+
+    /// The provider which will be created by this registration.
+    pub fn provider(&self) -> Provider {
+        self.provider
+    }
+
+    pub fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    pub(crate) async fn construct(self) -> Result<Arc<DynProvider>> {
+        (self.constructor)().await
+    }
+}
+
+/// Make the given provider, and its metadata, known.
+///
+/// Fails if the provider is already known with different metadata, e.g. when its id clashes with a built-in provider.
+pub fn register_provider(provider: Provider, metadata: ProviderMetadata) -> Result<()> {
+    REGISTRY.register(provider, metadata)
+}
+
+/// All known providers, regardless of whether they're active in a [WggProvider](crate::WggProvider).
+pub fn registered_providers() -> Vec<Provider> {
+    REGISTRY.providers()
+}
+
+/// Retrieve the metadata of the given provider, if it is known.
+pub fn provider_metadata(provider: Provider) -> Option<ProviderMetadata> {
+    REGISTRY.metadata(provider)
+}
+
+/// Whether the given provider is known.
+pub fn is_registered(provider: Provider) -> bool {
+    REGISTRY.metadata(provider).is_some()
+}
+
+/// Find the registered provider with the given id.
+///
+/// As every registered provider already has a `'static` id no new ids are ever allocated.
+pub(crate) fn find_registered(id: &str) -> Option<Provider> {
+    REGISTRY.find(id)
+}
+
+/// A collection of providers and their metadata, see [REGISTRY] for the one used by the functions in this module.
+struct Registry {
+    providers: RwLock<BTreeMap<Provider, ProviderMetadata>>,
+}
+
+impl Registry {
+    /// Create a registry which only knows the built-in providers.
+    fn with_built_in() -> Self {
+        let built_in = [
+            (Provider::PICNIC, <PicnicBridge as StaticProviderInfo>::metadata()),
+            (Provider::JUMBO, <JumboBridge as StaticProviderInfo>::metadata()),
+            (Provider::ALBERT_HEIJN, <AhBridge as StaticProviderInfo>::metadata()),
+        ];
+
+        Self {
+            providers: RwLock::new(built_in.into_iter().collect()),
+        }
+    }
+
+    fn register(&self, provider: Provider, metadata: ProviderMetadata) -> Result<()> {
+        let mut providers = self.providers.write().unwrap();
+
+        match providers.get(&provider) {
+            Some(existing) if *existing == metadata => Ok(()),
+            Some(_) => Err(ProviderError::InitialisationFailed(format!(
+                "Provider {provider} is already registered with different metadata"
+            ))),
+            None => {
+                providers.insert(provider, metadata);
+                Ok(())
+            }
+        }
+    }
+
+    fn providers(&self) -> Vec<Provider> {
+        self.providers.read().unwrap().keys().copied().collect()
+    }
+
+    fn metadata(&self, provider: Provider) -> Option<ProviderMetadata> {
+        self.providers.read().unwrap().get(&provider).cloned()
+    }
+
+    fn find(&self, id: &str) -> Option<Provider> {
+        self.providers
+            .read()
+            .unwrap()
+            .keys()
+            .find(|provider| provider.id() == id)
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Registry;
+    use crate::models::Provider;
+
+    #[test]
+    fn test_provider_ids() {
+        assert_eq!("JUMBO".parse::<Provider>().unwrap(), Provider::JUMBO);
+        assert!("NOT_A_STORE".parse::<Provider>().is_err());
+
+        assert_eq!(
+            serde_json::from_str::<Provider>(r#""AlbertHeijn""#).unwrap(),
+            Provider::ALBERT_HEIJN
+        );
+        assert!(serde_json::from_str::<Provider>(r#""NOT_A_STORE""#).is_err());
+    }
+
+    #[test]
+    fn test_registry() {
+        let registry = Registry::with_built_in();
+        let custom = Provider::from_static("CUSTOM_STORE");
+
+        assert_eq!(registry.find("JUMBO"), Some(Provider::JUMBO));
+        assert_eq!(registry.find("CUSTOM_STORE"), None);
+
+        registry.register(custom, Provider::JUMBO.get_metadata()).unwrap();
+
+        assert_eq!(registry.find("CUSTOM_STORE"), Some(custom));
+        assert!(registry.providers().contains(&custom));
+        assert_eq!(registry.metadata(custom), registry.metadata(Provider::JUMBO));
+    }
+
+    #[test]
+    fn test_duplicate_registration() {
+        let registry = Registry::with_built_in();
+        let jumbo = registry.metadata(Provider::JUMBO).unwrap();
+
+        // Registering the same metadata again, e.g. when building multiple providers, is a no-op.
+        registry.register(Provider::JUMBO, jumbo.clone()).unwrap();
+
+        let mut changed = jumbo.clone();
+        changed.supports_cart = !changed.supports_cart;
+
+        assert!(registry.register(Provider::JUMBO, changed).is_err());
+        assert_eq!(registry.metadata(Provider::JUMBO), Some(jumbo));
+    }
+}
@@ -42,6 +42,13 @@ pub struct SaleInfo {
     item_ids: Vec<ProductId>,
 }
 
+impl SaleInfo {
+    /// Whether the sale is valid at the given moment.
+    pub fn is_valid_at(&self, moment: DateTime<Utc>) -> bool {
+        self.valid_from <= moment && moment <= self.valid_until
+    }
+}
+
 impl SaleResolver {
     pub(crate) fn new(providers: Arc<DynamicProviders>, previous_cache: Option<PromotionsCache>) -> Self {
         let provider_keys = providers.keys().copied();
@@ -0,0 +1,293 @@
+use crate::error::{ProviderError, Result};
+use crate::models::sale_types::{
+    MinimumQuantity, NumEuroOff, NumEuroPrice, NumForPercentOff, NumForPrice, NumPercentOff, NumPlusNumFree,
+    NumthPercentOff, PricePerUnit, SaleType,
+};
+use crate::models::CentPrice;
+use crate::providers::common_bridge::{parse_int_fract_price, parse_stacking_tiers, parse_unit_component};
+use regex::{Captures, Regex, RegexBuilder, RegexSet, RegexSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::num::NonZeroU16;
+
+/// A table of rules used to derive a [SaleType] from a sale label like `1 + 1 GRATIS`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SaleLabelRules {
+    /// All rules, tried in order. The first rule which matches the label, and whose values are valid, is used.
+    #[serde(rename = "rule", default)]
+    pub rules: Vec<SaleLabelRule>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SaleLabelRule {
+    /// A case-insensitive regex, in which whitespace is ignored.
+    ///
+    /// The values required by the [SaleRuleKind] are taken from the named captures of this pattern.
+    pub pattern: String,
+    pub sale_type: SaleRuleKind,
+    /// Fixed values for any named captures which aren't part of the `pattern`.
+    ///
+    /// For example, `percent = "50"` for a `2e halve prijs` label.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub values: BTreeMap<String, String>,
+}
+
+/// The [SaleType] a [SaleLabelRule] constructs, and the named captures it expects.
+///
+/// Prices (the `price` capture) can be written as `4`, `4.50`, or `4,50`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SaleRuleKind {
+    /// [SaleType::NumPlusNumFree] from the `required` and `free` captures.
+    NumPlusNumFree,
+    /// [SaleType::NumPlusNumFree] from the `nth` capture, where the `nth` item is free.
+    NthFree,
+    /// [SaleType::NumPercentOff] from the `percent` capture.
+    NumPercentOff,
+    /// [SaleType::NumthPercentOff] from the `required` and `percent` captures.
+    NthPercentOff,
+    /// [SaleType::NumForPrice] from the `required` and `price` captures.
+    NumForPrice,
+    /// [SaleType::NumEuroOff] from the `price` capture.
+    NumEuroOff,
+    /// [SaleType::NumEuroPrice] from the `price` capture.
+    NumEuroPrice,
+    /// [SaleType::NumForPercentOff] from the `required` and `percent` captures.
+    NumForPercentOff,
+    /// [SaleType::StackingDiscount], the tiers are parsed from the full label.
+    StackingDiscount,
+    /// [SaleType::PricePerUnit] from the `unit` and `price` captures.
+    PricePerUnit,
+    /// [SaleType::MinimumQuantity] from the `required` capture.
+    MinimumQuantity,
+}
+
+impl SaleLabelRule {
+    fn new(pattern: &str, sale_type: SaleRuleKind) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            sale_type,
+            values: BTreeMap::new(),
+        }
+    }
+
+    fn with_value(mut self, name: &str, value: &str) -> Self {
+        self.values.insert(name.to_string(), value.to_string());
+        self
+    }
+}
+
+impl Default for SaleLabelRules {
+    fn default() -> Self {
+        //language=regexp
+        let rules = vec![
+            SaleLabelRule::new(r"stapelkorting", SaleRuleKind::StackingDiscount),
+            SaleLabelRule::new(
+                r"(?P<required>\d+) \s* voor \s* (?P<percent>\d+) \s* % \s* korting",
+                SaleRuleKind::NumForPercentOff,
+            ),
+            SaleLabelRule::new(r"vanaf \s* (?P<required>\d+) \s* stuks?", SaleRuleKind::MinimumQuantity),
+            SaleLabelRule::new(
+                r"per \s* (?P<unit>kilo|kg|liter|l|stuk) \s* €? \s* (?P<price>\d+(?:[,.]\d+)?)",
+                SaleRuleKind::PricePerUnit,
+            ),
+            SaleLabelRule::new(
+                r"(?P<required>\d+) \s* \+ \s* (?P<free>\d+) \s* gratis",
+                SaleRuleKind::NumPlusNumFree,
+            ),
+            SaleLabelRule::new(r"(?P<nth>\d+) \s* e \s* gratis", SaleRuleKind::NthFree),
+            SaleLabelRule::new(r"(?P<percent>\d+) \s* % \s* korting", SaleRuleKind::NumPercentOff),
+            SaleLabelRule::new(
+                r"(?P<required>\d+) \s* e \s* halve \s* prijs",
+                SaleRuleKind::NthPercentOff,
+            )
+            .with_value("percent", "50"),
+            SaleLabelRule::new(
+                r"(?P<required>\d+) \s* voor \s* €? \s* (?P<price>\d+(?:[,.]\d+)?)",
+                SaleRuleKind::NumForPrice,
+            ),
+            SaleLabelRule::new(
+                r"(?P<price>\d+(?:[,.]\d+)?) \s* euro \s* korting",
+                SaleRuleKind::NumEuroOff,
+            ),
+            SaleLabelRule::new(
+                r"€? \s* (?P<price>\d+(?:[,.]\d+)?) \s* korting",
+                SaleRuleKind::NumEuroOff,
+            ),
+            SaleLabelRule::new(r"NU \s* €(?P<price>\d+(?:[,.]\d+)?)", SaleRuleKind::NumEuroPrice),
+        ];
+
+        Self { rules }
+    }
+}
+
+/// The compiled form of [SaleLabelRules], shared by the [crate::WggProvider] and all its bridges.
+///
+/// The rules are fixed once the [crate::WggProvider] is built, changing them requires a restart.
+pub(crate) struct CompiledSaleRules {
+    set: RegexSet,
+    rules: Vec<CompiledSaleRule>,
+}
+
+struct CompiledSaleRule {
+    regex: Regex,
+    sale_type: SaleRuleKind,
+    values: BTreeMap<String, String>,
+}
+
+impl CompiledSaleRules {
+    pub(crate) fn new(rules: &SaleLabelRules) -> Result<Self> {
+        let set = RegexSetBuilder::new(rules.rules.iter().map(|rule| &rule.pattern))
+            .case_insensitive(true)
+            .ignore_whitespace(true)
+            .build()
+            .map_err(|e| ProviderError::InvalidSaleRule(e.to_string()))?;
+
+        let rules = rules
+            .rules
+            .iter()
+            .map(|rule| {
+                let regex = RegexBuilder::new(&rule.pattern)
+                    .case_insensitive(true)
+                    .ignore_whitespace(true)
+                    .build()
+                    .map_err(|e| ProviderError::InvalidSaleRule(e.to_string()))?;
+
+                Ok(CompiledSaleRule {
+                    regex,
+                    sale_type: rule.sale_type,
+                    values: rule.values.clone(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { set, rules })
+    }
+
+    /// Try to parse the given sale label into a proper [SaleType].
+    pub(crate) fn parse(&self, sale_label: &str) -> Option<SaleType> {
+        self.set
+            .matches(sale_label)
+            .into_iter()
+            .find_map(|idx| self.rules[idx].parse(sale_label))
+    }
+}
+
+impl Default for CompiledSaleRules {
+    fn default() -> Self {
+        Self::new(&SaleLabelRules::default()).expect("Default sale label rules are invalid")
+    }
+}
+
+impl CompiledSaleRule {
+    fn parse(&self, sale_label: &str) -> Option<SaleType> {
+        let capture = self.regex.captures(sale_label)?;
+        let value = |name: &str| self.value(&capture, name);
+
+        let result = match self.sale_type {
+            SaleRuleKind::NumPlusNumFree => SaleType::NumPlusNumFree(NumPlusNumFree {
+                required: value("required")?.parse().ok()?,
+                free: value("free")?.parse().ok()?,
+            }),
+            SaleRuleKind::NthFree => {
+                let nth: u16 = value("nth")?.parse().ok()?;
+
+                SaleType::NumPlusNumFree(NumPlusNumFree {
+                    required: nth.checked_sub(1)?.try_into().ok()?,
+                    free: NonZeroU16::new(1)?,
+                })
+            }
+            SaleRuleKind::NumPercentOff => {
+                let percent_off: NonZeroU16 = value("percent")?.parse().ok()?;
+
+                SaleType::NumPercentOff(NumPercentOff::new(percent_off)?)
+            }
+            SaleRuleKind::NthPercentOff => SaleType::NumthPercentOff(NumthPercentOff {
+                required: value("required")?.parse().ok()?,
+                last_percent_off: value("percent")?.parse().ok()?,
+            }),
+            SaleRuleKind::NumForPrice => SaleType::NumForPrice(NumForPrice {
+                required: value("required")?.parse().ok()?,
+                price: parse_price(value("price")?)?,
+            }),
+            SaleRuleKind::NumEuroOff => SaleType::NumEuroOff(NumEuroOff {
+                price_off: parse_price(value("price")?)?,
+            }),
+            SaleRuleKind::NumEuroPrice => SaleType::NumEuroPrice(NumEuroPrice {
+                price: parse_price(value("price")?)?,
+            }),
+            SaleRuleKind::NumForPercentOff => {
+                let required: NonZeroU16 = value("required")?.parse().ok()?;
+                let percent_off: NonZeroU16 = value("percent")?.parse().ok()?;
+
+                SaleType::NumForPercentOff(NumForPercentOff::new(required, percent_off)?)
+            }
+            SaleRuleKind::StackingDiscount => SaleType::StackingDiscount(parse_stacking_tiers(sale_label)),
+            SaleRuleKind::PricePerUnit => SaleType::PricePerUnit(PricePerUnit {
+                unit: parse_unit_component(&value("unit")?.to_lowercase())?,
+                price: parse_price(value("price")?)?,
+            }),
+            SaleRuleKind::MinimumQuantity => SaleType::MinimumQuantity(MinimumQuantity {
+                required: value("required")?.parse().ok()?,
+            }),
+        };
+
+        Some(result)
+    }
+
+    /// Get the value of the named capture, falling back to the rule's fixed values.
+    fn value<'a>(&'a self, capture: &Captures<'a>, name: &str) -> Option<&'a str> {
+        capture
+            .name(name)
+            .map(|capture| capture.as_str())
+            .or_else(|| self.values.get(name).map(String::as_str))
+    }
+}
+
+/// Parse a price of the form `4`, `4.50`, or `4,50`.
+fn parse_price(price: &str) -> Option<CentPrice> {
+    let (integer_part, fractional_part) = price.split_once([',', '.']).unwrap_or((price, "0"));
+
+    Some(parse_int_fract_price(
+        integer_part.parse().ok()?,
+        fractional_part.parse().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_rules() {
+        let rules = SaleLabelRules {
+            rules: vec![
+                SaleLabelRule::new(r"(?P<nth>\d+) \s* e \s* voor \s* niks", SaleRuleKind::NthFree),
+                SaleLabelRule::new(r"halve \s* prijs", SaleRuleKind::NumPercentOff).with_value("percent", "50"),
+            ],
+        };
+        let compiled = CompiledSaleRules::new(&rules).unwrap();
+
+        assert_eq!(
+            compiled.parse("3e voor niks"),
+            Some(SaleType::NumPlusNumFree(NumPlusNumFree {
+                required: NonZeroU16::new(2).unwrap(),
+                free: NonZeroU16::new(1).unwrap(),
+            }))
+        );
+        assert_eq!(
+            compiled.parse("Halve prijs"),
+            Some(SaleType::NumPercentOff(NumPercentOff::new(50).unwrap()))
+        );
+        assert_eq!(compiled.parse("1 + 1 gratis"), None);
+    }
+
+    #[test]
+    fn test_invalid_rule() {
+        let rules = SaleLabelRules {
+            rules: vec![SaleLabelRule::new(r"(?P<price>\d+", SaleRuleKind::NumEuroPrice)],
+        };
+
+        assert!(CompiledSaleRules::new(&rules).is_err());
+    }
+}
@@ -1,32 +1,45 @@
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::num::{NonZeroU32, NonZeroUsize};
 use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
 
-use async_graphql::EnumType;
-
-use wgg_jumbo::BaseJumboApi;
+use wgg_ah::BaseAhApi;
 use wgg_picnic::credentials::CredentialsCache;
 use wgg_scheduler::JobScheduler;
 
+use crate::account::AccountCart;
 use crate::caching::SerdeCache;
 use crate::caching::WggProviderCache;
 use crate::error::{ProviderError, Result};
+use crate::matching::ProductMatch;
+use crate::models::sale_types::SaleType;
 use crate::models::{
-    ProductIdRef, Provider, SublistId, WggAutocomplete, WggProduct, WggSaleCategory, WggSaleGroupComplete, WggSaleItem,
-    WggSearchProduct,
+    gtin_key, GtinRef, MultiProviderResult, PriceObservation, ProductIdRef, Provider, ProviderOutcome, SublistId,
+    WggAutocomplete, WggCartItem, WggCategory, WggCheckoutStatus, WggDeliverySlot, WggOrder, WggOrderSummary,
+    WggProduct, WggRemoteTally, WggSaleCategory, WggSaleGroupComplete, WggSaleItem, WggSearchProduct,
 };
-use crate::pagination::OffsetPagination;
-use crate::providers::{JumboBridge, PicnicBridge};
-use crate::providers::{PicnicCredentials, ProviderCart};
+use crate::pagination::{MultiProviderCursor, MultiProviderPagination, OffsetPagination};
+use crate::providers::{AhBridge, JumboBridge, PicnicBridge};
+use crate::providers::{JumboCredentials, PicnicCredentials, ProviderCart, ProviderDelivery};
+use crate::registry::ProviderRegistration;
 use crate::sale_resolver::{SaleInfo, SaleResolver};
-use crate::{DynProvider, DynamicProviders};
+use crate::sale_rules::{CompiledSaleRules, SaleLabelRules};
+use crate::{DynProvider, DynamicProviders, PicnicLoginStatus};
+
+/// The sending half for [PriceObservation]s, see [WggProviderBuilder::with_price_observer].
+pub type PriceObserver = tokio::sync::mpsc::Sender<PriceObservation>;
 
 pub struct WggProvider {
     pub(crate) dyn_providers: Arc<DynamicProviders>,
     pub(crate) cache: WggProviderCache,
     pub(crate) sales: SaleResolver,
+    pub(crate) price_observer: Option<PriceObserver>,
+    /// The rate limit for newly created Picnic accounts, see [Self::picnic_account_cart].
+    pub(crate) picnic_rps: Option<NonZeroU32>,
+    /// The rules shared with all bridges, including those of newly created Picnic accounts.
+    pub(crate) sale_rules: Arc<CompiledSaleRules>,
 }
 
 impl WggProvider {
@@ -100,48 +113,222 @@ impl WggProvider {
             self.cache.insert_search_product(provider, item.clone());
         }
 
+        self.observe_prices(result.items.iter().map(PriceObservation::from));
+
         Ok(result)
     }
 
-    /// Search all providers for the given query.
+    /// Search all providers for the given query, continuing from the given `cursor`.
     ///
-    /// The [OffsetPagination] will have no `offset` listed, but the `total_items` will be the sum of all APIs' total items.
+    /// The results of all providers are interleaved by their relevance to the query, returning at most `limit` items.
+    /// Pass the returned [MultiProviderPagination::next] cursor to retrieve the next page.
+    /// Providers which fail to respond are left out, and will be tried again on the next page. Their failure is listed
+    /// in [MultiProviderPagination::outcomes].
     #[tracing::instrument(level="debug", skip_all, fields(query = query.as_ref()))]
-    pub async fn search_all(&self, query: impl AsRef<str>) -> Result<OffsetPagination<WggSearchProduct>> {
+    pub async fn search_all(
+        &self,
+        query: impl AsRef<str>,
+        cursor: Option<&MultiProviderCursor>,
+        limit: usize,
+    ) -> MultiProviderPagination<WggSearchProduct> {
         #[cached::proc_macro::cached(
             size = 100,
             time = 86400,
             result = true,
-            key = "(String, Provider)",
-            convert = r#"{(query.to_string(), _provider)}"#
+            key = "(String, u32, Provider)",
+            convert = r#"{(query.to_string(), offset, _provider)}"#
         )]
         async fn inner(
             prov: &DynProvider,
             query: &str,
+            offset: u32,
             _provider: Provider,
         ) -> Result<OffsetPagination<WggSearchProduct>> {
-            prov.search(query, None).await
+            prov.search(query, Some(offset)).await
         }
 
-        let queries = self.active_providers().map(|i| inner(i, query.as_ref(), i.provider()));
+        /// The not yet returned results of a single provider.
+        struct ProviderResults {
+            provider: Provider,
+            offset: u32,
+            total_items: usize,
+            /// The remaining results of the fetched page, with their relevance.
+            items: VecDeque<(f64, WggSearchProduct)>,
+            consumed: u32,
+        }
 
-        let results = futures::future::join_all(queries)
-            .await
-            .into_iter()
-            .flatten()
-            .reduce(|mut accum, mut item| {
-                accum.items.append(&mut item.items);
-                accum.total_items += item.total_items;
-                accum
-            })
-            .ok_or(ProviderError::NothingFound)?;
+        let query = query.as_ref();
+        let previous = cursor.cloned().unwrap_or_default();
+
+        let queries = self
+            .active_providers()
+            .filter(|prov| !previous.is_exhausted(prov.provider()))
+            .map(|prov| {
+                let offset = previous.offset(prov.provider());
+                async move {
+                    let response = inner(prov, query, offset, prov.provider()).await;
+                    (prov.provider(), offset, response)
+                }
+            });
+        let responses = futures::future::join_all(queries).await;
+
+        let mut total_items = previous.exhausted_items();
+        let mut results = Vec::with_capacity(responses.len());
+        let mut outcomes = Vec::with_capacity(responses.len());
+
+        for (provider, offset, response) in responses {
+            let page = match response {
+                Ok(page) => page,
+                Err(e) => {
+                    tracing::debug!(%provider, error = ?e, "Failed to search provider");
+                    outcomes.push(ProviderOutcome::failed(provider, &e));
+                    continue;
+                }
+            };
+
+            // We persist any and all products for the sake of easing custom list searches.
+            for item in &page.items {
+                self.cache.insert_search_product(provider, item.clone());
+            }
+
+            self.observe_prices(page.items.iter().map(PriceObservation::from));
+
+            // Page based APIs can return results from before the requested offset.
+            let skip = offset.saturating_sub(page.offset) as usize;
+            let items: VecDeque<_> = page
+                .items
+                .into_iter()
+                .skip(skip)
+                .enumerate()
+                .map(|(i, item)| (crate::matching::relevance(query, &item, offset as usize + i), item))
+                .collect();
+            // An empty page means the provider has nothing more to give, regardless of its reported total.
+            let provider_total = if items.is_empty() {
+                if page.total_items > offset as usize {
+                    outcomes.push(ProviderOutcome::degraded(
+                        provider,
+                        format!("Returned {offset} of the {} reported results", page.total_items),
+                    ));
+                } else {
+                    outcomes.push(ProviderOutcome::ok(provider));
+                }
+
+                offset as usize
+            } else {
+                outcomes.push(ProviderOutcome::ok(provider));
+                page.total_items
+            };
+
+            total_items += provider_total;
+            results.push(ProviderResults {
+                provider,
+                offset,
+                total_items: provider_total,
+                items,
+                consumed: 0,
+            });
+        }
+
+        let mut items = Vec::with_capacity(limit);
+
+        while items.len() < limit {
+            // A provider which ran out of results on this page could have more relevant results on its next page.
+            let awaiting_next_page = results
+                .iter()
+                .any(|res| res.items.is_empty() && ((res.offset + res.consumed) as usize) < res.total_items);
+
+            if awaiting_next_page {
+                break;
+            }
+
+            let Some(best) = results
+                .iter_mut()
+                .filter(|res| !res.items.is_empty())
+                .max_by(|a, b| a.items[0].0.total_cmp(&b.items[0].0))
+            else {
+                break;
+            };
+
+            if let Some((_, item)) = best.items.pop_front() {
+                best.consumed += 1;
+                items.push(item);
+            }
+        }
+
+        let mut next = previous;
+
+        for res in &results {
+            next.advance(res.provider, res.offset + res.consumed, res.total_items);
+        }
+
+        let all_exhausted = self.active_providers().all(|prov| next.is_exhausted(prov.provider()));
+
+        MultiProviderPagination {
+            items,
+            total_items,
+            next: (!all_exhausted).then_some(next),
+            outcomes,
+        }
+    }
+
+    /// Retrieve the category tree of the catalogue of the given [Provider].
+    ///
+    /// See [Self::category_products] for the products in a category.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn categories(&self, provider: Provider) -> Result<Vec<WggCategory>> {
+        #[cached::proc_macro::cached(
+            size = 10,
+            time = 86400,
+            result = true,
+            key = "Provider",
+            convert = r#"{_provider}"#
+        )]
+        async fn inner(prov: &DynProvider, _provider: Provider) -> Result<Vec<WggCategory>> {
+            prov.categories().await
+        }
+
+        let provider_concrete = self.dyn_providers.find_provider(provider)?;
+
+        inner(provider_concrete, provider).await
+    }
+
+    /// Retrieve the products in the given category of the [Provider], including those of its sub-categories.
+    /// `offset` will always be respected, even if the underlying API does not support it.
+    #[tracing::instrument(level = "debug", skip(self, category_id), fields(category_id = category_id.as_ref()))]
+    pub async fn category_products(
+        &self,
+        provider: Provider,
+        category_id: impl AsRef<str>,
+        offset: Option<u32>,
+    ) -> Result<OffsetPagination<WggSearchProduct>> {
+        #[cached::proc_macro::cached(
+            size = 100,
+            time = 86400,
+            result = true,
+            key = "(String, Option<u32>, Provider)",
+            convert = r#"{(category_id.to_string(), offset, _provider)}"#
+        )]
+        async fn inner(
+            prov: &DynProvider,
+            category_id: &str,
+            offset: Option<u32>,
+            _provider: Provider,
+        ) -> Result<OffsetPagination<WggSearchProduct>> {
+            prov.category_products(category_id, offset).await
+        }
+
+        let provider_concrete = self.dyn_providers.find_provider(provider)?;
+
+        let result = inner(provider_concrete, category_id.as_ref(), offset, provider).await?;
 
         // We persist any and all products for the sake of easing custom list searches.
-        for item in &results.items {
-            self.cache.insert_search_product(item.provider, item.clone());
+        for item in &result.items {
+            self.cache.insert_search_product(provider, item.clone());
         }
 
-        Ok(results)
+        self.observe_prices(result.items.iter().map(PriceObservation::from));
+
+        Ok(result)
     }
 
     /// Retrieve all valid promotions for the current week for the given provider.
@@ -158,23 +345,52 @@ impl WggProvider {
             }
         }
 
+        self.observe_prices(
+            result
+                .iter()
+                .flat_map(|category| &category.items)
+                .flat_map(|item| match item {
+                    WggSaleItem::Product(product) => Some(PriceObservation::from(product)),
+                    WggSaleItem::Group(_) => None,
+                }),
+        );
+
         Ok(result)
     }
 
-    /// Retrieve all valid promotions for the current week.
+    /// Retrieve all valid promotions for the current week from all providers.
+    ///
+    /// Providers which fail to respond are left out, their failure is listed in [MultiProviderResult::outcomes].
     #[tracing::instrument(level = "debug", skip_all)]
-    pub async fn promotions_all(&self) -> Result<Vec<WggSaleCategory>> {
-        let provider = self.active_providers().map(|i| self.promotions(i.provider()));
+    pub async fn promotions_all(&self) -> MultiProviderResult<WggSaleCategory> {
+        let providers = self.active_providers().map(|i| async move {
+            let provider = i.provider();
+            (provider, self.promotions(provider).await)
+        });
+
+        let mut items = Vec::new();
+        let mut outcomes = Vec::new();
+
+        for (provider, response) in futures::future::join_all(providers).await {
+            match response {
+                Ok(mut promotions) => {
+                    // Every provider has weekly promotions, an empty response is more likely a silent failure.
+                    if promotions.is_empty() {
+                        outcomes.push(ProviderOutcome::degraded(provider, "No promotions were returned"));
+                    } else {
+                        outcomes.push(ProviderOutcome::ok(provider));
+                    }
+
+                    items.append(&mut promotions);
+                }
+                Err(e) => {
+                    tracing::debug!(%provider, error = ?e, "Failed to retrieve promotions");
+                    outcomes.push(ProviderOutcome::failed(provider, &e));
+                }
+            }
+        }
 
-        futures::future::join_all(provider)
-            .await
-            .into_iter()
-            .flatten()
-            .reduce(|mut accum, mut item| {
-                accum.append(&mut item);
-                accum
-            })
-            .ok_or(ProviderError::NothingFound)
+        MultiProviderResult { items, outcomes }
     }
 
     /// Retrieve all products that are part of the given promotion sub-list.
@@ -191,6 +407,8 @@ impl WggProvider {
             self.cache.insert_search_product(item.provider, item.clone());
         }
 
+        self.observe_prices(result.items.iter().map(PriceObservation::from));
+
         Ok(result)
     }
 
@@ -199,11 +417,15 @@ impl WggProvider {
     /// Note that this `product_id` needs to be obtained from this specific `provider`. Product ids do not cross provider boundaries.
     #[tracing::instrument(level="debug", skip_all, fields(provider, query = product_id.as_ref()))]
     pub async fn product(&self, provider: Provider, product_id: impl AsRef<str>) -> Result<WggProduct> {
-        if let Some(item) = self.cache.get_product(provider, product_id.as_ref()) {
-            Ok(item)
+        let result = if let Some(item) = self.cache.get_product(provider, product_id.as_ref()) {
+            item
         } else {
-            self.product_network(provider, product_id.as_ref()).await
-        }
+            self.product_network(provider, product_id.as_ref()).await?
+        };
+
+        self.observe_prices(std::iter::once(PriceObservation::from(&result)));
+
+        Ok(result)
     }
 
     /// Retrieve the search product representation of the requested product.
@@ -247,6 +469,84 @@ impl WggProvider {
             .await
     }
 
+    /// Find the product with the given barcode at every active provider.
+    ///
+    /// Providers which don't sell the product, or fail to respond, are skipped.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn products_by_gtin(&self, gtin: &GtinRef) -> Result<Vec<WggSearchProduct>> {
+        let lookups = self.active_providers().map(|i| async move {
+            let provider = i.provider();
+            (provider, self.product_by_gtin(provider, gtin).await)
+        });
+
+        let mut result = Vec::new();
+
+        for (provider, lookup) in futures::future::join_all(lookups).await {
+            match lookup {
+                Ok(found) => result.extend(found),
+                Err(e) => tracing::debug!(%provider, error = ?e, "Failed to look up barcode"),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Find the product with the given barcode at `provider`.
+    ///
+    /// The search of every provider also matches barcodes.
+    /// Not every provider lists barcodes in its search results though, in that case the best result is verified against
+    /// its full product.
+    async fn product_by_gtin(&self, provider: Provider, gtin: &GtinRef) -> Result<Option<WggSearchProduct>> {
+        if let Some(item) = self.cache.get_search_product_by_gtin(provider, gtin) {
+            return Ok(Some(item));
+        }
+
+        let found = self.search(provider, gtin, None).await?;
+
+        if let Some(item) = self.cache.get_search_product_by_gtin(provider, gtin) {
+            return Ok(Some(item));
+        }
+
+        let Some(best) = found.items.into_iter().next().filter(|item| item.gtin.is_none()) else {
+            return Ok(None);
+        };
+        let product = self.product(provider, &best.id).await?;
+        let matches = product
+            .gtin
+            .as_deref()
+            .is_some_and(|product_gtin| gtin_key(product_gtin) == gtin_key(gtin));
+
+        Ok(matches.then(|| product.into()))
+    }
+
+    /// Find products at all other active providers which are likely equivalent to the given `product`.
+    ///
+    /// Up to three matches are returned per provider, best match first.
+    /// Providers which fail to respond are skipped.
+    #[tracing::instrument(level = "debug", skip_all, fields(provider = %product.provider, product_id = %product.id))]
+    pub async fn equivalents(&self, product: &WggSearchProduct) -> Result<Vec<ProductMatch>> {
+        let query = &crate::matching::search_query(&product.name);
+
+        let searches = self
+            .active_providers()
+            .map(|i| i.provider())
+            .filter(|provider| *provider != product.provider)
+            .map(|provider| async move { (provider, self.search(provider, query, None).await) });
+
+        let mut result = Vec::new();
+
+        for (provider, search) in futures::future::join_all(searches).await {
+            match search {
+                Ok(found) => result.extend(crate::matching::best_matches(product, found.items)),
+                Err(e) => tracing::debug!(%provider, error = ?e, "Failed to search for equivalents"),
+            }
+        }
+
+        result.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        Ok(result)
+    }
+
     /// Retrieve the associated sale for this item.
     pub fn product_sale_association(
         &self,
@@ -263,6 +563,12 @@ impl WggProvider {
         self.sales.get_sale_sublist_id(provider, product_id.as_ref())
     }
 
+    /// Try to derive a [SaleType] from the given sale label, using the [SaleLabelRules] these providers were built
+    /// with.
+    pub fn parse_sale_label(&self, sale_label: &str) -> Option<SaleType> {
+        self.sale_rules.parse(sale_label)
+    }
+
     /// Add the given item(s) and the quantity thereof to the current cart.
     pub async fn add_to_cart(&self, provider: Provider, items: &[(&ProductIdRef, u32)]) -> Result<()> {
         let cart_provider = self.cart_provider(provider)?;
@@ -284,6 +590,106 @@ impl WggProvider {
         cart_provider.clear_cart().await
     }
 
+    /// Retrieve the contents of the current remote cart.
+    pub async fn cart_contents(&self, provider: Provider) -> Result<Vec<WggCartItem>> {
+        let cart_provider = self.cart_provider(provider)?;
+
+        cart_provider.cart_contents().await
+    }
+
+    /// Retrieve the tally of the current remote cart as calculated by the provider, including the progress of the given
+    /// sales.
+    pub async fn cart_tally(&self, provider: Provider, sale_ids: &[&str]) -> Result<WggRemoteTally> {
+        let cart_provider = self.cart_provider(provider)?;
+
+        cart_provider.cart_tally(sale_ids).await
+    }
+
+    /// Retrieve all delivery slots for the current remote cart.
+    pub async fn delivery_slots(&self, provider: Provider) -> Result<Vec<WggDeliverySlot>> {
+        let delivery_provider = self.delivery_provider(provider)?;
+
+        delivery_provider.delivery_slots().await
+    }
+
+    /// Select the delivery slot with the given id for the current remote cart.
+    pub async fn select_delivery_slot(&self, provider: Provider, slot_id: &str) -> Result<()> {
+        let delivery_provider = self.delivery_provider(provider)?;
+
+        delivery_provider.select_delivery_slot(slot_id).await
+    }
+
+    /// Retrieve the status of the checkout of the current remote cart.
+    pub async fn checkout_status(&self, provider: Provider) -> Result<WggCheckoutStatus> {
+        let delivery_provider = self.delivery_provider(provider)?;
+
+        delivery_provider.checkout_status().await
+    }
+
+    /// Retrieve all orders which have been delivered, most recent first.
+    pub async fn delivered_orders(&self, provider: Provider) -> Result<Vec<WggOrderSummary>> {
+        let delivery_provider = self.delivery_provider(provider)?;
+
+        delivery_provider.delivered_orders().await
+    }
+
+    /// Retrieve the full contents of the delivered order with the given id.
+    pub async fn delivered_order(&self, provider: Provider, order_id: &str) -> Result<WggOrder> {
+        let delivery_provider = self.delivery_provider(provider)?;
+
+        delivery_provider.delivered_order(order_id).await
+    }
+
+    /// The state of the login of the Picnic provider.
+    ///
+    /// Whilst a login is awaiting its second factor code all Picnic requests fail, see
+    /// [Self::picnic_submit_second_factor].
+    pub async fn picnic_login_status(&self) -> Result<PicnicLoginStatus> {
+        self.picnic_bridge()?.login_status().await
+    }
+
+    /// Submit the second factor (SMS) code of a Picnic login, after which Picnic requests can succeed again.
+    pub async fn picnic_submit_second_factor(&self, code: &str) -> Result<()> {
+        self.picnic_bridge()?.submit_second_factor(code).await
+    }
+
+    /// Discard any pending Picnic login and log in again, sending a new second factor code if one is required.
+    ///
+    /// Required once the second factor code of a login expired.
+    pub async fn picnic_restart_login(&self) -> Result<PicnicLoginStatus> {
+        self.picnic_bridge()?.restart_login().await
+    }
+
+    /// Create a cart for the given Picnic account, rather than the account these providers were built with.
+    ///
+    /// No network requests are made until the first cart operation, at which point the account will log in if `cache`
+    /// doesn't contain valid credentials.
+    pub async fn picnic_account_cart<C: CredentialsCache>(
+        &self,
+        credentials: PicnicCredentials,
+        cache: C,
+    ) -> Result<AccountCart> {
+        let bridge = PicnicBridge::new(credentials, cache, self.picnic_rps, self.sale_rules.clone()).await?;
+
+        Ok(AccountCart::new(bridge))
+    }
+
+    /// Pass the given observations to the price observer, if there is one.
+    ///
+    /// Observations are dropped if the observer can't keep up, as price history is not worth slowing down requests.
+    fn observe_prices(&self, observations: impl IntoIterator<Item = PriceObservation>) {
+        let Some(observer) = &self.price_observer else {
+            return;
+        };
+
+        for observation in observations {
+            if let Err(e) = observer.try_send(observation) {
+                tracing::debug!(error = %e, "Dropping price observations");
+                break;
+            }
+        }
+    }
+
     /// Push all jobs relevant for optimal service of [WggProvider]s onto the given scheduler.
     ///
     /// These services are *not* mandatory for this to work, but they *are* mandatory for things to stay up-to-date.
@@ -291,6 +697,14 @@ impl WggProvider {
         crate::scheduled_jobs::schedule_all_jobs(scheduler, self)
     }
 
+    /// The current health of all active providers, in arbitrary order.
+    ///
+    /// A provider is [OutcomeStatus::Degraded](crate::models::OutcomeStatus::Degraded) when its requests are known to
+    /// fail, such as Picnic whilst its login waits for a second factor code.
+    pub async fn provider_statuses(&self) -> Vec<ProviderOutcome> {
+        futures::future::join_all(self.active_providers().map(|prov| prov.status())).await
+    }
+
     /// Iterate over all providers in arbitrary order, allowing an action to be performed on all of them
     pub fn active_providers(&self) -> ProvidersIter<'_> {
         ProvidersIter {
@@ -316,6 +730,27 @@ impl WggProvider {
         Ok(result)
     }
 
+    fn picnic_bridge(&self) -> Result<&PicnicBridge> {
+        self.dyn_providers
+            .find_provider(Provider::PICNIC)?
+            .as_any()
+            .downcast_ref::<PicnicBridge>()
+            .ok_or_else(|| {
+                ProviderError::OperationUnsupported("Picnic is not provided by the built-in bridge".to_string())
+            })
+    }
+
+    fn delivery_provider(&self, provider: Provider) -> Result<&(dyn ProviderDelivery + Send + Sync)> {
+        self.dyn_providers
+            .find_provider(provider)?
+            .as_delivery_provider()
+            .ok_or_else(|| {
+                ProviderError::OperationUnsupported(
+                    "Delivery operations are not supported on the given provider".to_string(),
+                )
+            })
+    }
+
     fn cart_provider(&self, provider: Provider) -> Result<&(dyn ProviderCart + Send + Sync)> {
         self.dyn_providers
             .find_provider(provider)?
@@ -345,8 +780,13 @@ pub struct WggProviderBuilder<T = ()> {
     picnic_creds: Option<PicnicCredentials>,
     picnic_creds_cache: Option<T>,
     picnic_rps: Option<NonZeroU32>,
-    jumbo: Option<BaseJumboApi>,
+    jumbo: Option<wgg_jumbo::Config>,
+    jumbo_creds: Option<JumboCredentials>,
+    ah: Option<BaseAhApi>,
     cache: Option<SerdeCache>,
+    sale_rules: Option<SaleLabelRules>,
+    price_observer: Option<PriceObserver>,
+    registrations: Vec<ProviderRegistration>,
     startup_validation: bool,
 }
 
@@ -358,7 +798,12 @@ impl<T: CredentialsCache> WggProviderBuilder<T> {
             picnic_creds_cache: None,
             picnic_rps: None,
             jumbo: None,
+            jumbo_creds: None,
+            ah: None,
             cache: None,
+            sale_rules: None,
+            price_observer: None,
+            registrations: Vec::new(),
             startup_validation: false,
         }
     }
@@ -385,7 +830,23 @@ impl<T: CredentialsCache> WggProviderBuilder<T> {
     ///
     /// Even if this is not called the Jumbo service is still available.
     pub fn with_jumbo(mut self, config: wgg_jumbo::Config) -> Self {
-        self.jumbo = Some(BaseJumboApi::new(config));
+        self.jumbo = Some(config);
+        self
+    }
+
+    /// Log in to the Jumbo API, which enables cart operations for `Jumbo`.
+    ///
+    /// Without credentials the Jumbo service is still available, but only for unauthenticated routes.
+    pub fn with_jumbo_credentials(mut self, jumbo_credentials: JumboCredentials) -> Self {
+        self.jumbo_creds = Some(jumbo_credentials);
+        self
+    }
+
+    /// Provide a non-standard Albert Heijn config.
+    ///
+    /// Even if this is not called the Albert Heijn service is still available.
+    pub fn with_ah(mut self, config: wgg_ah::Config) -> Self {
+        self.ah = Some(BaseAhApi::new(config));
         self
     }
 
@@ -395,6 +856,31 @@ impl<T: CredentialsCache> WggProviderBuilder<T> {
         self
     }
 
+    /// Provide non-default rules for deriving a [SaleType] from a sale label.
+    ///
+    /// If this isn't called the built-in [SaleLabelRules::default] are used.
+    /// The rules are fixed for the lifetime of the built [WggProvider], changing them requires building a new one.
+    pub fn with_sale_label_rules(mut self, rules: SaleLabelRules) -> Self {
+        self.sale_rules = Some(rules);
+        self
+    }
+
+    /// Receive a [PriceObservation] for every product returned by [WggProvider::search], [WggProvider::product], and
+    /// [WggProvider::promotions] (and their variants).
+    pub fn with_price_observer(mut self, observer: PriceObserver) -> Self {
+        self.price_observer = Some(observer);
+        self
+    }
+
+    /// Add a provider which isn't built into this crate.
+    ///
+    /// The provider is registered and constructed during [Self::build], which fails should its id already be registered
+    /// with different metadata (e.g, a built-in provider).
+    pub fn with_provider(mut self, registration: ProviderRegistration) -> Self {
+        self.registrations.push(registration);
+        self
+    }
+
     /// Whether to launch an asynchronous fetching of sale/promotion data as soon as the [WggProvider] is constructed.
     pub fn with_startup_sale_validation(mut self, startup_validation: bool) -> Self {
         self.startup_validation = startup_validation;
@@ -403,28 +889,57 @@ impl<T: CredentialsCache> WggProviderBuilder<T> {
 
     /// Create a new collection of providers.
     ///
-    /// By default only the `JumboApi` is enabled, see [Self::with_picnic] to enable `Picnic`.
+    /// By default only the `JumboApi` and `AhApi` are enabled, see [Self::with_picnic] to enable `Picnic`.
     #[tracing::instrument(level = "info", skip_all)]
     pub async fn build(self) -> Result<WggProvider> {
+        let sale_rules = Arc::new(match &self.sale_rules {
+            Some(rules) => CompiledSaleRules::new(rules)?,
+            None => CompiledSaleRules::default(),
+        });
+
         // ** Create the providers **
         let mut dyn_providers: DynamicProviders = DynamicProviders::new();
 
         // Picnic
+        let picnic_rps = self.picnic_rps.or(crate::providers::PICNIC_RECOMMENDED_RPS);
         if let (Some(credentials), Some(cache)) = (self.picnic_creds, self.picnic_creds_cache) {
-            let rps = self.picnic_rps.or(crate::providers::PICNIC_RECOMMENDED_RPS);
-            let picnic = Arc::new(PicnicBridge::new(credentials, cache, rps).await?);
-            dyn_providers.insert(Provider::Picnic, picnic);
+            let picnic = Arc::new(PicnicBridge::new(credentials, cache, picnic_rps, sale_rules.clone()).await?);
+            dyn_providers.insert(Provider::PICNIC, picnic);
         }
 
         // Jumbo
-        let base_api = self.jumbo.unwrap_or_else(|| BaseJumboApi::new(Default::default()));
-        let jumbo = Arc::new(JumboBridge::new(base_api));
-        dyn_providers.insert(Provider::Jumbo, jumbo);
+        let jumbo_config = self.jumbo.unwrap_or_default();
+        let jumbo = if let Some(credentials) = self.jumbo_creds {
+            Arc::new(JumboBridge::new_authenticated(
+                jumbo_config,
+                credentials,
+                sale_rules.clone(),
+            ))
+        } else {
+            Arc::new(JumboBridge::new(
+                wgg_jumbo::BaseJumboApi::new(jumbo_config),
+                sale_rules.clone(),
+            ))
+        };
+        dyn_providers.insert(Provider::JUMBO, jumbo);
+
+        // Albert Heijn
+        let base_api = self.ah.unwrap_or_else(|| BaseAhApi::new(Default::default()));
+        let ah = Arc::new(AhBridge::new(base_api, sale_rules.clone()));
+        dyn_providers.insert(Provider::ALBERT_HEIJN, ah);
+
+        // Externally supplied
+        for registration in self.registrations {
+            let provider = registration.provider();
+            crate::registry::register_provider(provider, registration.metadata().clone())?;
+            tracing::debug!(%provider, "Constructing registered provider");
+            dyn_providers.insert(provider, registration.construct().await?);
+        }
 
         let dyn_providers = Arc::new(dyn_providers);
 
         // ** Caches **
-        let providers = Provider::items().iter().map(|i| i.value);
+        let providers = crate::registry::registered_providers().into_iter();
         let (sales_cache, product_cache) = if let Some(cache) = self.cache {
             (Some(cache.promotions_cache), Some(cache.product_cache))
         } else {
@@ -443,6 +958,9 @@ impl<T: CredentialsCache> WggProviderBuilder<T> {
             dyn_providers,
             cache: product_cache,
             sales,
+            price_observer: self.price_observer,
+            picnic_rps,
+            sale_rules,
         };
 
         if self.startup_validation {